@@ -56,10 +56,59 @@ pub struct Cli {
     #[arg(short, long)]
     human_readable: bool,
 
+    /// Do not descend into directories on other file systems when recursing
+    #[arg(short = 'x', long)]
+    pub one_file_system: bool,
+
     /// Recurse into directories and present the result as a tree
     #[arg(long)]
     pub tree: bool,
 
+    /// Present a tree of cumulative directory sizes, largest first, with proportional bars
+    #[arg(long, conflicts_with_all = ["tree", "recursive"])]
+    pub du: bool,
+
+    /// List the sets of files with identical content below the inputs, and the space they waste
+    #[arg(long, conflicts_with_all = ["tree", "du"])]
+    pub duplicates: bool,
+
+    /// Compare two trees, marking the entries added, removed, modified or changed in type
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with_all = ["du", "duplicates"])]
+    pub diff: Option<Vec<PathBuf>>,
+
+    /// With --diff and snapshots, compare the content of files of the same size instead of their mtime
+    #[arg(long)]
+    pub checksum: bool,
+
+    /// Keep the listing open, redrawing it and highlighting what changed as files change
+    #[arg(long, conflicts_with_all = ["du", "diff", "duplicates", "snapshot", "since_snapshot", "output"])]
+    pub watch: bool,
+
+    /// Browse the inputs as a tree on the terminal, and print the chosen paths on exit (no short
+    /// form, -i being --inode)
+    #[arg(long, conflicts_with_all = ["du", "diff", "duplicates", "snapshot", "since_snapshot", "output", "watch"])]
+    pub interactive: bool,
+
+    /// List the tree of a git revision (commit, tag or branch) at the inputs instead of the working tree
+    #[arg(long, value_name = "REV", conflicts_with_all = ["diff", "duplicates", "snapshot", "since_snapshot", "watch", "interactive"])]
+    pub rev: Option<String>,
+
+    /// With --rev, mark each entry with how the working tree differs from the revision
+    #[arg(long, requires = "rev")]
+    pub rev_diff: bool,
+
+    /// Save an index of the walk (path, size, mtime, mode, inode, and digest with --checksum) to FILE
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["diff", "duplicates", "du"])]
+    pub snapshot: Option<PathBuf>,
+
+    /// Show the tree marked with what changed since the snapshot saved to FILE
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["diff", "duplicates", "du"])]
+    pub since_snapshot: Option<PathBuf>,
+
+    /// In the --du layout, collapse entries below this percentage of their parent
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub du_threshold: Option<u8>,
+
     /// Stop recursing into directories after reaching specified depth
     #[arg(long, value_name = "NUM")]
     pub depth: Option<usize>,
@@ -76,6 +125,14 @@ pub struct Cli {
     #[arg(long, value_name = "MODE", value_parser = ["default", "short", "bytes"])]
     pub size: Option<String>,
 
+    /// Show the space allocated on disk instead of the apparent size
+    #[arg(long)]
+    pub disk_usage: bool,
+
+    /// Scale sizes and blocks to a unit, or to powers of 1000 with SI
+    #[arg(long, value_name = "UNIT", value_parser = ["K", "M", "G", "SI"])]
+    pub block_size: Option<String>,
+
     /// Display the total size of directories
     #[arg(long)]
     pub total_size: bool,
@@ -84,6 +141,23 @@ pub struct Cli {
     #[arg(long, value_parser = validate_date_argument)]
     pub date: Option<String>,
 
+    /// Which timestamp to show and sort by
+    #[arg(
+        long,
+        value_name = "FIELD",
+        value_parser = ["modified", "access", "change", "birth"],
+        overrides_with_all = ["access_time", "change_time"]
+    )]
+    pub time: Option<String>,
+
+    /// Show and sort by the access time, same as `--time access`
+    #[arg(short = 'u', overrides_with_all = ["time", "change_time"])]
+    pub access_time: bool,
+
+    /// Show and sort by the change time, same as `--time change`
+    #[arg(short = 'c', overrides_with_all = ["time", "access_time"])]
+    pub change_time: bool,
+
     /// Sort by time modified
     #[arg(short = 't', long)]
     pub timesort: bool,
@@ -104,11 +178,11 @@ pub struct Cli {
     #[arg(short = 'v', long)]
     pub versionsort: bool,
 
-    /// Sort by TYPE instead of name
+    /// Sort by KEYS instead of name, a comma separated chain such as `ext,size:desc,name`
+    /// [keys: name, size, time, version, extension, git, none, with an optional :asc or :desc]
     #[arg(
         long,
-        value_name = "TYPE",
-        value_parser = ["size", "time", "version", "extension", "git", "none"],
+        value_name = "KEYS",
         overrides_with_all = ["timesort", "sizesort", "extensionsort", "versionsort", "gitsort", "no_sort"]
     )]
     pub sort: Option<String>,
@@ -133,7 +207,7 @@ pub struct Cli {
     #[arg(
     long,
     value_delimiter = ',',
-    value_parser = ["permission", "user", "group", "context", "size", "date", "name", "inode", "links", "git"],
+    value_parser = ["permission", "user", "group", "context", "size", "blocks", "date", "atime", "ctime", "btime", "name", "inode", "links", "git", "xattrs", "caps", "flags", "fs", "mime", "hash"],
     )]
     pub blocks: Vec<String>,
 
@@ -150,6 +224,43 @@ pub struct Cli {
     #[arg(short = 'I', long, value_name = "PATTERN")]
     pub ignore_glob: Vec<String>,
 
+    /// Do not display files ignored by .gitignore files, the way git does
+    #[arg(long)]
+    pub git_ignore: bool,
+
+    /// Do not display files ignored by .ignore and .kglsignore files
+    #[arg(long)]
+    pub ignore_files: bool,
+
+    /// Only list entries of at least this size, e.g. 512, 10K, 1.5M or 2G
+    #[arg(long, value_name = "SIZE")]
+    pub min_size: Option<String>,
+
+    /// Only list entries of at most this size, e.g. 512, 10K, 1.5M or 2G
+    #[arg(long, value_name = "SIZE")]
+    pub max_size: Option<String>,
+
+    /// Only list entries modified within a duration (e.g. 30m, 2d, 1w) or after a reference file
+    #[arg(long, value_name = "WHEN")]
+    pub newer: Option<String>,
+
+    /// Only list entries modified before a duration ago (e.g. 30m, 2d, 1w) or before a reference file
+    #[arg(long, value_name = "WHEN")]
+    pub older: Option<String>,
+
+    /// Only list entries of these types: f (file), d (directory), l (symlink), p (pipe),
+    /// s (socket), b (block device), c (char device)
+    #[arg(long = "type", value_name = "TYPES", value_delimiter = ',', value_parser = ["f", "d", "l", "p", "s", "b", "c"])]
+    pub file_type: Vec<String>,
+
+    /// Only list entries owned by this user, given by name or id
+    #[arg(long, value_name = "USER")]
+    pub user: Option<String>,
+
+    /// Only list entries owned by this group, given by name or id
+    #[arg(long, value_name = "GROUP")]
+    pub group: Option<String>,
+
     /// Display the index number of each file
     #[arg(short, long)]
     pub inode: bool,
@@ -168,6 +279,22 @@ pub struct Cli {
     #[arg(short = 'Z', long)]
     pub context: bool,
 
+    /// Print the extended attributes of each file under it, in the long and one-line layouts
+    #[arg(short = '@', long)]
+    pub xattrs: bool,
+
+    /// Print the ACL entries of each file under it, in the long and one-line layouts
+    #[arg(long)]
+    pub acl: bool,
+
+    /// Digest computed by the hash block [default: xxh3]
+    #[arg(long, value_name = "ALGORITHM", value_parser = ["xxh3", "blake3"])]
+    pub hash: Option<String>,
+
+    /// Keep the digests of the hash block between runs, reusing them for unchanged files
+    #[arg(long)]
+    pub hash_cache: bool,
+
     /// Attach hyperlink to filenames [default: never]
     #[arg(long, value_name = "MODE", value_parser = ["always", "auto", "never"])]
     pub hyperlink: Option<String>,
@@ -176,6 +303,10 @@ pub struct Cli {
     #[arg(long)]
     pub header: bool,
 
+    /// Print the number of files and directories and their size after each directory listing
+    #[arg(long)]
+    pub summary: bool,
+
     /// Truncate the user and group names if they exceed a certain number of characters
     #[arg(long, value_name = "NUM")]
     pub truncate_owner_after: Option<usize>,
@@ -193,6 +324,10 @@ pub struct Cli {
     #[arg(short = 'N', long)]
     pub literal: bool,
 
+    /// Print entries as JSON or newline-delimited JSON instead of text [default: text]
+    #[arg(long, value_name = "FORMAT", value_parser = ["text", "json", "ndjson"])]
+    pub output: Option<String>,
+
     /// Print help information
     #[arg(long, action = ArgAction::Help)]
     help: (),
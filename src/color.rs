@@ -5,7 +5,7 @@ use std::path::Path;
 
 pub use crate::flags::color::ThemeOption;
 use crate::git::GitStatus;
-use crate::meta::{FileType, GitFileStatus};
+use crate::meta::{ContentClass, FileType, GitFileStatus};
 
 use crate::theme::{color::ColorTheme, Theme};
 use crate::theme::render::{ErrorStatus, Highlight};
@@ -24,6 +24,8 @@ pub enum Elem {
     Dir {
         uid: bool,
     },
+    /// A directory something is mounted on
+    MountPoint,
     Pipe,
     BlockDevice,
     CharDevice,
@@ -58,6 +60,8 @@ pub enum Elem {
     /// File Size
     NonFile,
     FileLarge,
+    /// A file with much less space allocated than its length
+    FileSparse,
     FileMedium,
     FileSmall,
 
@@ -105,6 +109,7 @@ impl Elem {
             Elem::MissingSymLinkTarget => theme.file_type.symlink.missing_target,
             Elem::Dir { uid: true } => theme.file_type.dir.uid,
             Elem::Dir { uid: false } => theme.file_type.dir.no_uid,
+            Elem::MountPoint => theme.file_type.dir.mount_point,
             Elem::Pipe => theme.file_type.pipe,
             Elem::BlockDevice => theme.file_type.block_device,
             Elem::CharDevice => theme.file_type.char_device,
@@ -133,6 +138,7 @@ impl Elem {
             Elem::Group => theme.group,
             Elem::NonFile => theme.size.none,
             Elem::FileLarge => theme.size.large,
+            Elem::FileSparse => theme.size.sparse,
             Elem::FileMedium => theme.size.medium,
             Elem::FileSmall => theme.size.small,
             Elem::INode { valid: true } => theme.inode.valid,
@@ -236,6 +242,18 @@ impl Colors {
         Self { theme, lscolors }
     }
 
+    /// Whether anything is colored at all
+    pub fn is_enabled(&self) -> bool {
+        self.theme.is_some()
+    }
+
+    /// Whether a render rule matches on the sniffed content of files
+    pub fn matches_contents(&self) -> bool {
+        self.theme
+            .as_ref()
+            .is_some_and(|theme| theme.render_rules.iter().any(|rule| rule.matchers.contents.is_some()))
+    }
+
     pub fn colorize<S: Into<String>>(&self, input: S, elem: &Elem) -> ColoredString {
         self.style(elem).apply(input.into())
     }
@@ -376,6 +394,7 @@ impl Colors {
         &self,
         file_type: &FileType,
         extension: Option<&str>,
+        content: Option<ContentClass>,
         git_status: Option<&GitFileStatus>,
         has_error: bool,
         draw_attention: bool,
@@ -407,7 +426,7 @@ impl Colors {
             
             // Evaluate rules in order - first match wins
             for rule in &theme.render_rules {
-                if rule.matches(file_type, extension, simple_git_status, error_status, highlight) {
+                if rule.matches(file_type, extension, content, simple_git_status, error_status, highlight) {
                     return self.apply_rule_actions(&rule.display, file_type);
                 }
             }
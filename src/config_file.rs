@@ -5,8 +5,12 @@ use crate::flags::icons::{IconOption, IconTheme};
 use crate::flags::layout::Layout;
 use crate::flags::permission::PermissionFlag;
 use crate::flags::size::SizeFlag;
-use crate::flags::sorting::{DirGrouping, SortColumn};
+use crate::flags::block_size::BlockSize;
+use crate::flags::sorting::{DirGrouping, SortColumn, SortKey};
+use crate::flags::time_field::TimeField;
+use crate::flags::hash_algorithm::HashAlgorithm;
 use crate::flags::HyperlinkOption;
+use crate::flags::OutputFormat;
 use crate::flags::{ColorOption, ThemeOption};
 
 use std::path::{Path, PathBuf};
@@ -27,14 +31,26 @@ pub struct Config {
     pub blocks: Option<Vec<String>>,
     pub color: Option<Color>,
     pub date: Option<String>,
+    pub time: Option<TimeField>,
     pub dereference: Option<bool>,
     pub display: Option<Display>,
     pub icons: Option<Icons>,
     pub ignore_globs: Option<Vec<String>>,
+    pub git_ignore: Option<bool>,
+    pub ignore_files: Option<bool>,
     pub indicators: Option<bool>,
     pub layout: Option<Layout>,
+    pub xattrs: Option<bool>,
+    pub acl: Option<bool>,
+    pub hash: Option<HashAlgorithm>,
+    pub hash_cache: Option<bool>,
+    pub checksum: Option<bool>,
+    pub du_threshold: Option<u8>,
     pub recursion: Option<Recursion>,
+    pub one_file_system: Option<bool>,
     pub size: Option<SizeFlag>,
+    pub disk_usage: Option<bool>,
+    pub block_size: Option<BlockSize>,
     pub permission: Option<PermissionFlag>,
     pub sorting: Option<Sorting>,
     pub no_symlink: Option<bool>,
@@ -42,7 +58,9 @@ pub struct Config {
     pub symlink_arrow: Option<String>,
     pub hyperlink: Option<HyperlinkOption>,
     pub header: Option<bool>,
+    pub summary: Option<bool>,
     pub literal: Option<bool>,
+    pub output: Option<OutputFormat>,
     pub truncate_owner: Option<TruncateOwner>,
 }
 
@@ -69,6 +87,7 @@ pub struct Recursion {
 #[serde(rename_all = "kebab-case")]
 pub struct Sorting {
     pub column: Option<SortColumn>,
+    pub keys: Option<Vec<SortKey>>,
     pub reverse: Option<bool>,
     pub dir_grouping: Option<DirGrouping>,
 }
@@ -126,14 +145,26 @@ impl Config {
             blocks: None,
             color: None,
             date: None,
+            time: None,
             dereference: None,
             display: None,
             icons: None,
             ignore_globs: None,
+            git_ignore: None,
+            ignore_files: None,
             indicators: None,
             layout: None,
+            xattrs: None,
+            acl: None,
+            hash: None,
+            hash_cache: None,
+            checksum: None,
+            du_threshold: None,
             recursion: None,
+            one_file_system: None,
             size: None,
+            disk_usage: None,
+            block_size: None,
             permission: None,
             sorting: None,
             no_symlink: None,
@@ -141,7 +172,9 @@ impl Config {
             symlink_arrow: None,
             hyperlink: None,
             header: None,
+            summary: None,
             literal: None,
+            output: None,
             truncate_owner: None,
         }
     }
@@ -244,7 +277,8 @@ classic: false
 # == Blocks ==
 # This specifies the columns and their order when using the long and the tree
 # layout.
-# Possible values: permission, user, group, context, size, date, name, inode, git
+# Possible values: permission, user, group, context, size, blocks, date, atime, ctime, btime,
+# name, inode, links, git, xattrs, caps, flags, fs, mime, hash
 blocks:
   - permission
   - user
@@ -275,6 +309,42 @@ color:
 # Possible values: date, locale, relative, +<date_format>
 # date: date
 
+# == Time ==
+# Which timestamp the date column shows and the time sort uses.
+# The birth time is not recorded by every file system.
+# Possible values: modified, access, change, birth
+# time: modified
+
+# == Xattrs ==
+# Whether to print the extended attributes of each file under it, with the size
+# and a preview of their value, in the long and one-line layouts.
+# Possible values: false, true
+xattrs: false
+
+# == ACL ==
+# Whether to print the access ACL entries of each file under it, with the
+# effective rights left by the mask, in the long and one-line layouts.
+# Possible values: false, true
+acl: false
+
+# == Hash ==
+# Which digest the hash block computes: the fast xxh3, or the cryptographic blake3.
+# Possible values: xxh3, blake3
+# hash: xxh3
+
+# == Hash Cache ==
+# Whether to keep the digests of the hash block in the cache directory, and reuse
+# them for the files whose inode, modification time and size are unchanged.
+# Possible values: false, true
+hash-cache: false
+
+# == Checksum ==
+# Whether --diff and --since-snapshot compare the content of files of the same size,
+# rather than their modification time, to tell whether they were modified. Snapshots
+# saved with it keep the digest of every file.
+# Possible values: false, true
+checksum: false
+
 # == Dereference ==
 # Whether to dereference symbolic links.
 # Possible values: false, true
@@ -309,6 +379,18 @@ icons:
 # To disable all default patterns and start fresh:
 # ignore-globs: []
 
+# == Git ignore ==
+# Whether to hide entries ignored by .gitignore files and .git/info/exclude,
+# with the same negation, anchoring and nesting rules as git.
+# Possible values: false, true
+git-ignore: false
+
+# == Ignore files ==
+# Whether to hide entries ignored by .ignore and .kglsignore files.
+# They use the .gitignore syntax and take precedence over .gitignore.
+# Possible values: false, true
+ignore-files: false
+
 # == Indicators ==
 # Whether to add indicator characters to certain listed files.
 # Possible values: false, true
@@ -317,9 +399,15 @@ indicators: false
 # == Layout ==
 # Which layout to use. "oneline" might be a bit confusing here and should be
 # called "one-per-line". It might be changed in the future.
-# Possible values: grid, tree, oneline
+# Possible values: grid, tree, oneline, du
 layout: grid
 
+# == Du threshold ==
+# In the du layout, entries taking less than this percentage of their parent
+# are collapsed into a single "N other entries" line, when there are two or more.
+# Possible values: 0 to 100
+du-threshold: 1
+
 # == Recursion ==
 recursion:
   # Whether to enable recursion.
@@ -329,11 +417,29 @@ recursion:
   # it unspecified for (virtually) infinite.
   # depth: 3
 
+# == One File System ==
+# Whether recursive listings stay on the file system of each input, listing the
+# mount points they meet without descending into them.
+# Possible values: false, true
+one-file-system: false
+
 # == Size ==
 # Specifies the format of the size column.
 # Possible values: default, short, bytes
 size: default
 
+# == Disk Usage ==
+# Whether sizes are the space allocated on disk rather than the apparent length,
+# which is much smaller for sparse files.
+# Possible values: false, true
+disk-usage: false
+
+# == Block Size ==
+# The unit of the size and blocks columns. Without it sizes pick their own unit
+# and blocks are counted in KiB. SI picks units in powers of 1000.
+# Possible values: K, M, G, SI
+# block-size: K
+
 # == Permission ==
 # Specify the format of the permission column.
 # Possible value: rwx, octal, attributes, disable
@@ -344,6 +450,10 @@ sorting:
   # Specify what to sort by.
   # Possible values: extension, name, time, size, version
   column: name
+  # A chain of keys to sort by, each one breaking the ties of the previous ones.
+  # Takes precedence over "column". A key may end with ":asc" or ":desc".
+  # Possible keys: extension, name, time, size, version, git
+  # keys: [ext, "size:desc", name]
   # Whether to reverse the sorting.
   # Possible values: false, true
   reverse: false
@@ -376,6 +486,17 @@ symlink-arrow: ⇒
 # Possible values: false, true
 literal: false
 
+# == Summary ==
+# Whether to print the number of files, directories and their size after each
+# directory listing.
+# Possible values: false, true
+summary: false
+
+# == Output ==
+# Whether to print text, a JSON array, or one JSON object per line.
+# Possible values: text, json, ndjson
+output: text
+
 # == Truncate owner ==
 # How to truncate the username and group name for the file if they exceed a
 # certain number of characters.
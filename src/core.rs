@@ -1,13 +1,24 @@
 use crate::color::Colors;
 use crate::display;
+use crate::display::RowRenderer;
+use crate::flags::blocks::Block;
 use crate::flags::{
-    ColorOption, Flags, HyperlinkOption, Layout, Literal, SortOrder, ThemeOption,
+    ColorOption, Display, Flags, HashAlgorithm, HyperlinkOption, Layout, Literal, OutputFormat, SortColumn, SortOrder,
+    ThemeOption,
 };
 use crate::icon::Icons;
+use crate::interactive;
 
-use crate::meta::Meta;
+use crate::meta::{DiffStatus, FileType, Meta, OwnerCache, Size};
+use crate::presentation::json;
+use crate::stream::{
+    compare_trees, find_duplicates, Archive, ArchivePath, DigestCache, DiskUsage, FileEntry, GridAccumulator,
+    IgnoreSources, OutputEvent, Revision, RunningTotals, Snapshot,
+};
+use crate::watch;
 use crate::{print_output, sort, ExitCode};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[cfg(not(target_os = "windows"))]
 use std::io;
@@ -20,9 +31,9 @@ use terminal_size::terminal_size;
 
 pub struct Core {
     flags: Flags,
-    icons: Icons,
-    colors: Colors,
-    git_theme: GitTheme,
+    icons: Arc<Icons>,
+    colors: Arc<Colors>,
+    git_theme: Arc<GitTheme>,
     sorters: Vec<(SortOrder, sort::SortFn)>,
     stdout_writer: Option<Box<dyn std::io::Write + Send>>,
     stderr_writer: Option<Box<dyn std::io::Write + Send>>,
@@ -32,8 +43,13 @@ impl Core {
     pub fn new(mut flags: Flags) -> Self {
         // Check through libc if stdout is a tty. Unix specific so not on windows.
         // Determine color output availability (and initialize color output (for Windows 10))
+        // The browser is drawn on stderr, leaving stdout to the chosen paths
         #[cfg(not(target_os = "windows"))]
-        let tty_available = unsafe { libc::isatty(io::stdout().as_raw_fd()) == 1 };
+        let tty_available = if flags.interactive.0 {
+            unsafe { libc::isatty(io::stderr().as_raw_fd()) == 1 }
+        } else {
+            unsafe { libc::isatty(io::stdout().as_raw_fd()) == 1 }
+        };
 
         #[cfg(not(target_os = "windows"))]
         let console_color_ok = true;
@@ -71,8 +87,8 @@ impl Core {
         // Most of the programs does not handle correctly the ansi colors
         // or require a raw output (like the `wc` command).
         if !tty_available {
-            // we should not overwrite the tree layout
-            if flags.layout != Layout::Tree {
+            // we should not overwrite the tree layouts
+            if !matches!(flags.layout, Layout::Tree | Layout::Du) {
                 flags.layout = Layout::OneLine;
             }
 
@@ -83,9 +99,9 @@ impl Core {
 
         Self {
             flags,
-            colors: Colors::new(color_theme),
-            icons: Icons::new(tty_available, icon_when, icon_theme, icon_separator),
-            git_theme: GitTheme::new(),
+            colors: Arc::new(Colors::new(color_theme)),
+            icons: Arc::new(Icons::new(tty_available, icon_when, icon_theme, icon_separator)),
+            git_theme: Arc::new(GitTheme::new()),
             sorters,
             stdout_writer: None,
             stderr_writer: None,
@@ -119,65 +135,321 @@ impl Core {
     }
 
     pub async fn run(mut self, paths: Vec<PathBuf>) -> ExitCode {
+        if let Some((old, new)) = self.flags.diff.0.clone() {
+            return self.display_diff(old, new).await;
+        }
+
+        if let Some(rev) = self.flags.rev.0.clone() {
+            return self.display_revision(&rev, &paths);
+        }
+
+        // Archives and paths into them are listed like directories, unless only the inputs are
+        let lists_archives = self.flags.display != Display::DirectoryOnly
+            && !(self.flags.watch.0
+                || self.flags.interactive.0
+                || self.flags.duplicates.0
+                || self.flags.snapshot.is_active());
+
         // Validate paths exist before processing
         let mut exit_code = ExitCode::OK;
-        let mut valid_paths = Vec::new();
-        
-        for path in &paths {
-            if !path.exists() {
+        let mut inputs = Vec::new();
+
+        for path in paths {
+            if let Some(archive_path) = lists_archives.then(|| ArchivePath::parse(&path)).flatten() {
+                match Archive::open(&archive_path) {
+                    Ok(archive) => inputs.push(Input::Archive(archive)),
+                    // A file that only looks like an archive is listed as the file it is
+                    Err(e) if archive_path.inner.as_os_str().is_empty() => {
+                        log::warn!("Cannot read archive '{}': {}", archive_path.archive.display(), e);
+                        self.write_error(format!("kgls: cannot read archive '{}': {}", archive_path.archive.display(), e));
+                        exit_code.set_if_greater(ExitCode::MinorIssue);
+                        inputs.push(Input::Path(archive_path.archive));
+                    }
+                    Err(e) => {
+                        log::error!("Cannot read archive '{}': {}", archive_path.archive.display(), e);
+                        self.write_error(format!("kgls: cannot read archive '{}': {}", archive_path.archive.display(), e));
+                        exit_code.set_if_greater(ExitCode::MinorIssue);
+                    }
+                }
+            } else if !path.exists() {
                 log::error!("Cannot access '{}': No such file or directory", path.display());
                 self.write_error(format!("kgls: cannot access '{}': No such file or directory", path.display()));
                 exit_code.set_if_greater(ExitCode::MinorIssue);
             } else {
-                valid_paths.push(path.clone());
+                inputs.push(Input::Path(path));
             }
         }
         
         // If no valid paths, return early
-        if valid_paths.is_empty() {
+        if inputs.is_empty() {
             return exit_code;
         }
-        
-        // Determine traversal depth based on flags (copied from fetch() logic)
-        let depth = match self.flags.layout {
-            Layout::Tree => self.flags.recursion.depth,
+
+        // Like ls, file inputs are listed before directories, archives counting as directories
+        inputs.sort_by_key(|input| match input {
+            Input::Path(path) => path.symlink_metadata().is_ok_and(|meta| meta.is_dir()),
+            Input::Archive(_) => true,
+        });
+        let depth = self.depth();
+
+        if self.flags.interactive.0 || self.flags.watch.0 {
+            let valid_paths = inputs
+                .into_iter()
+                .filter_map(|input| match input {
+                    Input::Path(path) => Some(path),
+                    Input::Archive(_) => None,
+                })
+                .collect();
+            if self.flags.interactive.0 {
+                exit_code.set_if_greater(self.browse(valid_paths).await);
+            } else {
+                exit_code.set_if_greater(self.watch(valid_paths, depth).await);
+            }
+            return exit_code;
+        }
+
+        // Archives take their place among the inputs, the paths between them being walked together
+        let input_count = inputs.len();
+        let mut printed = false;
+        let mut inputs = inputs.into_iter().peekable();
+        while let Some(input) = inputs.next() {
+            let input_exit_code = match input {
+                Input::Archive(archive) => self.display_archive(&archive, depth, input_count > 1, printed),
+                Input::Path(path) => {
+                    let mut valid_paths = vec![path];
+                    while let Some(Input::Path(path)) = inputs.next_if(|input| matches!(input, Input::Path(_))) {
+                        valid_paths.push(path);
+                    }
+                    self.display_paths(&valid_paths, depth, input_count, printed).await
+                }
+            };
+            exit_code.set_if_greater(input_exit_code);
+            printed = true;
+        }
+        exit_code
+    }
+
+    /// List paths on disk, walking them as one stream
+    async fn display_paths(&mut self, valid_paths: &[PathBuf], depth: usize, input_count: usize, printed: bool) -> ExitCode {
+        let file_stream = self.file_stream(valid_paths, depth);
+
+        // Route to appropriate output mode
+        if self.flags.snapshot.is_active() {
+            self.display_snapshot(file_stream, valid_paths).await
+        } else if self.flags.duplicates.0 {
+            self.display_duplicates(file_stream).await
+        } else if self.flags.output == OutputFormat::Ndjson {
+            self.display_ndjson_stream(file_stream, depth).await
+        } else if matches!(self.flags.layout, Layout::Tree | Layout::Du) {
+            self.display_tree_stream(file_stream, valid_paths).await
+        } else if self.flags.output == OutputFormat::Json {
+            self.display_buffered(file_stream, depth).await
+        } else {
+            self.display_grid_stream(file_stream, depth, input_count, printed).await
+        }
+    }
+
+    fn display_archive(&mut self, archive: &Archive, depth: usize, several: bool, printed: bool) -> ExitCode {
+        let path = archive.path();
+        let totals = self.disk_usage().is_some();
+        let entries = match archive.entries(depth, totals, self.keeps(), &self.flags) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::error!("Cannot read archive '{}': {}", path.archive.display(), e);
+                self.write_error(format!("kgls: cannot read archive '{}': {}", path.archive.display(), e));
+                return ExitCode::MinorIssue;
+            }
+        };
+        // A member named after `//` that is not a directory stands for itself
+        let alone = !path.inner.as_os_str().is_empty() && entries.len() == 1;
+        self.display_members(entries, alone, several, printed)
+    }
+
+    /// `--rev`: list the tree of a revision at each input as if it were checked out
+    fn display_revision(&mut self, rev: &str, paths: &[PathBuf]) -> ExitCode {
+        let mut exit_code = ExitCode::OK;
+        let depth = self.depth();
+        let compare = self.flags.rev_diff.0;
+        let totals = self.disk_usage().is_some();
+        // Sizes and changes of directories come from everything below them
+        let read_depth = if totals || compare { usize::MAX } else { depth };
+        for (index, path) in paths.iter().enumerate() {
+            let entries = match Revision::open(rev, path, read_depth, compare) {
+                Ok(revision) => revision.entries(depth, totals, self.keeps(), &self.flags),
+                Err(e) => {
+                    log::error!("Cannot read '{}' at {}: {}", path.display(), rev, e);
+                    self.write_error(format!("kgls: cannot read '{}' at {}: {}", path.display(), rev, e));
+                    exit_code.set_if_greater(ExitCode::MinorIssue);
+                    continue;
+                }
+            };
+            let alone = depth == 0 || !matches!(entries[0].1.file_type, FileType::Directory { .. });
+            exit_code.set_if_greater(self.display_members(entries, alone, paths.len() > 1, index > 0));
+        }
+        exit_code
+    }
+
+    /// Whether a name inside an archive or a revision is listed: hidden names and the ones
+    /// of the ignore globs are not, as on disk
+    fn keeps(&self) -> impl Fn(&std::ffi::OsStr) -> bool + '_ {
+        |name| {
+            let hidden = name.to_str().is_some_and(|name| name.starts_with('.'))
+                && matches!(self.flags.display, Display::VisibleOnly | Display::DirectoryOnly);
+            !hidden && !self.flags.ignore_globs.is_match(name)
+        }
+    }
+
+    /// Show entries read from elsewhere than the file system, keyed by their path relative
+    /// to the first one, the root. Like a directory given as input, the root stands for its
+    /// content unless it is listed `alone`.
+    fn display_members(&mut self, entries: Vec<(PathBuf, Meta)>, alone: bool, several: bool, printed: bool) -> ExitCode {
+        if self.flags.output == OutputFormat::Ndjson {
+            let owner_cache = OwnerCache::default();
+            let mut exit_code = ExitCode::OK;
+            for (_, meta) in &entries {
+                match json::line(meta, &owner_cache) {
+                    Ok(line) => self.write_output(line),
+                    Err(e) => {
+                        log::error!("Cannot serialize '{}': {}", meta.path.display(), e);
+                        self.write_error(format!("kgls: cannot serialize '{}': {}", meta.path.display(), e));
+                        exit_code.set_if_greater(ExitCode::MinorIssue);
+                    }
+                }
+            }
+            return exit_code;
+        }
+
+        if self.flags.output == OutputFormat::Json && !matches!(self.flags.layout, Layout::Tree | Layout::Du) {
+            let mut metas: Vec<Meta> = entries.into_iter().map(|(_, meta)| meta).collect();
+            self.sort(&mut metas);
+            return self.write_json(&metas);
+        }
+
+        // Entries compared with the working tree show how they differ from it
+        let flags = if entries.iter().any(|(_, meta)| meta.diff_status.is_some()) {
+            self.marked_flags()
+        } else {
+            self.flags.clone()
+        };
+        let roots = self.nest(entries);
+        if self.flags.output == OutputFormat::Json {
+            return self.write_json(&roots);
+        }
+        match self.flags.layout {
+            Layout::Du => {
+                let output = display::du(&roots, &flags, &self.colors, &self.icons);
+                self.write_output(output);
+            }
+            Layout::Tree => {
+                let output = display::tree(&roots, &flags, &self.colors, &self.icons, &self.git_theme);
+                self.write_output(output);
+            }
+            _ => {
+                let show_headers = self.flags.recursion.enabled || several;
+                // Like a recursive listing, each directory is a section of its own
+                fn directories<'a>(meta: &'a Meta, sections: &mut Vec<&'a Meta>) {
+                    sections.push(meta);
+                    for child in meta.content.iter().flatten() {
+                        if child.content.is_some() {
+                            directories(child, sections);
+                        }
+                    }
+                }
+                let (files, roots): (Vec<Meta>, Vec<Meta>) = roots.into_iter().partition(|_| alone);
+                let mut listed = Vec::new();
+                for root in &roots {
+                    directories(root, &mut listed);
+                }
+
+                let renderer = RowRenderer::new(
+                    flags.clone(),
+                    Arc::clone(&self.colors),
+                    Arc::clone(&self.icons),
+                    Arc::clone(&self.git_theme),
+                );
+                let mut printed = printed;
+                let sections = std::iter::once((None, renderer.render_paths(&files), files.as_slice()))
+                    .chain(listed.into_iter().map(|directory| {
+                        let children = directory.content.as_deref().unwrap_or_default();
+                        (Some(directory), renderer.render(children), children)
+                    }));
+                for (directory, rendered, metas) in sections {
+                    if let Some(directory) = directory
+                        && show_headers
+                    {
+                        let separator = if printed { "\n" } else { "" };
+                        self.write_output(format!("{separator}{}", display::directory_header(&directory.path)));
+                        printed = true;
+                    }
+                    if !metas.is_empty() {
+                        let rows = rendered.into_iter().zip(metas.iter().map(|meta| renderer.details(meta))).collect();
+                        self.write_output(display::rows(rows, &flags));
+                        printed = true;
+                    }
+                    if directory.is_some() {
+                        let dir_count =
+                            metas.iter().filter(|meta| matches!(meta.file_type, FileType::Directory { .. })).count();
+                        let total_size = metas.iter().filter_map(|meta| meta.size.as_ref()).map(Size::get_bytes).sum();
+                        self.write_summary(Some((metas.len() - dir_count, dir_count, total_size)));
+                    }
+                }
+            }
+        }
+        ExitCode::OK
+    }
+
+    /// How deep below the inputs the flags list
+    fn depth(&self) -> usize {
+        match self.flags.layout {
+            _ if self.flags.duplicates.0 || self.flags.snapshot.is_active() => self.flags.recursion.depth,
+            Layout::Tree | Layout::Du => self.flags.recursion.depth,
+            // Only the inputs themselves are listed
+            _ if self.flags.display == Display::DirectoryOnly => 0,
             _ if self.flags.recursion.enabled => self.flags.recursion.depth,
             _ => 1,
-        };
+        }
+    }
 
-        // Build streaming pipeline
-        let file_stream = crate::stream::FileStream::new(
-            valid_paths.clone(),
+    /// Walk `paths` down to `depth`, with the stages the flags ask for
+    pub(crate) fn file_stream(&self, paths: &[PathBuf], depth: usize) -> crate::stream::FileStream {
+        let mut file_stream = crate::stream::FileStream::new(
+            paths.to_vec(),
             depth,
             &self.flags.ignore_globs,
+            IgnoreSources::from_flags(&self.flags),
             self.flags.display,
+            self.flags.one_file_system.0,
         );
 
-        // Route to appropriate output mode
-        let stream_exit_code = if self.flags.layout == Layout::Tree {
-            self.display_tree_stream(file_stream, &valid_paths).await
-        } else {
-            // Grid/OneLine modes: buffer temporarily (can optimize with GridAccumulator later)
-            self.display_buffered(file_stream).await
-        };
-        
-        // Combine exit codes (take the greater error level)
-        exit_code.set_if_greater(stream_exit_code);
-        exit_code
+        if self.flags.filters.is_active() {
+            file_stream = file_stream.with_filters(self.flags.filters.clone());
+        }
+
+        if self.wants_git_status() {
+            file_stream = file_stream.with_git_status(paths);
+        }
+
+        if let Some(all) = self.wants_magic() {
+            file_stream = file_stream.with_magic(all);
+        }
+
+        if let Some(algorithm) = self.wants_hashes() {
+            let cache = self.flags.reuse_hashes.0.then(|| DigestCache::load(algorithm));
+            file_stream = file_stream.with_hashes(algorithm, cache);
+        }
+
+        file_stream
     }
 
-    async fn display_tree_stream(
+    /// Gather every entry of the stream, reporting the errors met on the way
+    pub(crate) async fn collect_entries(
         &mut self,
         file_stream: crate::stream::FileStream,
-        _paths: &[PathBuf],
-    ) -> ExitCode {
+        exit_code: &mut ExitCode,
+    ) -> Vec<FileEntry> {
         use futures::StreamExt;
-        use std::collections::HashMap;
 
-        // Buffer all entries and organize hierarchically
         let mut entries = Vec::new();
-        let mut exit_code = ExitCode::OK;
-
         let mut stream = Box::pin(file_stream);
         while let Some(result) = stream.next().await {
             match result {
@@ -189,15 +461,32 @@ impl Core {
                 }
             }
         }
+        entries
+    }
+
+    async fn display_tree_stream(
+        &mut self,
+        file_stream: crate::stream::FileStream,
+        _paths: &[PathBuf],
+    ) -> ExitCode {
+        use std::collections::HashMap;
+
+        // Buffer all entries and organize hierarchically
+        let mut exit_code = ExitCode::OK;
+        let mut entries = self.collect_entries(file_stream, &mut exit_code).await;
 
         // Sort by depth descending so we process deepest children first
         // This ensures children have their descendants before being cloned to parents
-        entries.sort_by(|a, b| b.depth.cmp(&a.depth));
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.depth));
+
+        if let Some(disk_usage) = self.disk_usage() {
+            disk_usage.fill(&mut entries, self.flags.recursion.depth);
+        }
 
         // Convert entries to Meta and build hierarchy
         let mut meta_map: HashMap<PathBuf, Meta> = HashMap::new();
         for entry in &entries {
-            let meta = entry.to_meta(self.flags.permission);
+            let meta = entry.to_meta(&self.flags);
             meta_map.insert(entry.path.clone(), meta);
         }
 
@@ -263,14 +552,23 @@ impl Core {
         // Sort root metas
         self.sort(&mut root_metas);
 
+        if self.flags.output == OutputFormat::Json {
+            exit_code.set_if_greater(self.write_json(&root_metas));
+            return exit_code;
+        }
+
         // Display using existing tree display logic
-        let output = display::tree(
-            &root_metas,
-            &self.flags,
-            &self.colors,
-            &self.icons,
-            &self.git_theme,
-        );
+        let output = if self.flags.layout == Layout::Du {
+            display::du(&root_metas, &self.flags, &self.colors, &self.icons)
+        } else {
+            display::tree(
+                &root_metas,
+                &self.flags,
+                &self.colors,
+                &self.icons,
+                &self.git_theme,
+            )
+        };
 
         self.write_output(output);
         exit_code
@@ -279,17 +577,84 @@ impl Core {
     async fn display_buffered(
         &mut self,
         file_stream: crate::stream::FileStream,
+        depth: usize,
+    ) -> ExitCode {
+        // Buffer entries from stream
+        let mut exit_code = ExitCode::OK;
+        let mut entries = self.collect_entries(file_stream, &mut exit_code).await;
+
+        if let Some(disk_usage) = self.disk_usage() {
+            entries.sort_by_key(|entry| std::cmp::Reverse(entry.depth));
+            disk_usage.fill(&mut entries, depth);
+        }
+
+        // Convert FileEntry to Meta
+        let mut metas: Vec<Meta> = entries
+            .iter()
+            .map(|entry| entry.to_meta(&self.flags))
+            .collect();
+
+        // Sort using configured sorters
+        self.sort(&mut metas);
+
+        exit_code.set_if_greater(self.write_json(&metas));
+        exit_code
+    }
+
+    /// Grid/OneLine modes: print each directory batch as soon as the accumulator completes it
+    async fn display_grid_stream(
+        &mut self,
+        file_stream: crate::stream::FileStream,
+        depth: usize,
+        input_count: usize,
+        printed: bool,
     ) -> ExitCode {
         use futures::StreamExt;
 
-        // Buffer entries from stream
-        let mut entries = Vec::new();
+        let renderer = RowRenderer::new(
+            self.flags.clone(),
+            Arc::clone(&self.colors),
+            Arc::clone(&self.icons),
+            Arc::clone(&self.git_theme),
+        );
+        let mut accumulator =
+            GridAccumulator::new(file_stream, renderer, self.sorters.clone(), depth);
+
+        // Like ls, name each directory when there is more than one listing to tell apart
+        let show_headers = self.flags.recursion.enabled || input_count > 1;
+
         let mut exit_code = ExitCode::OK;
+        let mut rows = Vec::new();
+        let mut printed = printed;
+        // Counts of the directory being listed, to print it without waiting for the next one
+        let mut section: Option<(usize, usize, u64)> = None;
 
-        let mut stream = Box::pin(file_stream);
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(entry) => entries.push(entry),
+        while let Some(event) = accumulator.next().await {
+            match event {
+                Ok(OutputEvent::DirectoryHeader { path, file_count, dir_count, total_size }) => {
+                    printed |= self.write_rows(&mut rows);
+                    if show_headers {
+                        let separator = if printed { "\n" } else { "" };
+                        self.write_output(format!("{separator}{}", display::directory_header(&path)));
+                        printed = true;
+                    }
+                    section = Some((file_count, dir_count, total_size));
+                    if file_count + dir_count == 0 {
+                        self.write_summary(section.take());
+                    }
+                }
+                Ok(OutputEvent::FileRow { formatted, details, .. }) => {
+                    rows.push((formatted, details));
+                    if let Some((file_count, dir_count, _)) = section
+                        && rows.len() == file_count + dir_count {
+                            printed |= self.write_rows(&mut rows);
+                            self.write_summary(section.take());
+                        }
+                }
+                Ok(OutputEvent::StreamComplete { .. }) => {
+                    self.write_rows(&mut rows);
+                }
+                Ok(OutputEvent::TreeNode { .. }) => {}
                 Err(e) => {
                     log::error!("Stream error: {}", e);
                     self.write_error(format!("Stream error: {}", e));
@@ -298,31 +663,394 @@ impl Core {
             }
         }
 
-        // Convert FileEntry to Meta
-        let mut metas: Vec<Meta> = entries
+        exit_code
+    }
+
+    /// `--duplicates`: walk everything below the inputs, then list the sets of identical files
+    /// by the space they waste
+    async fn display_duplicates(&mut self, file_stream: crate::stream::FileStream) -> ExitCode {
+        let mut exit_code = ExitCode::OK;
+        let entries = self.collect_entries(file_stream, &mut exit_code).await;
+
+        let sets = find_duplicates(entries, self.flags.hash);
+        let set_metas: Vec<Vec<Meta>> = sets
             .iter()
-            .map(|entry| entry.to_meta(self.flags.permission))
+            .map(|set| {
+                let mut metas = set.entries.iter().map(|entry| entry.to_meta(&self.flags)).collect();
+                self.sort(&mut metas);
+                metas
+            })
             .collect();
 
-        // Sort using configured sorters
-        self.sort(&mut metas);
+        if !self.flags.output.is_text() {
+            let owner_cache = OwnerCache::default();
+            let records: Vec<json::JsonDuplicateSet> = sets
+                .iter()
+                .zip(&set_metas)
+                .map(|(set, metas)| json::JsonDuplicateSet::new(set, metas, &owner_cache))
+                .collect();
+            match json::duplicate_sets(&records, self.flags.output == OutputFormat::Ndjson) {
+                Ok(output) => self.write_output(output),
+                Err(e) => {
+                    log::error!("Cannot serialize duplicate sets: {}", e);
+                    self.write_error(format!("kgls: cannot serialize duplicate sets: {}", e));
+                    exit_code.set_if_greater(ExitCode::MinorIssue);
+                }
+            }
+            return exit_code;
+        }
 
-        // Display using existing grid/oneline display logic
-        let output = display::grid(
-            &metas,
-            &self.flags,
-            &self.colors,
-            &self.icons,
-            &self.git_theme,
+        let renderer = RowRenderer::new(
+            self.flags.clone(),
+            Arc::clone(&self.colors),
+            Arc::clone(&self.icons),
+            Arc::clone(&self.git_theme),
         );
+        for (index, (set, metas)) in sets.iter().zip(&set_metas).enumerate() {
+            let separator = if index > 0 { "\n" } else { "" };
+            self.write_output(format!("{separator}{}", display::duplicate_header(set, &self.flags, &self.colors)));
+            let mut rows = renderer
+                .render_paths(metas)
+                .into_iter()
+                .zip(metas.iter().map(|meta| renderer.details(meta)))
+                .collect();
+            self.write_rows(&mut rows);
+        }
+        if !sets.is_empty() {
+            self.write_output(format!("\n{}", display::duplicates_total(&sets, &self.flags, &self.colors)));
+        }
+
+        exit_code
+    }
+
+    /// `--diff`: walk both trees, then show them merged into one, each entry marked with how
+    /// it changed
+    async fn display_diff(&mut self, old_root: PathBuf, new_root: PathBuf) -> ExitCode {
+        let mut exit_code = ExitCode::OK;
+        for path in [&old_root, &new_root] {
+            if !path.exists() {
+                log::error!("Cannot access '{}': No such file or directory", path.display());
+                self.write_error(format!("kgls: cannot access '{}': No such file or directory", path.display()));
+                exit_code.set_if_greater(ExitCode::MinorIssue);
+            }
+        }
+        if exit_code != ExitCode::OK {
+            return exit_code;
+        }
+
+        let depth = self.flags.recursion.depth;
+        let old = self.file_stream(std::slice::from_ref(&old_root), depth);
+        let old = self.collect_entries(old, &mut exit_code).await;
+        let new = self.file_stream(std::slice::from_ref(&new_root), depth);
+        let new = self.collect_entries(new, &mut exit_code).await;
+        let checksum = self.flags.checksum.0.then_some(self.flags.hash);
+        let entries = compare_trees(&old_root, old, &new_root, new, checksum);
+
+        let changes = entries
+            .iter()
+            .map(|diff| {
+                let mut meta = diff.entry().to_meta(&self.flags);
+                meta.diff_status = Some(diff.status);
+                (diff.relative.clone(), meta)
+            })
+            .collect();
+        exit_code.set_if_greater(self.write_changes(changes));
+        exit_code
+    }
+
+    /// `--snapshot` and `--since-snapshot`: index the walk, and show it merged with the
+    /// entries of an earlier one, each marked with how it changed since
+    async fn display_snapshot(&mut self, file_stream: crate::stream::FileStream, paths: &[PathBuf]) -> ExitCode {
+        let mut exit_code = ExitCode::OK;
+        let previous = match self.flags.snapshot.since.clone().map(|path| (Snapshot::load(&path), path)) {
+            Some((Ok(previous), _)) => Some(previous),
+            Some((Err(e), path)) => {
+                log::error!("Cannot read snapshot '{}': {}", path.display(), e);
+                self.write_error(format!("kgls: cannot read snapshot '{}': {}", path.display(), e));
+                return ExitCode::MajorIssue;
+            }
+            None => None,
+        };
+
+        let entries = self.collect_entries(file_stream, &mut exit_code).await;
+        // Digests are only comparable when computed the same way as the earlier ones
+        let algorithm = match &previous {
+            Some(previous) if previous.algorithm().is_some() => previous.algorithm(),
+            _ => self.flags.checksum.0.then_some(self.flags.hash),
+        };
+        let current = Snapshot::of(paths, &entries, algorithm, self.wants_hashes());
+
+        if let Some(path) = self.flags.snapshot.save.clone()
+            && let Err(e) = current.save(&path)
+        {
+            log::error!("Cannot save snapshot '{}': {}", path.display(), e);
+            self.write_error(format!("kgls: cannot save snapshot '{}': {}", path.display(), e));
+            exit_code.set_if_greater(ExitCode::MajorIssue);
+        }
+
+        let Some(previous) = previous else {
+            return exit_code;
+        };
+        let by_path: std::collections::HashMap<&Path, &FileEntry> =
+            entries.iter().map(|entry| (entry.path.as_path(), entry)).collect();
+        let changes = previous
+            .changes(&current)
+            .into_iter()
+            .filter_map(|(filed, status)| {
+                let path = current.shown(&filed);
+                let mut meta = match by_path.get(path.as_path()) {
+                    Some(entry) => entry.to_meta(&self.flags),
+                    None => previous.record(&filed)?.to_meta(&path),
+                };
+                meta.diff_status = Some(status);
+                Some((path, meta))
+            })
+            .collect();
+        exit_code.set_if_greater(self.write_changes(changes));
+        exit_code
+    }
+
+    /// `--watch`: list the inputs, then list them again after each burst of changes below
+    /// them, marking for a while the entries that were created, modified or deleted
+    async fn watch(&mut self, paths: Vec<PathBuf>, depth: usize) -> ExitCode {
+        let renderer = RowRenderer::new(
+            self.marked_flags(),
+            Arc::clone(&self.colors),
+            Arc::clone(&self.icons),
+            Arc::clone(&self.git_theme),
+        );
+        let output = self.stdout_writer.take();
+        match watch::watch(self, &renderer, &paths, depth, output).await {
+            Ok(exit_code) => exit_code,
+            Err(e) => {
+                log::error!("{}", e);
+                self.write_error(format!("kgls: {}", e));
+                ExitCode::MajorIssue
+            }
+        }
+    }
+
+    /// `--interactive`: browse the inputs as a tree on the terminal, and print the chosen
+    /// paths on exit
+    async fn browse(&mut self, paths: Vec<PathBuf>) -> ExitCode {
+        let mut flags = self.flags.clone();
+        flags.layout = Layout::Tree;
+        let renderer = RowRenderer::new(
+            flags,
+            Arc::clone(&self.colors),
+            Arc::clone(&self.icons),
+            Arc::clone(&self.git_theme),
+        );
+
+        let mut exit_code = ExitCode::OK;
+        match interactive::browse(self, &renderer, &paths, &mut exit_code).await {
+            Ok(Some(paths)) => {
+                for path in paths {
+                    self.write_output(format!("{}\n", path.display()));
+                }
+            }
+            // Like a failed command, so `cd "$(kgls --interactive)"` goes nowhere
+            Ok(None) => exit_code.set_if_greater(ExitCode::MinorIssue),
+            Err(e) => {
+                log::error!("{}", e);
+                self.write_error(format!("kgls: {}", e));
+                exit_code.set_if_greater(ExitCode::MajorIssue);
+            }
+        }
+        exit_code
+    }
+
+    /// Show entries marked with their [DiffStatus] as trees, each entry nested under its
+    /// parent when that is listed too
+    fn write_changes(&mut self, mut changes: Vec<(PathBuf, Meta)>) -> ExitCode {
+        let mut exit_code = ExitCode::OK;
+        let text = self.flags.output.is_text();
+        if self.flags.output == OutputFormat::Ndjson {
+            let owner_cache = OwnerCache::default();
+            for (_, meta) in &changes {
+                match json::line(meta, &owner_cache) {
+                    Ok(line) => self.write_output(line),
+                    Err(e) => {
+                        log::error!("Cannot serialize '{}': {}", meta.path.display(), e);
+                        self.write_error(format!("kgls: cannot serialize '{}': {}", meta.path.display(), e));
+                        exit_code.set_if_greater(ExitCode::MinorIssue);
+                    }
+                }
+            }
+            return exit_code;
+        }
 
+        // Directories on both sides are only modified through their content
+        let statuses: Vec<DiffStatus> = changes
+            .iter()
+            .filter(|(_, meta)| {
+                !(matches!(meta.file_type, FileType::Directory { .. }) && meta.diff_status == Some(DiffStatus::Modified))
+            })
+            .filter_map(|(_, meta)| meta.diff_status)
+            .collect();
+
+        // The tree shows the changes with the symbols and colors of git statuses
+        if text {
+            for (_, meta) in &mut changes {
+                meta.git_status = meta.diff_status.map(DiffStatus::git_status);
+            }
+        }
+        let roots = self.nest(changes);
+
+        if !text {
+            return self.write_json(&roots);
+        }
+
+        let mut flags = self.marked_flags();
+        flags.layout = Layout::Tree;
+        let output = display::tree(&roots, &flags, &self.colors, &self.icons, &self.git_theme);
         self.write_output(output);
+
+        if self.flags.summary.0 {
+            self.write_output(display::diff_summary(statuses.into_iter()));
+        }
         exit_code
     }
 
+    /// Nest each entry under its parent when that is listed too, returning the sorted roots
+    fn nest(&self, entries: Vec<(PathBuf, Meta)>) -> Vec<Meta> {
+        let mut roots = Meta::nest(entries);
+        self.sort(&mut roots);
+        roots
+    }
 
+    /// The flags with the git status block shown, which marks entries with how they changed
+    fn marked_flags(&self) -> Flags {
+        let mut flags = self.flags.clone();
+        if !flags.blocks.0.contains(&Block::GitStatus) {
+            let name = flags.blocks.0.iter().position(|block| *block == Block::Name).unwrap_or(0);
+            flags.blocks.0.insert(name, Block::GitStatus);
+        }
+        flags
+    }
 
-    fn sort(&self, metas: &mut Vec<Meta>) {
+    /// Lay out and print the rows of one finished batch, returning whether anything was printed
+    fn write_rows(&mut self, rows: &mut Vec<(Vec<String>, Vec<String>)>) -> bool {
+        if rows.is_empty() {
+            return false;
+        }
+        let output = display::rows(std::mem::take(rows), &self.flags);
+        self.write_output(output);
+        true
+    }
+
+    fn write_summary(&mut self, section: Option<(usize, usize, u64)>) {
+        if let Some((file_count, dir_count, total_size)) = section
+            && self.flags.summary.0 {
+                let summary =
+                    display::summary(file_count, dir_count, total_size, &self.flags, &self.colors);
+                self.write_output(summary);
+            }
+    }
+
+    /// Write one JSON line per entry as soon as it comes off the stream.
+    ///
+    /// Nothing is buffered or sorted, so consumers see the first entries right away.
+    async fn display_ndjson_stream(
+        &mut self,
+        file_stream: crate::stream::FileStream,
+        depth: usize,
+    ) -> ExitCode {
+        use futures::StreamExt;
+
+        let mut exit_code = ExitCode::OK;
+        let owner_cache = OwnerCache::default();
+        // Directories are only written once their content has been counted
+        let mut totals = self.disk_usage().map(|disk_usage| RunningTotals::new(disk_usage, depth));
+
+        let mut stream = Box::pin(file_stream);
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok(entry) => {
+                    let ready = match &mut totals {
+                        Some(totals) => totals.push(entry),
+                        None => vec![entry],
+                    };
+                    for entry in ready {
+                        exit_code.set_if_greater(self.write_ndjson_line(&entry, &owner_cache));
+                    }
+                }
+                Err(e) => {
+                    log::error!("Stream error: {}", e);
+                    self.write_error(format!("Stream error: {}", e));
+                    exit_code.set_if_greater(ExitCode::MinorIssue);
+                }
+            }
+        }
+        for entry in totals.map(|mut totals| totals.finish()).unwrap_or_default() {
+            exit_code.set_if_greater(self.write_ndjson_line(&entry, &owner_cache));
+        }
+
+        exit_code
+    }
+
+    fn write_ndjson_line(&mut self, entry: &FileEntry, owner_cache: &OwnerCache) -> ExitCode {
+        let meta = entry.to_meta(&self.flags);
+        match json::line(&meta, owner_cache) {
+            Ok(line) => {
+                self.write_output(line);
+                ExitCode::OK
+            }
+            Err(e) => {
+                log::error!("Cannot serialize '{}': {}", meta.path.display(), e);
+                self.write_error(format!("kgls: cannot serialize '{}': {}", meta.path.display(), e));
+                ExitCode::MinorIssue
+            }
+        }
+    }
+
+    fn write_json(&mut self, metas: &[Meta]) -> ExitCode {
+        match json::array(metas) {
+            Ok(output) => {
+                self.write_output(output);
+                ExitCode::OK
+            }
+            Err(e) => {
+                log::error!("Cannot serialize entries: {}", e);
+                self.write_error(format!("kgls: cannot serialize entries: {}", e));
+                ExitCode::MinorIssue
+            }
+        }
+    }
+
+    /// Sizes the directories for `--total-size` and the du layout
+    fn disk_usage(&self) -> Option<DiskUsage> {
+        (self.flags.total_size.0 || self.flags.layout == Layout::Du).then(|| DiskUsage::new(&self.flags))
+    }
+
+    /// Git status is only collected when something will show or sort by it
+    fn wants_git_status(&self) -> bool {
+        // `--diff` shows its own statuses in place of git's
+        self.flags.diff.0.is_none()
+            && (self.flags.blocks.0.contains(&Block::GitStatus)
+                || self.flags.sorting.uses(SortColumn::GitStatus)
+                // JSON records always carry the status, whatever the text layout flags
+                || !self.flags.output.is_text())
+    }
+
+    /// The algorithm the walk hashes regular files with, if it does
+    fn wants_hashes(&self) -> Option<HashAlgorithm> {
+        // The duplicate search hashes its candidates itself
+        (self.flags.blocks.0.contains(&Block::Hash) && !self.flags.duplicates.0).then_some(self.flags.hash)
+    }
+
+    /// Whether content is sniffed, and if so whether for every entry or only for the
+    /// extensionless files whose icon and color fall back on it
+    fn wants_magic(&self) -> Option<bool> {
+        if self.flags.blocks.0.contains(&Block::Mime) {
+            Some(true)
+        } else {
+            (self.flags.output.is_text() && (self.icons.is_enabled() || self.colors.matches_contents())).then_some(false)
+        }
+    }
+
+    /// Sort entries and their content by the sort flags
+    pub(crate) fn sort(&self, metas: &mut Vec<Meta>) {
         metas.sort_unstable_by(|a, b| sort::by_meta(&self.sorters, a, b));
 
         for meta in metas {
@@ -359,3 +1087,9 @@ impl Core {
         }
     }
 }
+
+/// An input to list: a path on disk, or an archive read for its members
+enum Input {
+    Path(PathBuf),
+    Archive(Archive),
+}
@@ -1,11 +1,14 @@
 use crate::color::{Colors, Elem};
 use crate::flags::blocks::Block;
-use crate::flags::{Display, Flags, HyperlinkOption, Layout};
+use crate::flags::{Display, Flags, HyperlinkOption, Layout, TimeField};
 use crate::git_theme::GitTheme;
 use crate::icon::Icons;
 use crate::meta::name::DisplayOption;
-use crate::meta::{FileType, Meta, Name, OwnerCache};
+use crate::meta::{DiffStatus, FileType, Meta, Name, OwnerCache, Size};
+use crate::stream::DuplicateSet;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 use term_grid::{Alignment, Cell, Direction, Filling, Grid, GridOptions};
 use terminal_size::terminal_size;
 use unicode_width::UnicodeWidthStr;
@@ -38,6 +41,127 @@ pub fn grid(
     )
 }
 
+/// Renders the blocks of each entry of a directory batch for the streaming accumulators.
+///
+/// Shares the theme state with [Core](crate::core::Core), so batches can be formatted while
+/// the traversal is still running.
+pub struct RowRenderer {
+    flags: Flags,
+    colors: Arc<Colors>,
+    icons: Arc<Icons>,
+    git_theme: Arc<GitTheme>,
+    owner_cache: OwnerCache,
+}
+
+impl RowRenderer {
+    pub fn new(flags: Flags, colors: Arc<Colors>, icons: Arc<Icons>, git_theme: Arc<GitTheme>) -> Self {
+        Self {
+            flags,
+            colors,
+            icons,
+            git_theme,
+            owner_cache: OwnerCache::default(),
+        }
+    }
+
+    pub fn flags(&self) -> &Flags {
+        &self.flags
+    }
+
+    /// The lines printed under the row of an entry: the extended attributes with `-@` and
+    /// the ACL entries with `--acl`
+    pub fn details(&self, meta: &Meta) -> Vec<String> {
+        let mut details = Vec::new();
+        if self.flags.layout != Layout::OneLine {
+            return details;
+        }
+        if let Some(xattrs) = meta.xattrs.as_ref().filter(|_| self.flags.list_xattrs.0) {
+            details.extend(xattrs.render_lines(&self.colors));
+        }
+        if let Some(acl) = meta.access_control.as_ref().and_then(|access| access.acl()).filter(|_| self.flags.list_acl.0) {
+            details.extend(acl.render_lines(&self.colors, &self.owner_cache));
+        }
+        details
+    }
+
+    /// One formatted string per block for every entry, padded against the whole batch
+    pub fn render(&self, metas: &[Meta]) -> Vec<Vec<String>> {
+        self.render_with(metas, &DisplayOption::FileName)
+    }
+
+    /// Like [render](Self::render), naming each entry by its path as it was walked rather
+    /// than by its file name, for entries gathered from several directories
+    pub fn render_paths(&self, metas: &[Meta]) -> Vec<Vec<String>> {
+        self.render_with(metas, &DisplayOption::Relative { base_path: Path::new("") })
+    }
+
+    /// The entries and their content drawn as a [tree]
+    pub fn tree(&self, metas: &[Meta]) -> String {
+        tree(metas, &self.flags, &self.colors, &self.icons, &self.git_theme)
+    }
+
+    fn render_with(&self, metas: &[Meta], display_option: &DisplayOption) -> Vec<Vec<String>> {
+        let padding_rules = get_padding_rules(metas, &self.flags);
+
+        metas
+            .iter()
+            .map(|meta| {
+                get_output(
+                    meta,
+                    &self.owner_cache,
+                    &self.colors,
+                    &self.icons,
+                    &self.git_theme,
+                    &self.flags,
+                    display_option,
+                    &padding_rules,
+                    (0, ""),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Lay out rows produced by a [RowRenderer] as a grid, or one entry per line.
+///
+/// Each row comes with its [details](RowRenderer::details), printed under it in the one-line
+/// layout.
+pub fn rows(rows: Vec<(Vec<String>, Vec<String>)>, flags: &Flags) -> String {
+    let term_width = terminal_size().map(|(w, _)| w.0 as usize);
+    let (rows, details): (Vec<Vec<String>>, Vec<Vec<String>>) = rows.into_iter().unzip();
+    let cells = rows
+        .into_iter()
+        .flatten()
+        .map(|block| Cell {
+            width: get_visible_width(&block, flags.hyperlink == HyperlinkOption::Always),
+            contents: block,
+            alignment: Alignment::Left,
+        })
+        .collect();
+
+    let output = layout_cells(cells, flags, term_width);
+    if flags.layout != Layout::OneLine || details.iter().all(Vec::is_empty) {
+        return output;
+    }
+
+    // One line per row, after the header if there is one
+    let mut lines = output.lines();
+    let mut interleaved = String::with_capacity(output.len());
+    if flags.header.0 && let Some(header) = lines.next() {
+        interleaved.push_str(header);
+        interleaved.push('\n');
+    }
+    for (line, details) in lines.zip(details) {
+        interleaved.push_str(line);
+        interleaved.push('\n');
+        for detail in details {
+            interleaved.push_str(&detail);
+            interleaved.push('\n');
+        }
+    }
+    interleaved
+}
+
 pub fn tree(
     metas: &[Meta],
     flags: &Flags,
@@ -75,7 +199,136 @@ pub fn tree(
         grid.add(cell);
     }
 
-    grid.fit_into_columns(1).to_string()
+    grid.fit_into_columns(flags.blocks.0.len()).to_string()
+}
+
+/// One line of the disk-usage layout
+struct DuLine {
+    size: String,
+    share: f64,
+    name: String,
+}
+
+/// Renders the disk-usage layout: a tree of cumulative sizes, each entry followed by its share
+/// of its parent as a percentage and a bar, largest entries first.
+///
+/// Entries below `--du-threshold` percent of their parent are folded into one line per
+/// directory, when there are at least two of them. Directory sizes are expected to be totals already, see
+/// [DiskUsage](crate::stream::DiskUsage).
+pub fn du(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> String {
+    let term_width = terminal_size().map_or(80, |(w, _)| w.0 as usize);
+
+    let total = metas.iter().map(du_bytes).fold(0, u64::saturating_add);
+    let mut lines = Vec::new();
+    inner_display_du(metas, total, "", true, flags, colors, icons, &mut lines);
+
+    let size_width = lines.iter().map(|line| get_visible_width(&line.size, false)).max().unwrap_or(0);
+    // The percentage takes "100.0%" and the columns are separated by two spaces
+    let bar_width = term_width
+        .saturating_sub(size_width + "100.0%".len() + 6)
+        .saturating_sub(term_width / 2)
+        .clamp(10, 60);
+
+    let mut output = String::new();
+    for line in lines {
+        let padding = size_width - get_visible_width(&line.size, false);
+        output += &format!(
+            "{}{}  {:>5.1}%  {}  {}\n",
+            " ".repeat(padding),
+            line.size,
+            line.share * 100.0,
+            du_bar(line.share, bar_width),
+            line.name
+        );
+    }
+    output
+}
+
+fn du_bytes(meta: &Meta) -> u64 {
+    meta.size.as_ref().map_or(0, Size::get_bytes)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn inner_display_du(
+    metas: &[Meta],
+    parent_bytes: u64,
+    prefix: &str,
+    is_root: bool,
+    flags: &Flags,
+    colors: &Colors,
+    icons: &Icons,
+    lines: &mut Vec<DuLine>,
+) {
+    let share = |bytes: u64| {
+        if parent_bytes == 0 {
+            0.0
+        } else {
+            bytes as f64 / parent_bytes as f64
+        }
+    };
+
+    let mut sorted: Vec<&Meta> = metas.iter().collect();
+    sorted.sort_by_key(|meta| std::cmp::Reverse(du_bytes(meta)));
+    let threshold = f64::from(flags.du_threshold.0) / 100.0;
+    let (mut shown, mut collapsed): (Vec<&Meta>, Vec<&Meta>) = sorted
+        .into_iter()
+        .partition(|meta| is_root || share(du_bytes(meta)) >= threshold);
+    // A single small entry takes no more room than the line it would fold into
+    if collapsed.len() == 1 {
+        shown.append(&mut collapsed);
+    }
+
+    let count = shown.len() + usize::from(!collapsed.is_empty());
+    for (idx, meta) in shown.iter().enumerate() {
+        let is_last = idx + 1 == count;
+        let (current_prefix, child_prefix) = if is_root {
+            (String::new(), String::new())
+        } else if is_last {
+            (format!("{prefix}{CORNER} "), format!("{prefix}{BLANK} "))
+        } else {
+            (format!("{prefix}{EDGE} "), format!("{prefix}{LINE} "))
+        };
+
+        let bytes = du_bytes(meta);
+        let name = meta.name.render(
+            Name::builder(colors, icons, &DisplayOption::FileName)
+                .hyperlink(flags.hyperlink)
+                .literal(flags.literal.0)
+                .cached_canonical(meta.canonical_path.as_ref()),
+        );
+        lines.push(DuLine {
+            size: Size::new(bytes).render(colors, flags, None).to_string(),
+            share: share(bytes),
+            name: format!("{current_prefix}{name}{}", meta.indicator.render(flags)),
+        });
+
+        if let Some(content) = &meta.content {
+            inner_display_du(content, bytes, &child_prefix, false, flags, colors, icons, lines);
+        }
+    }
+
+    if !collapsed.is_empty() {
+        let bytes = collapsed.iter().map(|meta| du_bytes(meta)).fold(0, u64::saturating_add);
+        lines.push(DuLine {
+            size: Size::new(bytes).render(colors, flags, None).to_string(),
+            share: share(bytes),
+            name: format!("{prefix}{CORNER} {} other entries", collapsed.len()),
+        });
+    }
+}
+
+/// A bar of `width` cells filled to `share`, in eighths of a cell
+fn du_bar(share: f64, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '\u{258f}', '\u{258e}', '\u{258d}', '\u{258c}', '\u{258b}', '\u{258a}', '\u{2589}'];
+
+    let eighths = (share.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let full = eighths / 8;
+    let mut bar = "\u{2588}".repeat(full);
+    if full < width {
+        bar.push(PARTIAL[eighths % 8]);
+        bar.push_str(&" ".repeat(width - full - 1));
+    }
+    bar
 }
 
 #[allow(clippy::too_many_arguments)] // should wrap flags, colors, icons, git_theme into one struct
@@ -90,20 +343,9 @@ fn inner_display_grid(
     depth: usize,
     term_width: Option<usize>,
 ) -> String {
-    let mut output = String::new();
     let mut cells = Vec::new();
 
     let padding_rules = get_padding_rules(metas, flags);
-    let mut grid = match flags.layout {
-        Layout::OneLine => Grid::new(GridOptions {
-            filling: Filling::Spaces(2),
-            direction: Direction::LeftToRight,
-        }),
-        _ => Grid::new(GridOptions {
-            filling: Filling::Spaces(2),
-            direction: Direction::TopToBottom,
-        }),
-    };
 
     // The first iteration (depth == 0) corresponds to the inputs given by the
     // user. We defer displaying directories given by the user unless we've been
@@ -142,31 +384,7 @@ fn inner_display_grid(
         }
     }
 
-    // Print block headers
-    if flags.header.0 && flags.layout == Layout::OneLine && !cells.is_empty() {
-        add_header(flags, &cells, &mut grid);
-    }
-
-    for cell in cells {
-        grid.add(cell);
-    }
-
-    if flags.layout == Layout::Grid {
-        if let Some(tw) = term_width {
-            if let Some(gridded_output) = grid.fit_into_width(tw) {
-                output += &gridded_output.to_string();
-            } else {
-                //does not fit into grid, usually because (some) filename(s)
-                //are longer or almost as long as term_width
-                //print line by line instead!
-                output += &grid.fit_into_columns(1).to_string();
-            }
-        } else {
-            output += &grid.fit_into_columns(1).to_string();
-        }
-    } else {
-        output += &grid.fit_into_columns(flags.blocks.0.len()).to_string();
-    }
+    let mut output = layout_cells(cells, flags, term_width);
 
     let should_display_folder_path = should_display_folder_path(depth, metas);
 
@@ -198,6 +416,45 @@ fn inner_display_grid(
     output
 }
 
+fn layout_cells(cells: Vec<Cell>, flags: &Flags, term_width: Option<usize>) -> String {
+    let mut grid = match flags.layout {
+        Layout::OneLine => Grid::new(GridOptions {
+            filling: Filling::Spaces(2),
+            direction: Direction::LeftToRight,
+        }),
+        _ => Grid::new(GridOptions {
+            filling: Filling::Spaces(2),
+            direction: Direction::TopToBottom,
+        }),
+    };
+
+    // Print block headers
+    if flags.header.0 && flags.layout == Layout::OneLine && !cells.is_empty() {
+        add_header(flags, &cells, &mut grid);
+    }
+
+    for cell in cells {
+        grid.add(cell);
+    }
+
+    if flags.layout == Layout::Grid {
+        if let Some(tw) = term_width {
+            if let Some(gridded_output) = grid.fit_into_width(tw) {
+                gridded_output.to_string()
+            } else {
+                //does not fit into grid, usually because (some) filename(s)
+                //are longer or almost as long as term_width
+                //print line by line instead!
+                grid.fit_into_columns(1).to_string()
+            }
+        } else {
+            grid.fit_into_columns(1).to_string()
+        }
+    } else {
+        grid.fit_into_columns(flags.blocks.0.len()).to_string()
+    }
+}
+
 fn add_header(flags: &Flags, cells: &[Cell], grid: &mut Grid) {
     let num_columns: usize = flags.blocks.0.len();
 
@@ -205,7 +462,7 @@ fn add_header(flags: &Flags, cells: &[Cell], grid: &mut Grid) {
         .blocks
         .0
         .iter()
-        .map(|b| get_visible_width(b.get_header(), flags.hyperlink == HyperlinkOption::Always))
+        .map(|b| get_visible_width(b.get_header(flags.time), flags.hyperlink == HyperlinkOption::Always))
         .collect::<Vec<usize>>();
 
     // find max widths of each column
@@ -217,7 +474,7 @@ fn add_header(flags: &Flags, cells: &[Cell], grid: &mut Grid) {
     for (idx, block) in flags.blocks.0.iter().enumerate() {
         // center and underline header
         let underlined_header = crossterm::style::Stylize::attribute(
-            format!("{: ^1$}", block.get_header(), widths[idx]),
+            format!("{: ^1$}", block.get_header(flags.time), widths[idx]),
             crossterm::style::Attribute::Underlined,
         )
         .to_string();
@@ -321,7 +578,74 @@ fn should_display_folder_path(depth: usize, metas: &[Meta]) -> bool {
 }
 
 fn display_folder_path(meta: &Meta) -> String {
-    format!("\n{}:\n", meta.path.to_string_lossy())
+    format!("\n{}", directory_header(&meta.path))
+}
+
+/// The `path:` line introducing a directory's listing
+pub fn directory_header(path: &Path) -> String {
+    format!("{}:\n", path.to_string_lossy())
+}
+
+/// The counts and size line printed after a directory's listing with `--summary`
+pub fn summary(
+    file_count: usize,
+    dir_count: usize,
+    total_size: u64,
+    flags: &Flags,
+    colors: &Colors,
+) -> String {
+    let plural = |count: usize, one: &str, many: &str| {
+        format!("{count} {}", if count == 1 { one } else { many })
+    };
+
+    format!(
+        "{}, {}, {}\n",
+        plural(file_count, "file", "files"),
+        plural(dir_count, "directory", "directories"),
+        Size::new(total_size).render(colors, flags, None)
+    )
+}
+
+/// The line naming a set of identical files for `--duplicates`
+pub fn duplicate_header(set: &DuplicateSet, flags: &Flags, colors: &Colors) -> String {
+    let hard_links = set.entries.len() - set.copies();
+    let linked = match hard_links {
+        0 => String::new(),
+        1 => ", 1 hard link".to_string(),
+        count => format!(", {count} hard links"),
+    };
+    format!(
+        "{} copies of {}, {} wasted{linked}\n",
+        set.copies(),
+        Size::new(set.size).render(colors, flags, None),
+        Size::new(set.wasted()).render(colors, flags, None)
+    )
+}
+
+/// The line closing a `--duplicates` listing with the space all the sets waste
+pub fn duplicates_total(sets: &[DuplicateSet], flags: &Flags, colors: &Colors) -> String {
+    let wasted = sets.iter().map(DuplicateSet::wasted).sum();
+    format!(
+        "{} {}, {} wasted\n",
+        sets.len(),
+        if sets.len() == 1 { "duplicate set" } else { "duplicate sets" },
+        Size::new(wasted).render(colors, flags, None)
+    )
+}
+
+/// The counts line printed after a `--diff` tree with `--summary`
+pub fn diff_summary(statuses: impl Iterator<Item = DiffStatus>) -> String {
+    let (mut added, mut removed, mut modified, mut type_changed) = (0, 0, 0, 0);
+    for status in statuses {
+        match status {
+            DiffStatus::Added => added += 1,
+            DiffStatus::Removed => removed += 1,
+            DiffStatus::Modified => modified += 1,
+            DiffStatus::TypeChanged => type_changed += 1,
+            DiffStatus::Unchanged => {}
+        }
+    }
+    format!("{added} added, {removed} removed, {modified} modified, {type_changed} type changed\n")
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -330,7 +654,7 @@ fn get_output(
     owner_cache: &OwnerCache,
     colors: &Colors,
     icons: &Icons,
-    _git_theme: &GitTheme,
+    git_theme: &GitTheme,
     flags: &Flags,
     display_option: &DisplayOption,
     padding_rules: &HashMap<Block, usize>,
@@ -338,6 +662,10 @@ fn get_output(
 ) -> Vec<String> {
     let mut strings: Vec<String> = Vec::new();
     let colorize_missing = |string: &str| colors.colorize(string, &Elem::NoAccess);
+    let render_timestamp = |field: TimeField| match &meta.timestamps {
+        Some(timestamps) => timestamps.get(field).render(colors, flags),
+        None => colorize_missing("?"),
+    };
 
     for (i, block) in flags.blocks.0.iter().enumerate() {
         let mut block_vec = if Layout::Tree == flags.layout && tree.0 == i {
@@ -397,10 +725,19 @@ fn get_output(
                 Some(size) => size.render_value(colors, flags),
                 None => colorize_missing("?"),
             }),
+            Block::Blocks => block_vec.push(
+                meta.size
+                    .as_ref()
+                    .and_then(|size| size.render_blocks(colors, flags))
+                    .unwrap_or_else(|| colorize_missing("?")),
+            ),
             Block::Date => block_vec.push(match &meta.date {
                 Some(date) => date.render(colors, flags),
                 None => colorize_missing("?"),
             }),
+            Block::Atime => block_vec.push(render_timestamp(TimeField::Access)),
+            Block::Ctime => block_vec.push(render_timestamp(TimeField::Change)),
+            Block::Btime => block_vec.push(render_timestamp(TimeField::Birth)),
             Block::Name => {
                 block_vec.extend([
                     meta.name.render(
@@ -409,6 +746,7 @@ fn get_output(
                             .literal(flags.literal.0)
                             .git_status(meta.git_status.as_ref())
                             .cached_canonical(meta.canonical_path.as_ref())
+                            .mount_point(meta.mount_point)
                     ),
                     meta.indicator.render(flags),
                 ]);
@@ -416,11 +754,36 @@ fn get_output(
                     block_vec.push(meta.symlink.render(colors, flags))
                 }
             }
+            Block::Xattrs => block_vec.push(match &meta.xattrs {
+                Some(xattrs) => xattrs.render_count(colors),
+                None => colorize_missing("?"),
+            }),
+            Block::Caps => block_vec.push(match &meta.capabilities {
+                Some(capabilities) => capabilities.render(colors),
+                None => colorize_missing("?"),
+            }),
+            Block::Flags => block_vec.push(match &meta.inode_flags {
+                Some(inode_flags) => inode_flags.render(colors),
+                None => colorize_missing("????"),
+            }),
+            Block::FileSystem => block_vec.push(match &meta.filesystem {
+                Some(filesystem) => filesystem.render(colors),
+                None => colorize_missing("?"),
+            }),
+            Block::Mime => block_vec.push(match &meta.magic {
+                Some(magic) => magic.render(colors),
+                None => colorize_missing("?"),
+            }),
+            Block::Hash => block_vec.push(match &meta.digest {
+                Some(digest) => digest.render(colors),
+                None if matches!(meta.file_type, FileType::File { .. }) => colorize_missing("?"),
+                None => colorize_missing("-"),
+            }),
             Block::GitStatus => {
-                // Git status is now shown through file/icon colors, not as a separate block
-                // if let Some(_s) = &meta.git_status {
-                //     block_vec.push(_s.render(colors, git_theme));
-                // }
+                // Clean entries render empty; the name colors already carry the status
+                if let Some(s) = &meta.git_status {
+                    block_vec.push(s.render(colors, git_theme));
+                }
             }
         }
         strings.push(
@@ -1,50 +1,95 @@
+pub mod allocated_size;
+pub mod block_size;
 pub mod blocks;
+pub mod checksum;
 pub mod color;
 pub mod date;
 pub mod dereference;
 pub mod display;
+pub mod diff;
+pub mod du_threshold;
+pub mod duplicates;
+pub mod filters;
+pub mod git_ignore;
+pub mod hash_algorithm;
 pub mod header;
 pub mod hyperlink;
 pub mod icons;
+pub mod ignore_files;
 pub mod ignore_globs;
 pub mod indicators;
+pub mod interactive;
 pub mod layout;
+pub mod list_acl;
+pub mod list_xattrs;
 pub mod literal;
+pub mod one_file_system;
+pub mod output;
 pub mod permission;
 pub mod recursion;
+pub mod reuse_hashes;
+pub mod rev;
+pub mod rev_diff;
 pub mod size;
+pub mod snapshot;
 pub mod sorting;
+pub mod summary;
 pub mod symlink_arrow;
 pub mod symlinks;
+pub mod time_field;
 pub mod total_size;
 pub mod truncate_owner;
+pub mod watch;
 
+pub use allocated_size::AllocatedSize;
+pub use block_size::BlockSize;
 pub use blocks::Blocks;
+pub use checksum::Checksum;
 pub use color::Color;
 pub use color::{ColorOption, ThemeOption};
 pub use date::DateFlag;
 pub use dereference::Dereference;
 pub use display::Display;
+pub use diff::Diff;
+pub use du_threshold::DuThreshold;
+pub use duplicates::Duplicates;
+pub use filters::Filters;
+pub use git_ignore::GitIgnore;
+pub use hash_algorithm::HashAlgorithm;
 pub use header::Header;
 pub use hyperlink::HyperlinkOption;
 pub use icons::IconOption;
 pub use icons::IconTheme;
 pub use icons::Icons;
+pub use ignore_files::IgnoreFiles;
 pub use ignore_globs::IgnoreGlobs;
 pub use indicators::Indicators;
+pub use interactive::Interactive;
 pub use layout::Layout;
+pub use list_acl::ListAcl;
+pub use list_xattrs::ListXattrs;
 pub use literal::Literal;
+pub use one_file_system::OneFileSystem;
+pub use output::OutputFormat;
 pub use permission::PermissionFlag;
 pub use recursion::Recursion;
+pub use reuse_hashes::ReuseHashes;
+pub use rev::Rev;
+pub use rev_diff::RevDiff;
 pub use size::SizeFlag;
+pub use snapshot::Snapshot;
 pub use sorting::DirGrouping;
 pub use sorting::SortColumn;
+pub use sorting::SortKey;
 pub use sorting::SortOrder;
 pub use sorting::Sorting;
+pub use summary::Summary;
 pub use symlink_arrow::SymlinkArrow;
 pub use symlinks::NoSymlink;
+pub use time_field::TimeField;
 pub use total_size::TotalSize;
 pub use truncate_owner::TruncateOwner;
+pub use watch::Watch;
 
 use crate::app::Cli;
 use crate::config_file::Config;
@@ -60,23 +105,44 @@ pub struct Flags {
     pub blocks: Blocks,
     pub color: Color,
     pub date: DateFlag,
+    pub time: TimeField,
     pub dereference: Dereference,
     pub display: Display,
     pub display_indicators: Indicators,
     pub icons: Icons,
     pub ignore_globs: IgnoreGlobs,
+    pub git_ignore: GitIgnore,
+    pub ignore_files: IgnoreFiles,
+    pub filters: Filters,
     pub layout: Layout,
+    pub list_xattrs: ListXattrs,
+    pub list_acl: ListAcl,
+    pub hash: HashAlgorithm,
+    pub reuse_hashes: ReuseHashes,
+    pub diff: Diff,
+    pub checksum: Checksum,
+    pub snapshot: Snapshot,
+    pub watch: Watch,
+    pub interactive: Interactive,
+    pub rev: Rev,
+    pub rev_diff: RevDiff,
+    pub du_threshold: DuThreshold,
+    pub duplicates: Duplicates,
     pub no_symlink: NoSymlink,
     pub recursion: Recursion,
+    pub one_file_system: OneFileSystem,
     pub size: SizeFlag,
+    pub allocated_size: AllocatedSize,
+    pub block_size: BlockSize,
     pub permission: PermissionFlag,
     pub sorting: Sorting,
-    #[allow(dead_code)] // Planned for integration with streaming
     pub total_size: TotalSize,
     pub symlink_arrow: SymlinkArrow,
     pub hyperlink: HyperlinkOption,
     pub header: Header,
+    pub summary: Summary,
     pub literal: Literal,
+    pub output: OutputFormat,
     pub truncate_owner: TruncateOwner,
     #[allow(dead_code)] // Planned for integration with streaming
     pub git: bool,
@@ -87,29 +153,52 @@ impl Flags {
     ///
     /// # Errors
     ///
-    /// This can return an [Error], when either the building of the ignore globs or the parsing of
-    /// the recursion depth parameter fails.
+    /// This can return an [Error], when either the building of the ignore globs, the parsing of
+    /// the filters, the parsing of the sort keys or the parsing of the recursion depth parameter
+    /// fails.
     pub fn configure_from(cli: &Cli, config: &Config) -> Result<Self, Error> {
         Ok(Self {
             blocks: Blocks::configure_from(cli, config),
             color: Color::configure_from(cli, config),
             date: DateFlag::configure_from(cli, config),
+            time: TimeField::configure_from(cli, config),
             dereference: Dereference::configure_from(cli, config),
             display: Display::configure_from(cli, config),
             layout: Layout::configure_from(cli, config),
+            list_xattrs: ListXattrs::configure_from(cli, config),
+            list_acl: ListAcl::configure_from(cli, config),
+            hash: HashAlgorithm::configure_from(cli, config),
+            reuse_hashes: ReuseHashes::configure_from(cli, config),
+            diff: Diff::configure_from(cli, config),
+            checksum: Checksum::configure_from(cli, config),
+            snapshot: Snapshot::configure_from(cli, config),
+            watch: Watch::configure_from(cli, config),
+            interactive: Interactive::configure_from(cli, config),
+            rev: Rev::configure_from(cli, config),
+            rev_diff: RevDiff::configure_from(cli, config),
+            du_threshold: DuThreshold::configure_from(cli, config),
+            duplicates: Duplicates::configure_from(cli, config),
             size: SizeFlag::configure_from(cli, config),
+            allocated_size: AllocatedSize::configure_from(cli, config),
+            block_size: BlockSize::configure_from(cli, config),
             permission: PermissionFlag::configure_from(cli, config),
             display_indicators: Indicators::configure_from(cli, config),
             icons: Icons::configure_from(cli, config),
             ignore_globs: IgnoreGlobs::configure_from(cli, config)?,
+            git_ignore: GitIgnore::configure_from(cli, config),
+            ignore_files: IgnoreFiles::configure_from(cli, config),
+            filters: Filters::configure_from(cli)?,
             no_symlink: NoSymlink::configure_from(cli, config),
             recursion: Recursion::configure_from(cli, config),
-            sorting: Sorting::configure_from(cli, config),
+            one_file_system: OneFileSystem::configure_from(cli, config),
+            sorting: Sorting::configure_from(cli, config)?,
             total_size: TotalSize::configure_from(cli, config),
             symlink_arrow: SymlinkArrow::configure_from(cli, config),
             hyperlink: HyperlinkOption::configure_from(cli, config),
             header: Header::configure_from(cli, config),
+            summary: Summary::configure_from(cli, config),
             literal: Literal::configure_from(cli, config),
+            output: OutputFormat::configure_from(cli, config),
             truncate_owner: TruncateOwner::configure_from(cli, config),
             git: cli.git,
         })
@@ -0,0 +1,35 @@
+//! This module defines the [AllocatedSize] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether sizes are the space allocated on disk rather than the apparent
+/// length of the files.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct AllocatedSize(pub bool);
+
+impl Configurable<Self> for AllocatedSize {
+    /// Get a potential `AllocatedSize` value from [Cli].
+    ///
+    /// If the "disk-usage" argument is passed, this returns an `AllocatedSize` with value `true`
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.disk_usage {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `AllocatedSize` value from a [Config].
+    ///
+    /// If the `Config::disk-usage` has value,
+    /// this returns it as the value of the `AllocatedSize`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.disk_usage.map(Self)
+    }
+}
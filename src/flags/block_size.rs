@@ -0,0 +1,59 @@
+//! This module defines the [BlockSize] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+use serde::Deserialize;
+
+/// The flag showing the unit sizes and allocated blocks are counted in.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize, Default)]
+pub enum BlockSize {
+    /// Sizes pick their own power of 1024, blocks count KiB.
+    #[default]
+    #[serde(skip)]
+    Default,
+    #[serde(rename = "K")]
+    Kilo,
+    #[serde(rename = "M")]
+    Mega,
+    #[serde(rename = "G")]
+    Giga,
+    /// Like the default, in powers of 1000.
+    #[serde(rename = "SI")]
+    Si,
+}
+
+impl BlockSize {
+    pub fn from_arg_str(value: &str) -> Self {
+        match value {
+            "K" => Self::Kilo,
+            "M" => Self::Mega,
+            "G" => Self::Giga,
+            "SI" => Self::Si,
+            // Invalid value should be handled by `clap` when building an `Cli`
+            other => unreachable!("Invalid value '{other}' for 'block-size'"),
+        }
+    }
+}
+
+impl Configurable<Self> for BlockSize {
+    /// Get a potential `BlockSize` variant from [Cli].
+    ///
+    /// If the "block-size" argument is passed, this returns the corresponding `BlockSize`
+    /// variant in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        cli.block_size.as_deref().map(Self::from_arg_str)
+    }
+
+    /// Get a potential `BlockSize` variant from a [Config].
+    ///
+    /// If the `Config::block-size` has value and is one of "K", "M", "G" or "SI",
+    /// this returns the corresponding `BlockSize` variant in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.block_size
+    }
+}
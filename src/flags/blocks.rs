@@ -1,4 +1,5 @@
 use super::Configurable;
+use super::TimeField;
 use crate::app::Cli;
 use crate::config_file::Config;
 
@@ -200,15 +201,34 @@ pub enum Block {
     Context,
     Size,
     SizeValue,
+    /// The space allocated to the entry itself, like `ls -s`
+    Blocks,
+    /// The timestamp picked by `--time`
     Date,
+    Atime,
+    Ctime,
+    Btime,
     Name,
     INode,
     Links,
     GitStatus,
+    /// The number of extended attributes
+    Xattrs,
+    /// The file capabilities, like `getcap`
+    Caps,
+    /// The inode flags set by `chattr`, like `lsattr`
+    Flags,
+    /// The type of the file system holding the entry
+    FileSystem,
+    /// The MIME type sniffed from the content
+    Mime,
+    /// A digest of the content of regular files
+    Hash,
 }
 
 impl Block {
-    pub fn get_header(&self) -> &'static str {
+    /// The column title, where the one of [Date](Block::Date) names the timestamp it shows.
+    pub fn get_header(&self, time: TimeField) -> &'static str {
         match self {
             Block::INode => "INode",
             Block::Links => "Links",
@@ -218,9 +238,24 @@ impl Block {
             Block::Context => "Context",
             Block::Size => "Size",
             Block::SizeValue => "SizeValue",
-            Block::Date => "Date Modified",
+            Block::Blocks => "Blocks",
+            Block::Date => match time {
+                TimeField::Modified => "Date Modified",
+                TimeField::Access => Block::Atime.get_header(time),
+                TimeField::Change => Block::Ctime.get_header(time),
+                TimeField::Birth => Block::Btime.get_header(time),
+            },
+            Block::Atime => "Date Accessed",
+            Block::Ctime => "Date Changed",
+            Block::Btime => "Date Created",
             Block::Name => "Name",
             Block::GitStatus => "Git",
+            Block::Xattrs => "Xattrs",
+            Block::Caps => "Capabilities",
+            Block::Flags => "Flags",
+            Block::FileSystem => "FS",
+            Block::Mime => "MIME Type",
+            Block::Hash => "Hash",
         }
     }
 }
@@ -236,11 +271,21 @@ impl TryFrom<&str> for Block {
             "context" => Ok(Self::Context),
             "size" => Ok(Self::Size),
             "size_value" => Ok(Self::SizeValue),
+            "blocks" => Ok(Self::Blocks),
             "date" => Ok(Self::Date),
+            "atime" => Ok(Self::Atime),
+            "ctime" => Ok(Self::Ctime),
+            "btime" => Ok(Self::Btime),
             "name" => Ok(Self::Name),
             "inode" => Ok(Self::INode),
             "links" => Ok(Self::Links),
             "git" => Ok(Self::GitStatus),
+            "xattrs" => Ok(Self::Xattrs),
+            "caps" => Ok(Self::Caps),
+            "flags" => Ok(Self::Flags),
+            "fs" => Ok(Self::FileSystem),
+            "mime" => Ok(Self::Mime),
+            "hash" => Ok(Self::Hash),
             _ => Err(format!("Not a valid block name: {string}")),
         }
     }
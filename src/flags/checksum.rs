@@ -0,0 +1,35 @@
+//! This module defines the [Checksum] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether `--diff` and `--since-snapshot` compare the content of files of
+/// the same size, rather than their modification time, and whether snapshots keep digests.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Checksum(pub bool);
+
+impl Configurable<Self> for Checksum {
+    /// Get a potential `Checksum` value from [Cli].
+    ///
+    /// If the "checksum" argument is passed, this returns a `Checksum` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.checksum {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `Checksum` value from a [Config].
+    ///
+    /// If the `Config::checksum` has value,
+    /// this returns it as the value of the `Checksum`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.checksum.map(Self)
+    }
+}
@@ -0,0 +1,31 @@
+//! This module defines the [Diff] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use std::path::PathBuf;
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag holding the old and the new tree to compare, instead of listing the inputs.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Diff(pub Option<(PathBuf, PathBuf)>);
+
+impl Configurable<Self> for Diff {
+    /// Get a potential `Diff` value from [Cli].
+    ///
+    /// If the "diff" argument is passed with its two paths, this returns a `Diff` holding them
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        match cli.diff.as_deref() {
+            Some([old, new]) => Some(Self(Some((old.clone(), new.clone())))),
+            _ => None,
+        }
+    }
+
+    /// The trees to compare are only given on the command line, so this always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
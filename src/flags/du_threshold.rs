@@ -0,0 +1,39 @@
+//! This module defines the [DuThreshold] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag holding the share of its parent, in percent, below which an entry of the
+/// disk-usage layout is collapsed, with the others of its directory, into an "other entries"
+/// line.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub struct DuThreshold(pub u8);
+
+impl Configurable<Self> for DuThreshold {
+    /// Get a potential `DuThreshold` value from [Cli].
+    ///
+    /// If the "du-threshold" argument is passed, this returns a `DuThreshold` with its value
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        cli.du_threshold.map(Self)
+    }
+
+    /// Get a potential `DuThreshold` value from a [Config].
+    ///
+    /// If the `Config::du-threshold` has value,
+    /// this returns it as the value of the `DuThreshold`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.du_threshold.map(Self)
+    }
+}
+
+/// The default value for `DuThreshold` is 1 percent.
+impl Default for DuThreshold {
+    fn default() -> Self {
+        Self(1)
+    }
+}
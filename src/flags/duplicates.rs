@@ -0,0 +1,32 @@
+//! This module defines the [Duplicates] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether to list the sets of regular files with identical content found
+/// below the inputs, instead of the inputs themselves.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Duplicates(pub bool);
+
+impl Configurable<Self> for Duplicates {
+    /// Get a potential `Duplicates` value from [Cli].
+    ///
+    /// If the "duplicates" argument is passed, this returns a `Duplicates` with value `true`
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.duplicates {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// A search is asked for on the command line, so the configuration file has no say and
+    /// this always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
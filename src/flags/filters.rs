@@ -0,0 +1,224 @@
+//! This module defines the [Filters]. To set them up from [Cli], use the
+//! [configure_from](Filters::configure_from) method. They are only taken from the command line,
+//! as they describe one search rather than a listing preference.
+
+use crate::app::Cli;
+use crate::meta::FileType;
+
+use clap::error::ErrorKind;
+use clap::Error;
+use std::fs::Metadata;
+use std::time::{Duration, SystemTime};
+
+/// A file type accepted by `--type`, named like the `find -type` letters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeFilter {
+    File,
+    Directory,
+    SymLink,
+    Pipe,
+    Socket,
+    BlockDevice,
+    CharDevice,
+}
+
+impl TypeFilter {
+    pub fn from_arg_str(value: &str) -> Self {
+        match value {
+            "f" => Self::File,
+            "d" => Self::Directory,
+            "l" => Self::SymLink,
+            "p" => Self::Pipe,
+            "s" => Self::Socket,
+            "b" => Self::BlockDevice,
+            "c" => Self::CharDevice,
+            // Invalid value should be handled by `clap` when building an `Cli`
+            other => unreachable!("Invalid value '{other}' for 'type'"),
+        }
+    }
+
+    pub fn matches(self, file_type: FileType) -> bool {
+        matches!(
+            (self, file_type),
+            (Self::File, FileType::File { .. })
+                | (Self::Directory, FileType::Directory { .. })
+                | (Self::SymLink, FileType::SymLink { .. })
+                | (Self::Pipe, FileType::Pipe)
+                | (Self::Socket, FileType::Socket)
+                | (Self::BlockDevice, FileType::BlockDevice)
+                | (Self::CharDevice, FileType::CharDevice)
+        )
+    }
+}
+
+/// The find-like predicates an entry has to pass to be listed. Unset ones accept everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filters {
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// Only entries modified after this time
+    pub newer: Option<SystemTime>,
+    /// Only entries modified before this time
+    pub older: Option<SystemTime>,
+    /// Accepted file types, any of them when empty
+    pub types: Vec<TypeFilter>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+}
+
+impl Filters {
+    /// Get the `Filters` from [Cli].
+    ///
+    /// # Errors
+    ///
+    /// If a size or a time cannot be parsed, a reference file cannot be read, or a user or
+    /// group does not exist.
+    pub fn configure_from(cli: &Cli) -> Result<Self, Error> {
+        Ok(Self {
+            min_size: cli.min_size.as_deref().map(Self::size_from).transpose()?,
+            max_size: cli.max_size.as_deref().map(Self::size_from).transpose()?,
+            newer: cli.newer.as_deref().map(Self::time_from).transpose()?,
+            older: cli.older.as_deref().map(Self::time_from).transpose()?,
+            types: cli.file_type.iter().map(|value| TypeFilter::from_arg_str(value)).collect(),
+            uid: cli.user.as_deref().map(Self::uid_from).transpose()?,
+            gid: cli.group.as_deref().map(Self::gid_from).transpose()?,
+        })
+    }
+
+    /// Whether any predicate is set
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Whether an entry passes every predicate
+    pub fn matches(&self, metadata: &Metadata, file_type: FileType) -> bool {
+        if !self.types.is_empty() && !self.types.iter().any(|kind| kind.matches(file_type)) {
+            return false;
+        }
+
+        let len = metadata.len();
+        if self.min_size.is_some_and(|min| len < min) || self.max_size.is_some_and(|max| len > max) {
+            return false;
+        }
+
+        if self.newer.is_some() || self.older.is_some() {
+            let Ok(modified) = metadata.modified() else {
+                return false;
+            };
+            if self.newer.is_some_and(|newer| modified <= newer)
+                || self.older.is_some_and(|older| modified >= older)
+            {
+                return false;
+            }
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            if self.uid.is_some_and(|uid| metadata.uid() != uid)
+                || self.gid.is_some_and(|gid| metadata.gid() != gid)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Parse a size such as `512`, `10K`, `1.5M` or `2GiB`, in powers of 1024.
+    pub fn size_from(value: &str) -> Result<u64, Error> {
+        let invalid = || {
+            Error::raw(
+                ErrorKind::ValueValidation,
+                format!("invalid size '{value}', expected a number with an optional K, M, G or T unit\n"),
+            )
+        };
+
+        let upper = value.trim().to_ascii_uppercase();
+        let digits = upper.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let factor: u64 = match upper[digits.len()..].trim_end_matches("IB").trim_end_matches('B') {
+            "" => 1,
+            "K" => 1 << 10,
+            "M" => 1 << 20,
+            "G" => 1 << 30,
+            "T" => 1 << 40,
+            _ => return Err(invalid()),
+        };
+        let number: f64 = digits.trim().parse().map_err(|_| invalid())?;
+        if !number.is_finite() || number < 0.0 {
+            return Err(invalid());
+        }
+        Ok((number * factor as f64).round() as u64)
+    }
+
+    /// Parse a duration such as `45s`, `30m`, `3h`, `2d`, `1w` or `1d12h`.
+    pub fn duration_from(value: &str) -> Option<Duration> {
+        let mut seconds: u64 = 0;
+        let mut number = String::new();
+        for c in value.trim().chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let unit = match c {
+                's' => 1,
+                'm' => 60,
+                'h' => 60 * 60,
+                'd' => 24 * 60 * 60,
+                'w' => 7 * 24 * 60 * 60,
+                _ => return None,
+            };
+            let count: u64 = number.parse().ok()?;
+            seconds = seconds.checked_add(count.checked_mul(unit)?)?;
+            number.clear();
+        }
+        (number.is_empty() && seconds > 0).then(|| Duration::from_secs(seconds))
+    }
+
+    /// A point in time given as a duration before now, or as the modification time of a file.
+    fn time_from(value: &str) -> Result<SystemTime, Error> {
+        if let Some(duration) = Self::duration_from(value) {
+            return SystemTime::now().checked_sub(duration).ok_or_else(|| {
+                Error::raw(ErrorKind::ValueValidation, format!("duration '{value}' is too long\n"))
+            });
+        }
+
+        std::fs::metadata(value)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|err| {
+                Error::raw(
+                    ErrorKind::ValueValidation,
+                    format!("'{value}' is neither a duration (like 2d or 3h) nor a readable file: {err}\n"),
+                )
+            })
+    }
+
+    #[cfg(unix)]
+    fn uid_from(value: &str) -> Result<u32, Error> {
+        value
+            .parse()
+            .ok()
+            .or_else(|| users::get_user_by_name(value).map(|user| user.uid()))
+            .ok_or_else(|| Error::raw(ErrorKind::ValueValidation, format!("unknown user '{value}'\n")))
+    }
+
+    #[cfg(unix)]
+    fn gid_from(value: &str) -> Result<u32, Error> {
+        value
+            .parse()
+            .ok()
+            .or_else(|| users::get_group_by_name(value).map(|group| group.gid()))
+            .ok_or_else(|| Error::raw(ErrorKind::ValueValidation, format!("unknown group '{value}'\n")))
+    }
+
+    #[cfg(not(unix))]
+    fn uid_from(_value: &str) -> Result<u32, Error> {
+        Err(Error::raw(ErrorKind::ValueValidation, "--user is only supported on unix\n"))
+    }
+
+    #[cfg(not(unix))]
+    fn gid_from(_value: &str) -> Result<u32, Error> {
+        Err(Error::raw(ErrorKind::ValueValidation, "--group is only supported on unix\n"))
+    }
+}
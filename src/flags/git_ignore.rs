@@ -0,0 +1,34 @@
+//! This module defines the [GitIgnore] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether to hide entries ignored by `.gitignore` files.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct GitIgnore(pub bool);
+
+impl Configurable<Self> for GitIgnore {
+    /// Get a potential `GitIgnore` value from [Cli].
+    ///
+    /// If the "git-ignore" argument is passed, this returns a `GitIgnore` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.git_ignore {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `GitIgnore` value from a [Config].
+    ///
+    /// If the `Config::git-ignore` has value,
+    /// this returns it as the value of the `GitIgnore`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.git_ignore.map(Self)
+    }
+}
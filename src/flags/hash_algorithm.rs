@@ -0,0 +1,55 @@
+//! This module defines the [HashAlgorithm] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+use serde::{Deserialize, Serialize};
+
+/// The flag showing which digest the hash block computes.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    /// The 128 bit XXH3, much faster but not cryptographic
+    #[default]
+    Xxh3,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    fn from_arg_str(value: &str) -> Self {
+        match value {
+            "xxh3" => Self::Xxh3,
+            "blake3" => Self::Blake3,
+            // Invalid value should be handled by `clap` when building an `Cli`
+            other => unreachable!("Invalid value '{other}' for 'hash'"),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Xxh3 => "xxh3",
+            Self::Blake3 => "blake3",
+        }
+    }
+}
+
+impl Configurable<Self> for HashAlgorithm {
+    /// Get a potential `HashAlgorithm` variant from [Cli].
+    ///
+    /// If the "hash" argument is passed, this returns the corresponding `HashAlgorithm`
+    /// variant in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        cli.hash.as_deref().map(Self::from_arg_str)
+    }
+
+    /// Get a potential `HashAlgorithm` variant from a [Config].
+    ///
+    /// If the `Config::hash` has value, this returns it in a [Some]. Otherwise this returns
+    /// [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.hash
+    }
+}
@@ -0,0 +1,34 @@
+//! This module defines the [IgnoreFiles] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether to hide entries ignored by `.ignore` and `.kglsignore` files.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct IgnoreFiles(pub bool);
+
+impl Configurable<Self> for IgnoreFiles {
+    /// Get a potential `IgnoreFiles` value from [Cli].
+    ///
+    /// If the "ignore-files" argument is passed, this returns a `IgnoreFiles` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.ignore_files {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `IgnoreFiles` value from a [Config].
+    ///
+    /// If the `Config::ignore-files` has value,
+    /// this returns it as the value of the `IgnoreFiles`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.ignore_files.map(Self)
+    }
+}
@@ -0,0 +1,31 @@
+//! This module defines the [Interactive] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether the inputs are browsed on the terminal instead of listed.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Interactive(pub bool);
+
+impl Configurable<Self> for Interactive {
+    /// Get a potential `Interactive` value from [Cli].
+    ///
+    /// If the "interactive" argument is passed, this returns an `Interactive` with value `true`
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.interactive {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Browsing is asked for on the command line, so the configuration file has no say and
+    /// this always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
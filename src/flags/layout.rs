@@ -13,17 +13,21 @@ pub enum Layout {
     Grid,
     Tree,
     OneLine,
+    /// A tree of cumulative sizes with proportional bars, largest entries first
+    Du,
 }
 
 impl Configurable<Layout> for Layout {
     /// Get a potential `Layout` variant from [Cli].
     ///
-    /// If any of the "tree", "long" or "oneline" arguments is passed, this returns the
+    /// If any of the "du", "tree", "long" or "oneline" arguments is passed, this returns the
     /// corresponding `Layout` variant in a [Some]. Otherwise if the number of passed "blocks"
     /// arguments is greater than 1, this also returns the [OneLine](Layout::OneLine) variant.
     /// Finally if neither of them is passed, this returns [None].
     fn from_cli(cli: &Cli) -> Option<Self> {
-        if cli.tree {
+        if cli.du {
+            Some(Self::Du)
+        } else if cli.tree {
             Some(Self::Tree)
         } else if cli.long || cli.oneline || cli.inode || cli.context || cli.blocks.len() > 1
         // TODO: handle this differently
@@ -36,7 +40,7 @@ impl Configurable<Layout> for Layout {
 
     /// Get a potential Layout variant from a [Config].
     ///
-    /// If the `Config::layout` has value and is one of "tree", "oneline" or "grid",
+    /// If the `Config::layout` has value and is one of "tree", "oneline", "grid" or "du",
     /// this returns the corresponding `Layout` variant in a [Some].
     /// Otherwise this returns [None].
     fn from_config(config: &Config) -> Option<Self> {
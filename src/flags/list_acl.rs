@@ -0,0 +1,35 @@
+//! This module defines the [ListAcl] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether to print the access ACL entries of each file under it, in the
+/// one-line and long layouts.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct ListAcl(pub bool);
+
+impl Configurable<Self> for ListAcl {
+    /// Get a potential `ListAcl` value from [Cli].
+    ///
+    /// If the "acl" argument is passed, this returns a `ListAcl` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.acl {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `ListAcl` value from a [Config].
+    ///
+    /// If the `Config::acl` has value,
+    /// this returns it as the value of the `ListAcl`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.acl.map(Self)
+    }
+}
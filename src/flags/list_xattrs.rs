@@ -0,0 +1,35 @@
+//! This module defines the [ListXattrs] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether to print the extended attributes of each entry under it, in the
+/// one-line and long layouts.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct ListXattrs(pub bool);
+
+impl Configurable<Self> for ListXattrs {
+    /// Get a potential `ListXattrs` value from [Cli].
+    ///
+    /// If the "xattrs" argument is passed, this returns a `ListXattrs` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.xattrs {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `ListXattrs` value from a [Config].
+    ///
+    /// If the `Config::xattrs` has value,
+    /// this returns it as the value of the `ListXattrs`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.xattrs.map(Self)
+    }
+}
@@ -0,0 +1,35 @@
+//! This module defines the [OneFileSystem] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether a recursive listing stays on the file system of each input,
+/// listing the mount points it meets without descending into them.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct OneFileSystem(pub bool);
+
+impl Configurable<Self> for OneFileSystem {
+    /// Get a potential `OneFileSystem` value from [Cli].
+    ///
+    /// If the "one-file-system" argument is passed, this returns a `OneFileSystem` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.one_file_system {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `OneFileSystem` value from a [Config].
+    ///
+    /// If the `Config::one_file_system` has value,
+    /// this returns it as the value of the `OneFileSystem`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.one_file_system.map(Self)
+    }
+}
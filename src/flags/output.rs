@@ -0,0 +1,52 @@
+use crate::app::Cli;
+use crate::config_file::Config;
+
+use super::Configurable;
+
+use serde::Deserialize;
+
+/// The flag showing whether to print human-readable text or machine-readable records.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    /// A single JSON array, with nested `content` in tree mode
+    Json,
+    /// One JSON object per line, written as entries are discovered
+    Ndjson,
+}
+
+impl OutputFormat {
+    pub fn from_arg_str(value: &str) -> Self {
+        match value {
+            "text" => Self::Text,
+            "json" => Self::Json,
+            "ndjson" => Self::Ndjson,
+            // Invalid value should be handled by `clap` when building an `Cli`
+            other => unreachable!("Invalid value '{other}' for 'output'"),
+        }
+    }
+
+    pub fn is_text(self) -> bool {
+        self == Self::Text
+    }
+}
+
+impl Configurable<Self> for OutputFormat {
+    /// Get a potential `OutputFormat` variant from [Cli].
+    ///
+    /// If the "output" argument is passed, this returns the variant corresponding to its
+    /// parameter in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        cli.output.as_deref().map(Self::from_arg_str)
+    }
+
+    /// Get a potential `OutputFormat` variant from a [Config].
+    ///
+    /// If the `Config::output` has value and is one of "text", "json" or "ndjson",
+    /// this returns its corresponding variant in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.output
+    }
+}
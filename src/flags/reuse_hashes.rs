@@ -0,0 +1,35 @@
+//! This module defines the [ReuseHashes] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether the digests of the hash block are kept between runs, and reused
+/// for files whose inode, modification time and size are unchanged.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct ReuseHashes(pub bool);
+
+impl Configurable<Self> for ReuseHashes {
+    /// Get a potential `ReuseHashes` value from [Cli].
+    ///
+    /// If the "hash-cache" argument is passed, this returns a `ReuseHashes` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.hash_cache {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `ReuseHashes` value from a [Config].
+    ///
+    /// If the `Config::hash_cache` has value,
+    /// this returns it as the value of the `ReuseHashes`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.hash_cache.map(Self)
+    }
+}
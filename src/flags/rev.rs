@@ -0,0 +1,26 @@
+//! This module defines the [Rev] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag holding the git revision whose tree is listed in place of the working tree.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Rev(pub Option<String>);
+
+impl Configurable<Self> for Rev {
+    /// Get a potential `Rev` value from [Cli].
+    ///
+    /// If the "rev" argument is passed, this returns a `Rev` holding the revision in a [Some].
+    /// Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        cli.rev.clone().map(|rev| Self(Some(rev)))
+    }
+
+    /// The revision is only given on the command line, so this always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
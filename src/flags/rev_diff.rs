@@ -0,0 +1,32 @@
+//! This module defines the [RevDiff] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether the entries of `--rev` are marked with how the working tree
+/// differs from them.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct RevDiff(pub bool);
+
+impl Configurable<Self> for RevDiff {
+    /// Get a potential `RevDiff` value from [Cli].
+    ///
+    /// If the "rev-diff" argument is passed, this returns a `RevDiff` with value `true`
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.rev_diff {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// The comparison goes with `--rev`, which is only given on the command line, so this
+    /// always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
@@ -0,0 +1,44 @@
+//! This module defines the [Snapshot] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use std::path::PathBuf;
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag holding the snapshot files of a walk: the one to save it to, and the one it is
+/// compared with.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Snapshot {
+    pub save: Option<PathBuf>,
+    pub since: Option<PathBuf>,
+}
+
+impl Snapshot {
+    pub fn is_active(&self) -> bool {
+        self.save.is_some() || self.since.is_some()
+    }
+}
+
+impl Configurable<Self> for Snapshot {
+    /// Get a potential `Snapshot` value from [Cli].
+    ///
+    /// If the "snapshot" or the "since-snapshot" argument is passed, this returns a `Snapshot`
+    /// holding their files in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.snapshot.is_none() && cli.since_snapshot.is_none() {
+            return None;
+        }
+        Some(Self {
+            save: cli.snapshot.clone(),
+            since: cli.since_snapshot.clone(),
+        })
+    }
+
+    /// Snapshot files are only given on the command line, so this always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
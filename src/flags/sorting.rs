@@ -3,31 +3,146 @@ use super::Configurable;
 use crate::app::Cli;
 use crate::config_file::Config;
 
+use clap::error::ErrorKind;
+use clap::Error;
 use serde::Deserialize;
 
 /// A collection of flags on how to sort the output.
-#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Sorting {
-    pub column: SortColumn,
-    pub order: SortOrder,
+    /// The keys to sort by, each one breaking the ties of the previous ones
+    pub keys: Vec<SortKey>,
     pub dir_grouping: DirGrouping,
 }
 
 impl Sorting {
     /// Get a `Sorting` struct from [Cli], a [Config] or the [Default] values.
     ///
-    /// The [SortColumn], [SortOrder] and [DirGrouping] are configured with their respective
-    /// [Configurable] implementation.
-    pub fn configure_from(cli: &Cli, config: &Config) -> Self {
-        let column = SortColumn::configure_from(cli, config);
-        let order = SortOrder::configure_from(cli, config);
-        let dir_grouping = DirGrouping::configure_from(cli, config);
+    /// The keys come from a `--sort` chain, a single column flag such as `--timesort`, the
+    /// `sorting.keys` list or the `sorting.column` of the [Config], in that order of precedence.
+    /// A [SortOrder::Reverse] then reverses the whole chain. The [DirGrouping] is configured
+    /// with its [Configurable] implementation.
+    ///
+    /// # Errors
+    ///
+    /// This returns an [Error] when the `--sort` chain holds an unknown key or direction.
+    pub fn configure_from(cli: &Cli, config: &Config) -> Result<Self, Error> {
+        let chain = cli
+            .sort
+            .as_deref()
+            .map(|chain| {
+                SortKey::chain_from_str(chain).map_err(|err| {
+                    Error::raw(ErrorKind::ValueValidation, format!("invalid value '{chain}' for '--sort': {err}\n"))
+                })
+            })
+            .transpose()?;
+        let keys = chain
+            .or_else(|| SortColumn::from_cli(cli).map(|column| vec![SortKey::new(column)]))
+            .or_else(|| config.sorting.as_ref().and_then(|s| s.keys.clone()))
+            .unwrap_or_else(|| vec![SortKey::new(SortColumn::from_config(config).unwrap_or_default())]);
+
+        let keys = match SortOrder::configure_from(cli, config) {
+            SortOrder::Default => keys,
+            SortOrder::Reverse => keys.into_iter().map(SortKey::reversed).collect(),
+        };
+
+        Ok(Self {
+            keys,
+            dir_grouping: DirGrouping::configure_from(cli, config),
+        })
+    }
+
+    /// Whether any key sorts by the given column
+    pub fn uses(&self, column: SortColumn) -> bool {
+        self.keys.iter().any(|key| key.column == column)
+    }
+}
+
+/// The default `Sorting` is by name, with directories mixed with files.
+impl Default for Sorting {
+    fn default() -> Self {
+        Self {
+            keys: vec![SortKey::new(SortColumn::default())],
+            dir_grouping: DirGrouping::default(),
+        }
+    }
+}
+
+/// One link of a sort chain, such as `size:desc`.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct SortKey {
+    pub column: SortColumn,
+    pub order: SortOrder,
+}
+
+impl SortKey {
+    /// A key sorting in the natural direction of its column
+    pub fn new(column: SortColumn) -> Self {
         Self {
             column,
-            order,
-            dir_grouping,
+            order: SortOrder::Default,
+        }
+    }
+
+    fn reversed(self) -> Self {
+        Self {
+            column: self.column,
+            order: match self.order {
+                SortOrder::Default => SortOrder::Reverse,
+                SortOrder::Reverse => SortOrder::Default,
+            },
         }
     }
+
+    /// Parse a key such as `name`, `ext` or `size:asc`.
+    ///
+    /// Without a direction the column's natural one is used: largest and newest first for
+    /// `size` and `time`, ascending for everything else.
+    pub fn from_arg_str(value: &str) -> Result<Self, String> {
+        let (column, direction) = match value.split_once(':') {
+            Some((column, direction)) => (column, Some(direction)),
+            None => (value, None),
+        };
+
+        let column = match column.trim() {
+            "name" => SortColumn::Name,
+            "size" => SortColumn::Size,
+            "time" => SortColumn::Time,
+            "version" => SortColumn::Version,
+            "extension" | "ext" => SortColumn::Extension,
+            "git" => SortColumn::GitStatus,
+            "none" => SortColumn::None,
+            other => {
+                return Err(format!(
+                    "unknown sort key '{other}', possible values: name, size, time, version, extension, git, none"
+                ))
+            }
+        };
+        let descending_by_default = matches!(column, SortColumn::Size | SortColumn::Time);
+        let order = match direction.map(str::trim) {
+            None => SortOrder::Default,
+            Some("asc") if descending_by_default => SortOrder::Reverse,
+            Some("desc") if !descending_by_default => SortOrder::Reverse,
+            Some("asc" | "desc") => SortOrder::Default,
+            Some(other) => return Err(format!("unknown sort direction '{other}', possible values: asc, desc")),
+        };
+
+        Ok(Self { column, order })
+    }
+
+    /// Parse a comma separated chain of keys, such as `ext,size:desc,name`.
+    pub fn chain_from_str(value: &str) -> Result<Vec<Self>, String> {
+        value.split(',').map(Self::from_arg_str).collect()
+    }
+}
+
+impl TryFrom<String> for SortKey {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_arg_str(&value)
+    }
 }
 
 /// The flag showing which column to use for sorting.
@@ -48,24 +163,27 @@ impl Configurable<Self> for SortColumn {
     /// Get a potential `SortColumn` variant from [Cli].
     ///
     /// If either the "timesort" or "sizesort" arguments are passed, this returns the corresponding
-    /// `SortColumn` variant in a [Some]. Otherwise this returns [None].
+    /// `SortColumn` variant in a [Some]. A "sort" chain gives its first column. Otherwise this
+    /// returns [None].
     fn from_cli(cli: &Cli) -> Option<Self> {
-        let sort = cli.sort.as_deref();
-
-        if cli.timesort || sort == Some("time") {
+        if cli.timesort {
             Some(Self::Time)
-        } else if cli.sizesort || sort == Some("size") {
+        } else if cli.sizesort {
             Some(Self::Size)
-        } else if cli.extensionsort || sort == Some("extension") {
+        } else if cli.extensionsort {
             Some(Self::Extension)
-        } else if cli.versionsort || sort == Some("version") {
+        } else if cli.versionsort {
             Some(Self::Version)
-        } else if cli.gitsort || sort == Some("git") {
+        } else if cli.gitsort {
             Some(Self::GitStatus)
-        } else if cli.no_sort || sort == Some("none") {
+        } else if cli.no_sort {
             Some(Self::None)
         } else {
-            None
+            cli.sort
+                .as_deref()
+                .and_then(|chain| chain.split(',').next())
+                .and_then(|key| SortKey::from_arg_str(key).ok())
+                .map(|key| key.column)
         }
     }
 
@@ -0,0 +1,31 @@
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether to print a count and size summary after each directory.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Summary(pub bool);
+
+impl Configurable<Self> for Summary {
+    /// Get a potential `Summary` value from [Cli].
+    ///
+    /// If the "summary" argument is passed, this returns a `Summary` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.summary {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `Summary` value from a [Config].
+    ///
+    /// If the `Config::summary` has value,
+    /// this returns it as the value of the `Summary`, in a [Some].
+    /// Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.summary.map(Self)
+    }
+}
@@ -0,0 +1,63 @@
+//! This module defines the [TimeField] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+use serde::Deserialize;
+
+/// The flag showing which timestamp the date block shows and the time sort uses.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeField {
+    #[default]
+    Modified,
+    Access,
+    /// The last change of the inode (ctime)
+    Change,
+    /// The creation time, not recorded by every file system
+    Birth,
+}
+
+impl TimeField {
+    fn from_arg_str(value: &str) -> Self {
+        match value {
+            "modified" => Self::Modified,
+            "access" => Self::Access,
+            "change" => Self::Change,
+            "birth" => Self::Birth,
+            // Invalid value should be handled by `clap` when building an `Cli`
+            other => unreachable!("Invalid value '{other}' for 'time'"),
+        }
+    }
+}
+
+impl Configurable<Self> for TimeField {
+    /// Get a potential `TimeField` variant from [Cli].
+    ///
+    /// If the "time" argument is passed, this returns the corresponding `TimeField` variant in
+    /// a [Some]. Otherwise the "access-time" (`-u`) and "change-time" (`-c`) arguments give
+    /// [Access](TimeField::Access) and [Change](TimeField::Change). If neither is passed, this
+    /// returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if let Some(time) = cli.time.as_deref() {
+            Some(Self::from_arg_str(time))
+        } else if cli.access_time {
+            Some(Self::Access)
+        } else if cli.change_time {
+            Some(Self::Change)
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `TimeField` variant from a [Config].
+    ///
+    /// If the `Config::time` has value, this returns it in a [Some]. Otherwise this returns
+    /// [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.time
+    }
+}
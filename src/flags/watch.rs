@@ -0,0 +1,31 @@
+//! This module defines the [Watch] flag. To set it up from [Cli], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app::Cli;
+use crate::config_file::Config;
+
+/// The flag showing whether the listing stays open, redrawn as the file system changes.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Watch(pub bool);
+
+impl Configurable<Self> for Watch {
+    /// Get a potential `Watch` value from [Cli].
+    ///
+    /// If the "watch" argument is passed, this returns a `Watch` with value `true`
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_cli(cli: &Cli) -> Option<Self> {
+        if cli.watch {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Watching is asked for on the command line, so the configuration file has no say and
+    /// this always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
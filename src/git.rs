@@ -1,10 +1,13 @@
-//! Git integration (to be integrated with streaming code)
-#![allow(dead_code)]
+//! Git integration, consumed by the streaming pipeline through `stream::GitStage`
 
 use crate::meta::git_file_status::GitFileStatus;
-use std::path::{Path, PathBuf};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum GitStatus {
     /// No status info
     #[default]
@@ -37,14 +40,64 @@ pub struct GitStatusInfo {
     pub workdir_status: Option<GitStatus>,
 }
 
+/// Prefix trie over the repository paths that carry a status.
+///
+/// Every node keeps the status of its own path, if git reported one, and the aggregate of
+/// its whole subtree, so both file and directory lookups cost one step per path component.
+#[derive(Debug, Default)]
+struct StatusNode {
+    own: Option<GitFileStatus>,
+    aggregate: GitFileStatus,
+    children: HashMap<OsString, StatusNode>,
+}
+
+impl StatusNode {
+    fn insert(&mut self, relative: &Path, status: GitFileStatus) {
+        let mut node = self;
+        node.aggregate = merge(node.aggregate, status);
+        for component in relative.components() {
+            if let Component::Normal(name) = component {
+                node = node.children.entry(name.to_os_string()).or_default();
+                node.aggregate = merge(node.aggregate, status);
+            }
+        }
+        // A path can be reported twice (staged and unstaged), keep both sides
+        node.own = Some(node.own.map_or(status, |own| merge(own, status)));
+    }
+
+    fn find(&self, relative: &Path) -> Option<&StatusNode> {
+        let mut node = self;
+        for component in relative.components() {
+            if let Component::Normal(name) = component {
+                node = node.children.get(name)?;
+            }
+        }
+        Some(node)
+    }
+}
+
+/// Keep the most significant status of each side
+fn merge(a: GitFileStatus, b: GitFileStatus) -> GitFileStatus {
+    GitFileStatus {
+        index: std::cmp::max(a.index, b.index),
+        workdir: std::cmp::max(a.workdir, b.workdir),
+    }
+}
+
 pub struct GitCache {
-    statuses: Vec<(PathBuf, GitStatusInfo)>,
+    workdir: Option<PathBuf>,
+    statuses: StatusNode,
 }
 
 impl GitCache {
     pub fn new(path: &Path) -> GitCache {
-        // Discover the git repository from the given path
-        let repo = match gix::discover(path) {
+        // Discover the git repository from the given path, or from the directory of a file
+        let dir = match path.parent() {
+            Some(parent) if !path.is_dir() && parent.as_os_str().is_empty() => Path::new("."),
+            Some(parent) if !path.is_dir() => parent,
+            _ => path,
+        };
+        let repo = match gix::discover(dir) {
             Ok(r) => r,
             Err(_e) => {
                 // Unable to retrieve Git info; it doesn't seem to be a git directory
@@ -135,7 +188,7 @@ impl GitCache {
                 }
             }
 
-            GitCache { statuses }
+            Self::from_statuses(workdir, statuses)
         } else {
             // No workdir
             Self::empty()
@@ -144,7 +197,29 @@ impl GitCache {
 
     pub fn empty() -> Self {
         GitCache {
-            statuses: Vec::new(),
+            workdir: None,
+            statuses: StatusNode::default(),
+        }
+    }
+
+    /// Build a cache from already collected statuses of the canonical `workdir`.
+    ///
+    /// Paths outside of `workdir` are skipped.
+    pub fn from_statuses(
+        workdir: PathBuf,
+        statuses: impl IntoIterator<Item = (PathBuf, GitStatusInfo)>,
+    ) -> Self {
+        let mut root = StatusNode::default();
+        for (path, info) in statuses {
+            match path.strip_prefix(&workdir) {
+                Ok(relative) => root.insert(relative, GitFileStatus::from_gix_status(&info)),
+                Err(_) => log::debug!("Skipping git status outside of {:?}: {:?}", workdir, path),
+            }
+        }
+
+        GitCache {
+            workdir: Some(workdir),
+            statuses: root,
         }
     }
 
@@ -167,28 +242,29 @@ impl GitCache {
             }
         };
         
-        Some(self.inner_get(&filename, is_directory))
+        // Entries outside the repository working tree have no git status at all
+        match &self.workdir {
+            Some(workdir) if filename.starts_with(workdir) => {
+                Some(self.inner_get(&filename, is_directory))
+            }
+            _ => None,
+        }
     }
 
-    fn inner_get(&self, filepath: &PathBuf, is_directory: bool) -> GitFileStatus {
-        if is_directory {
-            self.statuses
-                .iter()
-                .filter(|&x| x.0.starts_with(filepath))
-                .map(|x| GitFileStatus::from_gix_status(&x.1))
-                .fold(GitFileStatus::default(), |acc, x| GitFileStatus {
-                    index: std::cmp::max(acc.index, x.index),
-                    workdir: std::cmp::max(acc.workdir, x.workdir),
-                })
-        } else {
-            self.statuses
-                .iter()
-                .find(|&x| filepath == &x.0)
-                .map(|e| GitFileStatus::from_gix_status(&e.1))
-                .unwrap_or_default()
+    fn inner_get(&self, filepath: &Path, is_directory: bool) -> GitFileStatus {
+        let node = self
+            .workdir
+            .as_ref()
+            .and_then(|workdir| filepath.strip_prefix(workdir).ok())
+            .and_then(|relative| self.statuses.find(relative));
+
+        match node {
+            Some(node) if is_directory => node.aggregate,
+            Some(node) => node.own.unwrap_or_default(),
+            None => GitFileStatus::default(),
         }
     }
-    
+
     fn convert_gix_status(item: &gix::status::index_worktree::Item) -> GitStatusInfo {
         match item {
             gix::status::index_worktree::Item::Modification { status, .. } => {
@@ -1,5 +1,5 @@
 use crate::flags::{IconOption, IconTheme as FlagTheme};
-use crate::meta::{FileType, Name};
+use crate::meta::{ContentClass, FileType, Name};
 use crate::theme::{icon::IconTheme, Theme};
 
 fn _convert_unicode_escapes(input: &str) -> String {
@@ -77,6 +77,11 @@ impl Icons {
         }
     }
 
+    /// Whether icons are shown at all
+    pub fn is_enabled(&self) -> bool {
+        self.theme.is_some()
+    }
+
     pub fn get(&self, name: &Name) -> String {
         match &self.theme {
             None => String::new(),
@@ -99,9 +104,20 @@ impl Icons {
                             .and_then(|ext| t.extension.get(ext.to_lowercase().as_str()))
                         {
                             icon
+                        } else if let Some(icon) = name
+                            .content_magic()
+                            .and_then(|magic| magic.extension())
+                            .and_then(|ext| t.extension.get(ext))
+                        {
+                            icon
                         } else {
                             match file_type {
                                 FileType::Directory { .. } => &t.filetype.dir,
+                                _ if name.content_magic().and_then(|magic| magic.class())
+                                    == Some(ContentClass::Executable) =>
+                                {
+                                    &t.filetype.executable
+                                }
                                 // If a file has no extension and is executable, show an icon.
                                 // Except for Windows, it marks everything as an executable.
                                 #[cfg(not(windows))]
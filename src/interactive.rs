@@ -0,0 +1,617 @@
+//! The full-screen tree browser of `--interactive`
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, SetAttribute};
+use crossterm::{cursor, queue, terminal};
+
+use crate::core::Core;
+use crate::display::RowRenderer;
+use crate::flags::{Flags, SortColumn, SortKey, SortOrder};
+use crate::meta::{FileType, Meta, OwnerCache};
+use crate::presentation::json::JsonEntry;
+use crate::{sort, ExitCode};
+
+/// The widest the metadata panel gets, in columns
+const PANEL_WIDTH: usize = 44;
+
+/// What the caller has to do after a key press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Redraw,
+    /// Walk the directory, which was expanded before it was read, and [attach](Browser::attach)
+    /// its entries
+    Load(PathBuf),
+    /// Sort by the column after the current one
+    NextSort,
+    /// Sort the other way
+    Reverse,
+    /// Quit, printing these paths
+    Accept(Vec<PathBuf>),
+    /// Quit without choosing anything
+    Cancel,
+}
+
+/// Browse `paths` as a tree drawn by `renderer` on the terminal, reading each directory as it
+/// is expanded, until the user accepts some entries or cancels.
+///
+/// Returns the accepted paths, [None] when cancelled, or the message to report when the
+/// terminal cannot be used. Errors met while reading are shown in the status line and raise
+/// `exit_code`.
+pub async fn browse(
+    core: &Core,
+    renderer: &RowRenderer,
+    paths: &[PathBuf],
+    exit_code: &mut ExitCode,
+) -> Result<Option<Vec<PathBuf>>, String> {
+    use std::io::IsTerminal;
+
+    if !std::io::stderr().is_terminal() {
+        return Err("--interactive needs a terminal".to_string());
+    }
+
+    // The sort keys change as the user cycles through them
+    let mut flags = renderer.flags().clone();
+    let mut browser = Browser::default();
+    // An explicit --depth reads that many levels up front, so the filter can reach them
+    let depth = if flags.recursion.depth == usize::MAX { 1 } else { flags.recursion.depth };
+    load(core, &flags, &mut browser, paths, depth, exit_code).await;
+
+    let mut screen = BrowserScreen::open().map_err(|e| format!("cannot draw on the terminal: {}", e))?;
+    let owner_cache = OwnerCache::default();
+    loop {
+        let height = BrowserScreen::height();
+        let view = browser.view(height);
+        let tree = renderer.tree(&view.metas);
+        let lines: Vec<&str> = tree.lines().collect();
+        let panel = browser
+            .panel()
+            .then(|| browser.selected().map(|meta| panel(meta, &owner_cache)))
+            .flatten();
+        let sort = flags.sorting.keys.first().copied().unwrap_or(SortKey::new(SortColumn::None));
+        screen
+            .draw(&lines, &view, panel.as_deref(), &browser.status(&sort))
+            .map_err(|e| format!("cannot draw on the terminal: {}", e))?;
+
+        let key = match crossterm::event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            // Resizes and the like only need a redraw
+            Ok(_) => continue,
+            Err(e) => return Err(format!("cannot read the terminal: {}", e)),
+        };
+        match browser.handle(key, height) {
+            Action::Redraw => {}
+            Action::Load(path) => load(core, &flags, &mut browser, &[path], 1, exit_code).await,
+            Action::NextSort => {
+                flags.sorting.keys = vec![SortKey::new(next_column(sort.column))];
+                resort(&flags, &mut browser);
+            }
+            Action::Reverse => {
+                let order = match sort.order {
+                    SortOrder::Default => SortOrder::Reverse,
+                    SortOrder::Reverse => SortOrder::Default,
+                };
+                flags.sorting.keys = vec![SortKey { column: sort.column, order }];
+                resort(&flags, &mut browser);
+            }
+            Action::Accept(paths) => return Ok(Some(paths)),
+            Action::Cancel => return Ok(None),
+        }
+    }
+}
+
+/// Walk `paths` down to `depth` into the browser.
+///
+/// Errors are shown in the status line rather than printed over the screen.
+async fn load(
+    core: &Core,
+    flags: &Flags,
+    browser: &mut Browser,
+    paths: &[PathBuf],
+    depth: usize,
+    exit_code: &mut ExitCode,
+) {
+    use futures::StreamExt;
+
+    let mut entries = Vec::new();
+    let mut stream = Box::pin(core.file_stream(paths, depth));
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(entry) => entries.push((entry.depth, entry.to_meta(flags))),
+            Err(e) => {
+                log::warn!("Stream error: {}", e);
+                browser.set_message(e.to_string());
+                exit_code.set_if_greater(ExitCode::MinorIssue);
+            }
+        }
+    }
+    browser.attach(entries, depth);
+    resort(flags, browser);
+}
+
+/// Sort the browser by the sort keys of `flags`, after they changed
+fn resort(flags: &Flags, browser: &mut Browser) {
+    let sorters = sort::assemble_sorters(flags);
+    browser.sort_by(|a, b| sort::by_meta(&sorters, a, b));
+}
+
+/// An entry of the tree
+struct Node {
+    meta: Meta,
+    parent: Option<usize>,
+    /// The entries of a directory, [None] until it is read
+    children: Option<Vec<usize>>,
+    expanded: bool,
+}
+
+/// The part of the tree on screen, in the order [display::tree](crate::display::tree) draws
+/// it, one line per entry.
+pub struct View {
+    /// The visible roots, each with its visible entries as content
+    pub metas: Vec<Meta>,
+    /// The index of the first line on screen
+    pub first: usize,
+    /// The index of the line under the cursor
+    pub cursor: Option<usize>,
+    /// Whether each line is marked to be printed
+    pub marked: Vec<bool>,
+}
+
+/// The state of the browser: the entries read so far, which directories are expanded, the
+/// cursor, the marks and the filter.
+///
+/// It knows nothing of the file system or the terminal: directories are read by the caller
+/// when [handle](Self::handle) asks for it, and the [View] is drawn by a [BrowserScreen].
+#[derive(Default)]
+pub struct Browser {
+    nodes: Vec<Node>,
+    roots: Vec<usize>,
+    by_path: HashMap<PathBuf, usize>,
+    cursor: usize,
+    offset: usize,
+    marked: BTreeSet<usize>,
+    filter: String,
+    /// Whether keys go to the filter
+    typing: bool,
+    panel: bool,
+    message: Option<String>,
+}
+
+impl Browser {
+    /// Add the entries of a walk `walk_depth` deep, each with its depth in the walk.
+    ///
+    /// Entries are nested under their parent when it is known, and the others become roots,
+    /// expanded. Directories less than `walk_depth` deep are complete.
+    pub fn attach(&mut self, mut entries: Vec<(usize, Meta)>, walk_depth: usize) {
+        // Parents first
+        entries.sort_by_key(|(depth, _)| *depth);
+        for (depth, meta) in entries {
+            let is_dir = matches!(meta.file_type, FileType::Directory { .. });
+            let id = match self.by_path.get(&meta.path) {
+                // The directory being expanded
+                Some(&id) => id,
+                None => {
+                    let id = self.nodes.len();
+                    let parent = meta.path.parent().and_then(|parent| self.by_path.get(parent)).copied();
+                    self.by_path.insert(meta.path.clone(), id);
+                    self.nodes.push(Node {
+                        meta,
+                        parent,
+                        children: None,
+                        expanded: parent.is_none(),
+                    });
+                    match parent {
+                        Some(parent) => self.nodes[parent].children.get_or_insert_with(Vec::new).push(id),
+                        None => self.roots.push(id),
+                    }
+                    id
+                }
+            };
+            if is_dir && depth < walk_depth {
+                self.nodes[id].children.get_or_insert_with(Vec::new);
+            }
+        }
+    }
+
+    /// Order the roots and the entries of every directory
+    pub fn sort_by(&mut self, compare: impl Fn(&Meta, &Meta) -> Ordering) {
+        let nodes = &self.nodes;
+        self.roots.sort_by(|a, b| compare(&nodes[*a].meta, &nodes[*b].meta));
+        for id in 0..self.nodes.len() {
+            if let Some(mut children) = self.nodes[id].children.take() {
+                children.sort_by(|a, b| compare(&self.nodes[*a].meta, &self.nodes[*b].meta));
+                self.nodes[id].children = Some(children);
+            }
+        }
+    }
+
+    /// Show `message` in the status line until the next key
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = Some(message.into());
+    }
+
+    /// The entry under the cursor
+    pub fn selected(&self) -> Option<&Meta> {
+        self.nodes.get(self.cursor).map(|node| &node.meta)
+    }
+
+    /// Whether the metadata panel is shown
+    pub fn panel(&self) -> bool {
+        self.panel
+    }
+
+    /// The entries shown with the current filter and expansions, for a screen `height` lines
+    /// high, scrolled to keep the cursor in sight
+    pub fn view(&mut self, height: usize) -> View {
+        let shown = self.filtered();
+        let mut order = Vec::new();
+        let metas = self.collect(&self.roots, shown.as_ref(), &mut order);
+
+        if !order.contains(&self.cursor)
+            && let Some(&first) = order.iter().find(|id| self.matches(**id)).or(order.first())
+        {
+            self.cursor = first;
+        }
+        let cursor = order.iter().position(|id| *id == self.cursor);
+        if let Some(cursor) = cursor {
+            if cursor < self.offset {
+                self.offset = cursor;
+            } else if cursor >= self.offset + height {
+                self.offset = cursor + 1 - height;
+            }
+        }
+        self.offset = self.offset.min(order.len().saturating_sub(height));
+
+        View {
+            metas,
+            first: self.offset,
+            cursor,
+            marked: order.iter().map(|id| self.marked.contains(id)).collect(),
+        }
+    }
+
+    /// React to a key, with `page` lines on screen
+    pub fn handle(&mut self, key: KeyEvent, page: usize) -> Action {
+        self.message = None;
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Cancel;
+        }
+        if self.typing {
+            match key.code {
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.jump_to_match();
+                }
+                KeyCode::Backspace => {
+                    if self.filter.pop().is_none() {
+                        self.typing = false;
+                    }
+                    self.jump_to_match();
+                }
+                KeyCode::Enter => self.typing = false,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.typing = false;
+                }
+                // The arrows still move while typing
+                _ => return self.navigate(key.code, page),
+            }
+            return Action::Redraw;
+        }
+
+        // Nothing is chosen when the filter hides every entry
+        let hidden = !self.visible().contains(&self.cursor);
+        match key.code {
+            KeyCode::Enter | KeyCode::Char(' ') if hidden => {}
+            KeyCode::Enter => return Action::Accept(self.chosen()),
+            KeyCode::Char(' ') => {
+                if !self.marked.remove(&self.cursor) {
+                    self.marked.insert(self.cursor);
+                }
+                return self.navigate(KeyCode::Down, page);
+            }
+            KeyCode::Char('/') => self.typing = true,
+            KeyCode::Char('s') => return Action::NextSort,
+            KeyCode::Char('r') => return Action::Reverse,
+            KeyCode::Char('p') => self.panel = !self.panel,
+            KeyCode::Esc if !self.filter.is_empty() => self.filter.clear(),
+            KeyCode::Esc | KeyCode::Char('q') => return Action::Cancel,
+            KeyCode::Char('k') => return self.navigate(KeyCode::Up, page),
+            KeyCode::Char('j') => return self.navigate(KeyCode::Down, page),
+            KeyCode::Char('h') => return self.navigate(KeyCode::Left, page),
+            KeyCode::Char('l') => return self.navigate(KeyCode::Right, page),
+            KeyCode::Char('g') => return self.navigate(KeyCode::Home, page),
+            KeyCode::Char('G') => return self.navigate(KeyCode::End, page),
+            code => return self.navigate(code, page),
+        }
+        Action::Redraw
+    }
+
+    /// The status line: the filter being typed, or what the keys do
+    pub fn status(&self, sort: &SortKey) -> String {
+        if self.typing {
+            return format!("/{}", self.filter);
+        }
+        let mut status = String::new();
+        if let Some(message) = &self.message {
+            status.push_str(message);
+            status.push_str(" · ");
+        }
+        if !self.marked.is_empty() {
+            status.push_str(&format!("{} marked · ", self.marked.len()));
+        }
+        if !self.filter.is_empty() {
+            status.push_str(&format!("filter: {} · ", self.filter));
+        }
+        let reversed = if sort.order == SortOrder::Reverse { ", reversed" } else { "" };
+        status.push_str(&format!(
+            "sort: {}{reversed} · ←→ fold  / filter  s sort  r reverse  p panel  space mark  enter choose  q quit",
+            column_name(sort.column)
+        ));
+        status
+    }
+
+    fn navigate(&mut self, code: KeyCode, page: usize) -> Action {
+        let order = self.visible();
+        let Some(index) = order.iter().position(|id| *id == self.cursor) else {
+            return Action::Redraw;
+        };
+        let target = match code {
+            KeyCode::Up => index.saturating_sub(1),
+            KeyCode::Down => index + 1,
+            KeyCode::PageUp => index.saturating_sub(page),
+            KeyCode::PageDown => index + page,
+            KeyCode::Home => 0,
+            KeyCode::End => usize::MAX,
+            KeyCode::Right => return self.expand(&order, index),
+            KeyCode::Left => {
+                self.collapse();
+                return Action::Redraw;
+            }
+            _ => return Action::Redraw,
+        };
+        self.cursor = order[target.min(order.len() - 1)];
+        Action::Redraw
+    }
+
+    /// Move the cursor to the first entry on screen that matches the filter
+    fn jump_to_match(&mut self) {
+        if let Some(&id) = self.visible().iter().find(|id| self.matches(**id)) {
+            self.cursor = id;
+        }
+    }
+
+    /// Expand the directory under the cursor, or step into it when it already is
+    fn expand(&mut self, order: &[usize], index: usize) -> Action {
+        let id = self.cursor;
+        let node = &mut self.nodes[id];
+        if !matches!(node.meta.file_type, FileType::Directory { .. }) {
+            return Action::Redraw;
+        }
+        if node.children.is_none() {
+            node.expanded = true;
+            return Action::Load(node.meta.path.clone());
+        }
+        if !node.expanded && self.filter.is_empty() {
+            node.expanded = true;
+        } else if let Some(&next) = order.get(index + 1)
+            && self.nodes[next].parent == Some(id)
+        {
+            self.cursor = next;
+        }
+        Action::Redraw
+    }
+
+    /// Collapse the directory under the cursor, or step out to its parent
+    fn collapse(&mut self) {
+        let node = &mut self.nodes[self.cursor];
+        if node.expanded && node.children.is_some() && self.filter.is_empty() {
+            node.expanded = false;
+        } else if let Some(parent) = node.parent {
+            self.cursor = parent;
+        }
+    }
+
+    /// The marked paths, or the one under the cursor when none is
+    fn chosen(&self) -> Vec<PathBuf> {
+        if self.marked.is_empty() {
+            return self.selected().map(|meta| vec![meta.path.clone()]).unwrap_or_default();
+        }
+        let mut paths: Vec<PathBuf> = self.marked.iter().map(|id| self.nodes[*id].meta.path.clone()).collect();
+        paths.sort();
+        paths
+    }
+
+    fn matches(&self, id: usize) -> bool {
+        !self.filter.is_empty()
+            && self.nodes[id].meta.name.file_name().to_lowercase().contains(&self.filter.to_lowercase())
+    }
+
+    /// With a filter, the entries matching it and their parents
+    fn filtered(&self) -> Option<HashSet<usize>> {
+        if self.filter.is_empty() {
+            return None;
+        }
+        let mut shown = HashSet::new();
+        for id in (0..self.nodes.len()).filter(|id| self.matches(*id)) {
+            let mut next = Some(id);
+            while let Some(id) = next
+                && shown.insert(id)
+            {
+                next = self.nodes[id].parent;
+            }
+        }
+        Some(shown)
+    }
+
+    /// The entries on screen, top to bottom, as [collect](Self::collect) orders them
+    fn visible(&self) -> Vec<usize> {
+        let mut order = Vec::new();
+        self.collect(&self.roots, self.filtered().as_ref(), &mut order);
+        order
+    }
+
+    /// The entries among `ids` that are shown, nested, pushing their ids in drawing order.
+    ///
+    /// With a filter, every path down to a match is shown, expanded or not.
+    fn collect(&self, ids: &[usize], shown: Option<&HashSet<usize>>, order: &mut Vec<usize>) -> Vec<Meta> {
+        let mut metas = Vec::new();
+        for &id in ids {
+            if shown.is_some_and(|shown| !shown.contains(&id)) {
+                continue;
+            }
+            order.push(id);
+            let node = &self.nodes[id];
+            let mut meta = node.meta.clone();
+            if let Some(children) = node.children.as_ref().filter(|_| node.expanded || shown.is_some()) {
+                let content = self.collect(children, shown, order);
+                if !content.is_empty() {
+                    meta.content = Some(content);
+                }
+            }
+            metas.push(meta);
+        }
+        metas
+    }
+}
+
+fn column_name(column: SortColumn) -> &'static str {
+    match column {
+        SortColumn::None => "none",
+        SortColumn::Extension => "extension",
+        SortColumn::Name => "name",
+        SortColumn::Time => "time",
+        SortColumn::Size => "size",
+        SortColumn::Version => "version",
+        SortColumn::GitStatus => "git",
+    }
+}
+
+/// The column sorted by after `column` when cycling with `s`
+pub fn next_column(column: SortColumn) -> SortColumn {
+    match column {
+        SortColumn::Name => SortColumn::Size,
+        SortColumn::Size => SortColumn::Time,
+        SortColumn::Time => SortColumn::Extension,
+        SortColumn::Extension => SortColumn::Version,
+        _ => SortColumn::Name,
+    }
+}
+
+/// The lines of the metadata panel, taken from the JSON record of the entry
+pub fn panel(meta: &Meta, owner_cache: &OwnerCache) -> Vec<String> {
+    let entry = JsonEntry::from_meta(meta, owner_cache);
+    let mut lines = vec![entry.name, String::new()];
+    let mut field = |key: &str, value: Option<String>| {
+        if let Some(value) = value {
+            lines.push(format!("{key:<12}{value}"));
+        }
+    };
+    field("path", Some(entry.path));
+    field("type", Some(entry.file_type.to_string()));
+    field("size", entry.size.map(|size| format!("{size} bytes")));
+    field("modified", entry.modified);
+    field("owner", entry.owner.map(|owner| format!("{}:{}", owner.user, owner.group)));
+    field("permissions", entry.permissions);
+    field("inode", entry.inode.map(|inode| inode.to_string()));
+    field("links", entry.links.map(|links| links.to_string()));
+    field(
+        "target",
+        entry
+            .symlink
+            .map(|symlink| format!("{}{}", symlink.target, if symlink.valid { "" } else { " (broken)" })),
+    );
+    field("hash", entry.hash);
+    lines
+}
+
+/// The terminal the browser is drawn on: the alternate screen of stderr, in raw mode, so
+/// stdout is left for the chosen paths.
+pub struct BrowserScreen {
+    stderr: std::io::Stderr,
+}
+
+impl BrowserScreen {
+    pub fn open() -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let mut stderr = std::io::stderr();
+        queue!(stderr, terminal::EnterAlternateScreen, terminal::DisableLineWrap, cursor::Hide)?;
+        stderr.flush()?;
+        Ok(Self { stderr })
+    }
+
+    /// The lines available for the tree, below which is the status line
+    pub fn height() -> usize {
+        terminal::size().map_or(24, |(_, rows)| rows as usize).saturating_sub(1).max(1)
+    }
+
+    /// Draw the lines of the tree from `view.first` with a gutter for the cursor and marks,
+    /// the panel on the right if there is one, and the status line at the bottom
+    pub fn draw(&mut self, lines: &[&str], view: &View, panel: Option<&[String]>, status: &str) -> std::io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        let (columns, height) = (columns as usize, rows.saturating_sub(1));
+        // Too narrow a terminal leaves the whole width to the tree
+        let panel_width = PANEL_WIDTH.min(columns / 3);
+        let panel = panel.filter(|_| panel_width > 10);
+
+        for row in 0..height {
+            queue!(self.stderr, cursor::MoveTo(0, row))?;
+            let index = view.first + row as usize;
+            if let Some(line) = lines.get(index) {
+                let selected = view.cursor == Some(index);
+                let gutter = match (selected, view.marked.get(index).copied().unwrap_or_default()) {
+                    (true, true) => ">+",
+                    (true, false) => "> ",
+                    (false, true) => " +",
+                    (false, false) => "  ",
+                };
+                if selected {
+                    queue!(self.stderr, SetAttribute(Attribute::Reverse))?;
+                }
+                write!(self.stderr, "{gutter}")?;
+                queue!(self.stderr, SetAttribute(Attribute::Reset))?;
+                write!(self.stderr, "{line}")?;
+            }
+            queue!(self.stderr, SetAttribute(Attribute::Reset), terminal::Clear(terminal::ClearType::UntilNewLine))?;
+
+            // Drawn over the end of long lines
+            if let Some(panel) = panel {
+                let text = panel.get(row as usize).map_or("", String::as_str);
+                let x = (columns - panel_width) as u16;
+                queue!(self.stderr, cursor::MoveTo(x, row), terminal::Clear(terminal::ClearType::UntilNewLine))?;
+                write!(self.stderr, "│ {}", truncate(text, panel_width - 2))?;
+            }
+        }
+
+        queue!(
+            self.stderr,
+            cursor::MoveTo(0, height),
+            SetAttribute(Attribute::Reverse),
+            terminal::Clear(terminal::ClearType::UntilNewLine)
+        )?;
+        write!(self.stderr, "{:<columns$}", truncate(status, columns))?;
+        queue!(self.stderr, SetAttribute(Attribute::Reset))?;
+        self.stderr.flush()
+    }
+}
+
+impl Drop for BrowserScreen {
+    fn drop(&mut self) {
+        let _ = queue!(
+            self.stderr,
+            cursor::Show,
+            terminal::EnableLineWrap,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = self.stderr.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
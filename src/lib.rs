@@ -55,11 +55,13 @@ pub mod flags;
 pub mod git;
 pub mod git_theme;
 pub mod icon;
+pub mod interactive;
 pub mod meta;
 pub mod presentation;
 pub mod sort;
 pub mod stream;
 pub mod theme;
+pub mod watch;
 
 // Re-export commonly used types at the crate root for convenience
 pub use app::Cli;
@@ -1,9 +1,12 @@
 use crate::color::{ColoredString, Colors, Elem};
+use crate::meta::Acl;
 use std::path::Path;
 
 #[derive(Clone, Debug)]
 pub struct AccessControl {
     has_acl: bool,
+    /// The decoded entries of the access ACL
+    acl: Option<Acl>,
     selinux_context: String,
     smack_context: String,
 }
@@ -13,6 +16,7 @@ impl AccessControl {
     pub fn for_path(_: &Path) -> Self {
         Self {
             has_acl: false,
+            acl: None,
             selinux_context: String::new(),
             smack_context: String::new(),
         }
@@ -20,11 +24,8 @@ impl AccessControl {
 
     #[cfg(unix)]
     pub fn for_path(path: &Path) -> Self {
-        let has_acl = xattr::get(path, Method::Acl.name())
-            .ok()
-            .flatten()
-            .map(|v| !v.is_empty())
-            .unwrap_or(false);
+        let acl = xattr::get(path, Method::Acl.name()).ok().flatten();
+        let has_acl = acl.as_ref().map(|v| !v.is_empty()).unwrap_or(false);
 
         let selinux_context = xattr::get(path, Method::Selinux.name())
             .ok()
@@ -36,18 +37,23 @@ impl AccessControl {
             .flatten()
             .unwrap_or_default();
 
-        Self::from_data(has_acl, &selinux_context, &smack_context)
+        Self::from_data(has_acl, acl.as_deref(), &selinux_context, &smack_context)
     }
 
     #[cfg(unix)]
-    fn from_data(has_acl: bool, selinux_context: &[u8], smack_context: &[u8]) -> Self {
+    fn from_data(has_acl: bool, acl: Option<&[u8]>, selinux_context: &[u8], smack_context: &[u8]) -> Self {
         Self {
             has_acl,
+            acl: acl.and_then(Acl::from_xattr),
             selinux_context: String::from_utf8_lossy(selinux_context).into_owned(),
             smack_context: String::from_utf8_lossy(smack_context).into_owned(),
         }
     }
 
+    pub fn acl(&self) -> Option<&Acl> {
+        self.acl.as_ref()
+    }
+
     pub fn render_method(&self, colors: &Colors) -> ColoredString {
         let (symbol, elem) = if self.has_acl {
             ("+", &Elem::Acl)
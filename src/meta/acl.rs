@@ -0,0 +1,138 @@
+//! POSIX access control lists, decoded from the `system.posix_acl_access` attribute
+
+use crate::color::{Colors, Elem};
+use crate::meta::OwnerCache;
+
+/// The only version of the attribute format, see `linux/posix_acl_xattr.h`
+const XATTR_VERSION: u32 = 2;
+/// The id of the entries that apply to the owner, the owning group, the mask and others
+const UNDEFINED_ID: u32 = u32::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AclTag {
+    /// The owner of the file
+    UserObj,
+    User,
+    /// The owning group of the file
+    GroupObj,
+    Group,
+    /// The most rights a named user or any group can get
+    Mask,
+    Other,
+}
+
+impl AclTag {
+    fn from_raw(tag: u16) -> Option<Self> {
+        match tag {
+            0x01 => Some(Self::UserObj),
+            0x02 => Some(Self::User),
+            0x04 => Some(Self::GroupObj),
+            0x08 => Some(Self::Group),
+            0x10 => Some(Self::Mask),
+            0x20 => Some(Self::Other),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::UserObj | Self::User => "user",
+            Self::GroupObj | Self::Group => "group",
+            Self::Mask => "mask",
+            Self::Other => "other",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AclEntry {
+    pub tag: AclTag,
+    /// The uid or gid of a named user or group
+    pub id: Option<u32>,
+    /// The read (4), write (2) and execute (1) bits
+    pub permissions: u8,
+}
+
+impl AclEntry {
+    /// The name of the user or group the entry is about, empty for the other tags
+    pub fn qualifier(&self, cache: &OwnerCache) -> String {
+        match (self.tag, self.id) {
+            (AclTag::User, Some(uid)) => cache.user_name(uid),
+            (AclTag::Group, Some(gid)) => cache.group_name(gid),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Acl {
+    entries: Vec<AclEntry>,
+}
+
+impl Acl {
+    /// Decode the little-endian attribute: a version, then a tag, permissions and id per entry.
+    pub fn from_xattr(bytes: &[u8]) -> Option<Self> {
+        let (version, entries) = bytes.split_first_chunk::<4>()?;
+        if u32::from_le_bytes(*version) != XATTR_VERSION || entries.len() % 8 != 0 {
+            return None;
+        }
+
+        let entries = entries
+            .chunks_exact(8)
+            .map(|entry| {
+                let tag = AclTag::from_raw(u16::from_le_bytes([entry[0], entry[1]]))?;
+                let permissions = u16::from_le_bytes([entry[2], entry[3]]) as u8 & 0o7;
+                let id = u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]);
+                Some(AclEntry {
+                    tag,
+                    id: (id != UNDEFINED_ID).then_some(id),
+                    permissions,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { entries })
+    }
+
+    pub fn entries(&self) -> &[AclEntry] {
+        &self.entries
+    }
+
+    /// The rights an entry actually grants once the mask is applied
+    pub fn effective(&self, entry: &AclEntry) -> u8 {
+        let mask = self.entries.iter().find(|entry| entry.tag == AclTag::Mask);
+        match (entry.tag, mask) {
+            (AclTag::User | AclTag::GroupObj | AclTag::Group, Some(mask)) => {
+                entry.permissions & mask.permissions
+            }
+            _ => entry.permissions,
+        }
+    }
+
+    /// One line per entry, as `getfacl` prints them
+    pub fn render_lines(&self, colors: &Colors, cache: &OwnerCache) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|entry| {
+                let mut line = format!(
+                    "    {}:{}:{}",
+                    entry.tag.name(),
+                    entry.qualifier(cache),
+                    colors.colorize(rwx(entry.permissions), &Elem::Acl)
+                );
+                let effective = self.effective(entry);
+                if effective != entry.permissions {
+                    line += &format!("  #effective:{}", colors.colorize(rwx(effective), &Elem::Acl));
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+/// Permission bits as `rwx`, with `-` for the missing ones
+pub fn rwx(permissions: u8) -> String {
+    [(0o4, 'r'), (0o2, 'w'), (0o1, 'x')]
+        .iter()
+        .map(|&(bit, c)| if permissions & bit != 0 { c } else { '-' })
+        .collect()
+}
@@ -0,0 +1,162 @@
+use crate::color::{ColoredString, Colors, Elem};
+use std::path::Path;
+
+const VFS_CAP_REVISION_MASK: u32 = 0xFF00_0000;
+const VFS_CAP_REVISION_1: u32 = 0x0100_0000;
+const VFS_CAP_REVISION_2: u32 = 0x0200_0000;
+const VFS_CAP_REVISION_3: u32 = 0x0300_0000;
+const VFS_CAP_FLAGS_EFFECTIVE: u32 = 0x0000_0001;
+
+/// Capability names by bit, from `linux/capability.h`
+const NAMES: [&str; 41] = [
+    "chown",
+    "dac_override",
+    "dac_read_search",
+    "fowner",
+    "fsetid",
+    "kill",
+    "setgid",
+    "setuid",
+    "setpcap",
+    "linux_immutable",
+    "net_bind_service",
+    "net_broadcast",
+    "net_admin",
+    "net_raw",
+    "ipc_lock",
+    "ipc_owner",
+    "sys_module",
+    "sys_rawio",
+    "sys_chroot",
+    "sys_ptrace",
+    "sys_pacct",
+    "sys_admin",
+    "sys_boot",
+    "sys_nice",
+    "sys_resource",
+    "sys_time",
+    "sys_tty_config",
+    "mknod",
+    "lease",
+    "audit_write",
+    "audit_control",
+    "setfcap",
+    "mac_override",
+    "mac_admin",
+    "syslog",
+    "wake_alarm",
+    "block_suspend",
+    "audit_read",
+    "perfmon",
+    "bpf",
+    "checkpoint_restore",
+];
+
+/// The file capabilities of an entry, decoded from its `security.capability` xattr.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The sets, or [None] when they cannot be read
+    sets: Option<CapabilitySets>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct CapabilitySets {
+    permitted: u64,
+    inheritable: u64,
+    effective: bool,
+}
+
+impl Capabilities {
+    /// Read the capabilities of `path`, which has none when it lacks the attribute.
+    #[cfg(target_os = "linux")]
+    pub fn for_path(path: &Path) -> Self {
+        let sets = match xattr::get(path, "security.capability") {
+            Ok(Some(value)) => Self::from_xattr(&value).sets,
+            Ok(None) => Some(CapabilitySets::default()),
+            Err(_) => None,
+        };
+        Self { sets }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn for_path(_: &Path) -> Self {
+        Self { sets: None }
+    }
+
+    /// Decode a `vfs_cap_data` value, as stored in the `security.capability` xattr.
+    pub fn from_xattr(value: &[u8]) -> Self {
+        let words: Vec<u32> = value
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+        let Some(&magic) = words.first() else {
+            return Self { sets: None };
+        };
+        let count = match magic & VFS_CAP_REVISION_MASK {
+            VFS_CAP_REVISION_1 => 1,
+            VFS_CAP_REVISION_2 | VFS_CAP_REVISION_3 => 2,
+            _ => return Self { sets: None },
+        };
+        if words.len() < 1 + 2 * count {
+            return Self { sets: None };
+        }
+
+        let mut sets = CapabilitySets {
+            effective: magic & VFS_CAP_FLAGS_EFFECTIVE != 0,
+            ..CapabilitySets::default()
+        };
+        for (index, pair) in words[1..=2 * count].chunks_exact(2).enumerate() {
+            sets.permitted |= u64::from(pair[0]) << (32 * index);
+            sets.inheritable |= u64::from(pair[1]) << (32 * index);
+        }
+        Self { sets: Some(sets) }
+    }
+
+    /// The capabilities like `getcap` writes them, e.g. `cap_net_bind_service=ep`, with the
+    /// ones sharing their sets grouped together, or [None] when they cannot be read.
+    pub fn text(&self) -> Option<String> {
+        let sets = self.sets?;
+        let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+        for bit in 0..64 {
+            let permitted = sets.permitted & (1 << bit) != 0;
+            let inheritable = sets.inheritable & (1 << bit) != 0;
+            if !permitted && !inheritable {
+                continue;
+            }
+            let mut flags = String::new();
+            if sets.effective {
+                flags.push('e');
+            }
+            if inheritable {
+                flags.push('i');
+            }
+            if permitted {
+                flags.push('p');
+            }
+            let name = match NAMES.get(bit) {
+                Some(name) => format!("cap_{name}"),
+                None => bit.to_string(),
+            };
+            match groups.iter_mut().find(|(group, _)| *group == flags) {
+                Some((_, names)) => names.push(name),
+                None => groups.push((flags, vec![name])),
+            }
+        }
+
+        Some(
+            groups
+                .into_iter()
+                .map(|(flags, names)| format!("{}={flags}", names.join(",")))
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+
+    pub fn render(&self, colors: &Colors) -> ColoredString {
+        match self.text() {
+            Some(text) if text.is_empty() => colors.colorize('-', &Elem::NoAccess),
+            Some(text) => colors.colorize(text, &Elem::Exec),
+            None => colors.colorize('?', &Elem::NoAccess),
+        }
+    }
+}
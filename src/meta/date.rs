@@ -1,6 +1,6 @@
 use super::locale::current_locale;
 use crate::color::{ColoredString, Colors, Elem};
-use crate::flags::{DateFlag, Flags};
+use crate::flags::{DateFlag, Flags, TimeField};
 use chrono::{DateTime, Duration, Local};
 use chrono_humanize::HumanTime;
 use std::fs::Metadata;
@@ -41,6 +41,53 @@ impl From<&Metadata> for Date {
     }
 }
 
+/// The four timestamps of an entry, one of which is picked by `--time`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timestamps {
+    pub modified: Date,
+    pub accessed: Date,
+    pub changed: Date,
+    pub born: Date,
+}
+
+impl From<&Metadata> for Timestamps {
+    fn from(meta: &Metadata) -> Self {
+        let date = |time: std::io::Result<SystemTime>| time.map_or(Date::Invalid, Date::from);
+
+        #[cfg(unix)]
+        let changed = {
+            use std::os::unix::fs::MetadataExt;
+
+            match DateTime::from_timestamp(meta.ctime(), meta.ctime_nsec() as u32) {
+                Some(datetime) => Date::Date(datetime.with_timezone(&Local)),
+                None => Date::Invalid,
+            }
+        };
+        #[cfg(not(unix))]
+        let changed = date(meta.modified());
+
+        Self {
+            modified: Date::from(meta),
+            accessed: date(meta.accessed()),
+            changed,
+            // On Linux the standard library reads it with statx, and reports an error when
+            // the file system does not record it
+            born: date(meta.created()),
+        }
+    }
+}
+
+impl Timestamps {
+    pub fn get(&self, field: TimeField) -> &Date {
+        match field {
+            TimeField::Modified => &self.modified,
+            TimeField::Access => &self.accessed,
+            TimeField::Change => &self.changed,
+            TimeField::Birth => &self.born,
+        }
+    }
+}
+
 impl Date {
     pub fn render(&self, colors: &Colors, flags: &Flags) -> ColoredString {
         let now = Local::now();
@@ -0,0 +1,42 @@
+use serde::Serialize;
+
+use crate::git::GitStatus;
+use crate::meta::GitFileStatus;
+
+/// How an entry compares between the old and the new tree of `--diff`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffStatus {
+    Unchanged,
+    /// Only in the new tree
+    Added,
+    /// Only in the old tree
+    Removed,
+    /// A file of another size, time or content, a symlink to another target, or a
+    /// directory with changes below it
+    Modified,
+    /// A file replaced by a directory, a symlink or the like
+    TypeChanged,
+}
+
+impl DiffStatus {
+    /// The git status shown for the entry, so the diff takes the symbols and colors of the
+    /// git theme
+    pub fn git_status(self) -> GitFileStatus {
+        let workdir = match self {
+            Self::Unchanged => GitStatus::Unmodified,
+            Self::Added => GitStatus::NewInWorkdir,
+            Self::Removed => GitStatus::Deleted,
+            Self::Modified => GitStatus::Modified,
+            Self::TypeChanged => GitStatus::Typechange,
+        };
+        GitFileStatus {
+            index: GitStatus::Default,
+            workdir,
+        }
+    }
+
+    pub fn is_changed(self) -> bool {
+        self != Self::Unchanged
+    }
+}
@@ -0,0 +1,61 @@
+use crate::color::{ColoredString, Colors, Elem};
+use crate::flags::HashAlgorithm;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// How many leading hex digits the hash block shows
+const SHOWN_DIGITS: usize = 16;
+
+/// The digest of the content of a regular file, in lowercase hex.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Digest(String);
+
+impl Digest {
+    /// Hash the content of `path`, reading it in chunks so large files are never loaded whole.
+    pub fn of_file(path: &Path, algorithm: HashAlgorithm) -> io::Result<Self> {
+        let mut file = std::fs::File::open(path)?;
+        let mut buffer = vec![0; 64 * 1024];
+        match algorithm {
+            HashAlgorithm::Xxh3 => {
+                let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+                read_chunks(&mut file, &mut buffer, |chunk| hasher.update(chunk))?;
+                Ok(Self(format!("{:032x}", hasher.digest128())))
+            }
+            HashAlgorithm::Blake3 => {
+                let mut hasher = blake3::Hasher::new();
+                read_chunks(&mut file, &mut buffer, |chunk| {
+                    hasher.update(chunk);
+                })?;
+                Ok(Self(hasher.finalize().to_hex().to_string()))
+            }
+        }
+    }
+
+    /// Wrap a digest already in hex, such as one from the hash cache
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        (!hex.is_empty() && hex.bytes().all(|byte| byte.is_ascii_hexdigit()))
+            .then(|| Self(hex.to_ascii_lowercase()))
+    }
+
+    /// The full digest, as JSON output shows it
+    pub fn hex(&self) -> &str {
+        &self.0
+    }
+
+    /// The leading digits, enough to tell files apart in a listing
+    pub fn render(&self, colors: &Colors) -> ColoredString {
+        let shown = &self.0[..self.0.len().min(SHOWN_DIGITS)];
+        colors.colorize(shown, &Elem::Context)
+    }
+}
+
+fn read_chunks(file: &mut std::fs::File, buffer: &mut [u8], mut update: impl FnMut(&[u8])) -> io::Result<()> {
+    loop {
+        match file.read(buffer) {
+            Ok(0) => return Ok(()),
+            Ok(read) => update(&buffer[..read]),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::color::{ColoredString, Colors, Elem};
+use std::collections::HashSet;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// One line of `/proc/self/mountinfo`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mount {
+    /// `(major, minor)` of the mounted device
+    pub device: (u32, u32),
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+}
+
+/// The mounts of the process, to tell the file system of an entry and the mount points.
+#[derive(Clone, Debug, Default)]
+pub struct MountTable {
+    mounts: Vec<Mount>,
+    mount_points: HashSet<PathBuf>,
+}
+
+impl MountTable {
+    /// The mounts of the process, read once on first use.
+    ///
+    /// The table is empty where there is no `/proc/self/mountinfo` to read.
+    pub fn current() -> &'static MountTable {
+        static TABLE: OnceLock<MountTable> = OnceLock::new();
+        TABLE.get_or_init(|| {
+            std::fs::read_to_string("/proc/self/mountinfo")
+                .map(|text| Self::parse(&text))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Parse the format of `/proc/<pid>/mountinfo`, skipping malformed lines.
+    ///
+    /// Each line reads `id parent major:minor root mount-point options [optional...] - type
+    /// source super-options`, with spaces and backslashes in paths escaped as octal.
+    pub fn parse(text: &str) -> Self {
+        let mounts: Vec<Mount> = text.lines().filter_map(parse_line).collect();
+        let mount_points = mounts.iter().map(|mount| mount.mount_point.clone()).collect();
+        Self { mounts, mount_points }
+    }
+
+    pub fn mounts(&self) -> &[Mount] {
+        &self.mounts
+    }
+
+    /// Whether `canonical` is where something is mounted
+    pub fn is_mount_point(&self, canonical: &Path) -> bool {
+        self.mount_points.contains(canonical)
+    }
+
+    /// The type of the file system holding an entry.
+    ///
+    /// The device of the entry picks the mount. Entries on a device missing from the table,
+    /// as with some btrfs subvolumes, fall back to the deepest mount above `canonical`.
+    pub fn fs_type(&self, metadata: &Metadata, canonical: Option<&Path>) -> Option<&str> {
+        let by_device = device_of(metadata)
+            .and_then(|device| self.mounts.iter().rev().find(|mount| mount.device == device));
+        let by_path = || {
+            let canonical = canonical?;
+            self.mounts
+                .iter()
+                .rev()
+                .filter(|mount| canonical.starts_with(&mount.mount_point))
+                .max_by_key(|mount| mount.mount_point.components().count())
+        };
+        by_device.or_else(by_path).map(|mount| mount.fs_type.as_str())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn device_of(metadata: &Metadata) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = metadata.dev() as libc::dev_t;
+    Some((libc::major(device) as u32, libc::minor(device) as u32))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn device_of(_: &Metadata) -> Option<(u32, u32)> {
+    None
+}
+
+fn parse_line(line: &str) -> Option<Mount> {
+    let mut fields = line.split(' ');
+    let device = fields.nth(2)?;
+    let mount_point = fields.nth(1)?;
+    // The optional fields end with a lone dash
+    let fs_type = fields.skip_while(|field| *field != "-").nth(1)?;
+
+    let (major, minor) = device.split_once(':')?;
+    Some(Mount {
+        device: (major.parse().ok()?, minor.parse().ok()?),
+        mount_point: PathBuf::from(unescape(mount_point)),
+        fs_type: fs_type.to_string(),
+    })
+}
+
+/// Undo the `\ooo` escaping the kernel applies to spaces, tabs, newlines and backslashes
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes
+            .get(i + 1..i + 4)
+            .filter(|digits| bytes[i] == b'\\' && digits.iter().all(|digit| (b'0'..=b'7').contains(digit)))
+            .map(|digits| digits.iter().fold(0u32, |value, digit| value * 8 + u32::from(digit - b'0')));
+        match octal {
+            Some(value) if value <= 0xFF => {
+                res.push(value as u8);
+                i += 4;
+            }
+            _ => {
+                res.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&res).into_owned()
+}
+
+/// The type of the file system holding an entry, such as `ext4` or `tmpfs`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileSystem {
+    /// The type, or [None] when no mount holds the entry
+    fs_type: Option<String>,
+}
+
+impl FileSystem {
+    pub fn new(fs_type: Option<&str>) -> Self {
+        Self {
+            fs_type: fs_type.map(str::to_string),
+        }
+    }
+
+    pub fn for_entry(metadata: &Metadata, canonical: Option<&Path>) -> Self {
+        Self::new(MountTable::current().fs_type(metadata, canonical))
+    }
+
+    pub fn fs_type(&self) -> Option<&str> {
+        self.fs_type.as_deref()
+    }
+
+    pub fn render(&self, colors: &Colors) -> ColoredString {
+        match &self.fs_type {
+            Some(fs_type) => colors.colorize(fs_type.as_str(), &Elem::Context),
+            None => colors.colorize('?', &Elem::NoAccess),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use crate::color::{self, ColoredString, Colors};
 use crate::git::GitStatus;
 use crate::git_theme::GitTheme;
+use serde::Serialize;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 pub struct GitFileStatus {
     pub index: GitStatus,
     pub workdir: GitStatus,
@@ -33,7 +34,15 @@ impl GitFileStatus {
         matches!(self.workdir, GitStatus::Modified) || matches!(self.index, GitStatus::Modified)
     }
 
-    #[allow(dead_code)]
+    /// Whether either side carries a change worth showing
+    pub fn has_changes(&self) -> bool {
+        !Self::is_clean(self.index) || !Self::is_clean(self.workdir)
+    }
+
+    fn is_clean(status: GitStatus) -> bool {
+        matches!(status, GitStatus::Default | GitStatus::Unmodified)
+    }
+
     pub fn render(&self, colors: &Colors, git_theme: &GitTheme) -> ColoredString {
         let index_symbol = colors.colorize(
             git_theme.get_symbol(&self.index),
@@ -51,22 +60,22 @@ impl GitFileStatus {
         let mut result = String::with_capacity(160);
 
         // Only show git status if there are actual changes
-        if self.index != GitStatus::Unmodified || self.workdir != GitStatus::Unmodified {
+        if self.has_changes() {
             // Use a more subtle visual indicator
             result.push('│');
 
             // Show index status if modified
-            if self.index != GitStatus::Unmodified {
+            if !Self::is_clean(self.index) {
                 result.push_str(&index_symbol.to_string());
             }
 
             // Add separator only if both statuses are shown
-            if self.index != GitStatus::Unmodified && self.workdir != GitStatus::Unmodified {
+            if !Self::is_clean(self.index) && !Self::is_clean(self.workdir) {
                 result.push('·');
             }
 
             // Show workdir status if modified
-            if self.workdir != GitStatus::Unmodified {
+            if !Self::is_clean(self.workdir) {
                 result.push_str(&workdir_symbol.to_string());
             }
 
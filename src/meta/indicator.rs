@@ -21,6 +21,11 @@ impl From<FileType> for Indicator {
 }
 
 impl Indicator {
+    /// The indicator of a directory something is mounted on
+    pub fn mount_point() -> Self {
+        Indicator("󰋊 ") // Nerd Font hard disk icon
+    }
+
     pub fn render(&self, flags: &Flags) -> ColoredString {
         if flags.display_indicators.0 {
             ColoredString::new(Colors::default_style(), self.0.to_string())
@@ -23,6 +23,11 @@ impl From<&Metadata> for INode {
 }
 
 impl INode {
+    /// Returns the inode number if available
+    pub fn index(&self) -> Option<u64> {
+        self.index
+    }
+
     pub fn render(&self, colors: &Colors) -> ColoredString {
         match self.index {
             Some(i) => colors.colorize(format!(" {}", i), &Elem::INode { valid: true }),
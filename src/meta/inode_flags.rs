@@ -0,0 +1,111 @@
+use crate::color::{ColoredString, Colors, Elem};
+use std::fs::Metadata;
+use std::path::Path;
+
+const FS_IMMUTABLE_FL: u32 = 0x0000_0010;
+const FS_APPEND_FL: u32 = 0x0000_0020;
+const FS_NODUMP_FL: u32 = 0x0000_0040;
+const FS_NOATIME_FL: u32 = 0x0000_0080;
+
+/// The shown flags with their `lsattr` letters, in the order they are written
+const SHOWN: [(u32, char); 4] = [
+    (FS_IMMUTABLE_FL, 'i'),
+    (FS_APPEND_FL, 'a'),
+    (FS_NODUMP_FL, 'd'),
+    (FS_NOATIME_FL, 'A'),
+];
+
+/// The inode flags of an entry, as set by `chattr`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InodeFlags {
+    /// The raw flags, or [None] when they cannot be read
+    bits: Option<u32>,
+}
+
+impl InodeFlags {
+    /// Read the flags of `path` with the `FS_IOC_GETFLAGS` ioctl.
+    ///
+    /// Only regular files and directories are opened, as opening a device or a fifo can block
+    /// or have side effects, and symlinks have no flags of their own.
+    #[cfg(target_os = "linux")]
+    pub fn for_path(path: &Path, metadata: &Metadata) -> Self {
+        use std::os::unix::fs::OpenOptionsExt;
+        use std::os::unix::io::AsRawFd;
+
+        if !(metadata.is_file() || metadata.is_dir()) {
+            return Self { bits: None };
+        }
+        let Ok(file) = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK | libc::O_NOFOLLOW)
+            .open(path)
+        else {
+            return Self { bits: None };
+        };
+
+        // The kernel writes an int, whatever the size the request number claims
+        let mut bits: libc::c_int = 0;
+        // SAFETY: the descriptor is open for the whole call and `bits` outlives it
+        let result = unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut bits) };
+        Self {
+            bits: (result == 0).then_some(bits as u32),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn for_path(_: &Path, _: &Metadata) -> Self {
+        Self { bits: None }
+    }
+
+    pub fn from_bits(bits: u32) -> Self {
+        Self { bits: Some(bits) }
+    }
+
+    pub fn is_immutable(&self) -> bool {
+        self.has(FS_IMMUTABLE_FL)
+    }
+
+    pub fn is_append_only(&self) -> bool {
+        self.has(FS_APPEND_FL)
+    }
+
+    pub fn is_nodump(&self) -> bool {
+        self.has(FS_NODUMP_FL)
+    }
+
+    pub fn is_noatime(&self) -> bool {
+        self.has(FS_NOATIME_FL)
+    }
+
+    fn has(&self, flag: u32) -> bool {
+        self.bits.is_some_and(|bits| bits & flag != 0)
+    }
+
+    /// The flags like `lsattr` writes them, e.g. `i---` for an immutable file, or [None] when
+    /// they cannot be read.
+    pub fn text(&self) -> Option<String> {
+        let bits = self.bits?;
+        Some(
+            SHOWN
+                .iter()
+                .map(|&(flag, letter)| if bits & flag != 0 { letter } else { '-' })
+                .collect(),
+        )
+    }
+
+    pub fn render(&self, colors: &Colors) -> ColoredString {
+        let Some(bits) = self.bits else {
+            return colors.colorize("????", &Elem::NoAccess);
+        };
+        let mut res = String::with_capacity(SHOWN.len());
+        for &(flag, letter) in SHOWN.iter() {
+            let string = if bits & flag != 0 {
+                colors.colorize(letter, &Elem::Write)
+            } else {
+                colors.colorize('-', &Elem::NoAccess)
+            };
+            res += &string.to_string();
+        }
+        ColoredString::new(Colors::default_style(), res)
+    }
+}
@@ -37,12 +37,12 @@ impl Links {
     }
 
     /// Returns the number of links if available
-    pub fn _count(&self) -> Option<u64> {
+    pub fn count(&self) -> Option<u64> {
         self.link_count
     }
 
     /// Checks if link information is available
-    pub fn _is_available(&self) -> bool {
+    pub fn is_available(&self) -> bool {
         self.link_count.is_some()
     }
 }
@@ -0,0 +1,180 @@
+use crate::color::{ColoredString, Colors, Elem};
+use crate::meta::FileType;
+use std::io::Read;
+use std::path::Path;
+
+/// How many leading bytes are read, enough to reach the tar header magic at 257
+const SNIFF_LEN: usize = 512;
+
+/// A coarse class of content, which render rules match on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ContentClass {
+    /// Native or portable binaries
+    Executable,
+    /// Text run through the interpreter of its shebang
+    Script,
+    Archive,
+    Image,
+    Document,
+}
+
+/// The type of an entry, told from the leading bytes of its content.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Magic {
+    mime: &'static str,
+    class: Option<ContentClass>,
+    /// An extension of this type of content, whose icon stands in for a missing extension
+    extension: Option<&'static str>,
+}
+
+impl Magic {
+    const fn new(mime: &'static str, class: Option<ContentClass>, extension: Option<&'static str>) -> Self {
+        Self { mime, class, extension }
+    }
+
+    /// Detect the type of an entry, reading the start of regular files.
+    ///
+    /// Other entries get the `inode/*` types `file --mime-type` gives them. Returns [None]
+    /// when a regular file cannot be read.
+    pub fn detect(path: &Path, file_type: FileType) -> Option<Self> {
+        let mime = match file_type {
+            FileType::File { .. } => return Self::sniff_file(path),
+            FileType::Directory { .. } => "inode/directory",
+            FileType::SymLink { .. } => "inode/symlink",
+            FileType::Pipe => "inode/fifo",
+            FileType::Socket => "inode/socket",
+            FileType::CharDevice => "inode/chardevice",
+            FileType::BlockDevice => "inode/blockdevice",
+            FileType::Special => "application/octet-stream",
+        };
+        Some(Self::new(mime, None, None))
+    }
+
+    fn sniff_file(path: &Path) -> Option<Self> {
+        let mut head = Vec::with_capacity(SNIFF_LEN);
+        std::fs::File::open(path)
+            .and_then(|file| file.take(SNIFF_LEN as u64).read_to_end(&mut head))
+            .ok()?;
+        Some(Self::sniff(&head))
+    }
+
+    /// The type of content starting with `head`.
+    pub fn sniff(head: &[u8]) -> Self {
+        use ContentClass::*;
+
+        const SIGNATURES: &[(&[u8], Magic)] = &[
+            (b"\x7fELF", Magic::new("application/x-executable", Some(Executable), None)),
+            (b"\xfe\xed\xfa\xce", Magic::new("application/x-mach-binary", Some(Executable), None)),
+            (b"\xfe\xed\xfa\xcf", Magic::new("application/x-mach-binary", Some(Executable), None)),
+            (b"\xce\xfa\xed\xfe", Magic::new("application/x-mach-binary", Some(Executable), None)),
+            (b"\xcf\xfa\xed\xfe", Magic::new("application/x-mach-binary", Some(Executable), None)),
+            (b"\0asm", Magic::new("application/wasm", Some(Executable), Some("wasm"))),
+            (b"\x89PNG\r\n\x1a\n", Magic::new("image/png", Some(Image), Some("png"))),
+            (b"\xff\xd8\xff", Magic::new("image/jpeg", Some(Image), Some("jpg"))),
+            (b"GIF87a", Magic::new("image/gif", Some(Image), Some("gif"))),
+            (b"GIF89a", Magic::new("image/gif", Some(Image), Some("gif"))),
+            (b"%PDF-", Magic::new("application/pdf", Some(Document), Some("pdf"))),
+            (b"\x1f\x8b", Magic::new("application/gzip", Some(Archive), Some("gz"))),
+            (b"PK\x03\x04", Magic::new("application/zip", Some(Archive), Some("zip"))),
+            (b"PK\x05\x06", Magic::new("application/zip", Some(Archive), Some("zip"))),
+            (b"BZh", Magic::new("application/x-bzip2", Some(Archive), Some("bz2"))),
+            (b"\xfd7zXZ\0", Magic::new("application/x-xz", Some(Archive), Some("xz"))),
+            (b"\x28\xb5\x2f\xfd", Magic::new("application/zstd", Some(Archive), Some("zst"))),
+            (b"7z\xbc\xaf\x27\x1c", Magic::new("application/x-7z-compressed", Some(Archive), Some("7z"))),
+            (b"SQLite format 3\0", Magic::new("application/vnd.sqlite3", None, Some("sqlite"))),
+        ];
+
+        if head.is_empty() {
+            return Self::new("inode/x-empty", None, None);
+        }
+        if let Some(script) = head.strip_prefix(b"#!") {
+            return Self::script(script);
+        }
+        if let Some((_, magic)) = SIGNATURES.iter().find(|(signature, _)| head.starts_with(signature)) {
+            return *magic;
+        }
+        if is_portable_executable(head) {
+            return Self::new("application/vnd.microsoft.portable-executable", Some(Executable), Some("exe"));
+        }
+        if head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP" {
+            return Self::new("image/webp", Some(Image), Some("webp"));
+        }
+        if head.get(257..262) == Some(b"ustar") {
+            return Self::new("application/x-tar", Some(Archive), Some("tar"));
+        }
+        if is_text(head) {
+            Self::new("text/plain", None, None)
+        } else {
+            Self::new("application/octet-stream", None, None)
+        }
+    }
+
+    /// A script, typed after the interpreter of its shebang line
+    fn script(shebang: &[u8]) -> Self {
+        let line = shebang.split(|byte| *byte == b'\n').next().unwrap_or_default();
+        let line = String::from_utf8_lossy(line);
+        let mut words = line.split_whitespace();
+        let program = words.next().and_then(|path| path.rsplit('/').next()).unwrap_or_default();
+        // `#!/usr/bin/env python3` names the interpreter in its argument
+        let interpreter = if program == "env" {
+            words.find(|word| !word.starts_with('-')).unwrap_or_default()
+        } else {
+            program
+        };
+
+        let (mime, extension) = match interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+            "python" => ("text/x-script.python", "py"),
+            "perl" => ("text/x-perl", "pl"),
+            "ruby" => ("text/x-ruby", "rb"),
+            "node" | "deno" | "bun" => ("text/javascript", "js"),
+            "lua" => ("text/x-lua", "lua"),
+            "php" => ("text/x-php", "php"),
+            _ => ("text/x-shellscript", "sh"),
+        };
+        Self::new(mime, Some(ContentClass::Script), Some(extension))
+    }
+
+    pub fn mime(&self) -> &'static str {
+        self.mime
+    }
+
+    pub fn class(&self) -> Option<ContentClass> {
+        self.class
+    }
+
+    pub fn extension(&self) -> Option<&'static str> {
+        self.extension
+    }
+
+    pub fn render(&self, colors: &Colors) -> ColoredString {
+        colors.colorize(self.mime, &Elem::Context)
+    }
+}
+
+/// Whether the bytes look like text: valid UTF-8, allowing a character cut at the end, and
+/// free of control characters other than whitespace
+fn is_text(head: &[u8]) -> bool {
+    let valid = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(error) if error.error_len().is_none() => {
+            std::str::from_utf8(&head[..error.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+    !valid
+        .chars()
+        .any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t' | '\x0c' | '\x1b'))
+}
+
+/// Whether the bytes start a Windows executable: the `MZ` DOS stub alone is two letters any
+/// text may start with, so the `PE\0\0` header it points to at 0x3c must follow
+fn is_portable_executable(head: &[u8]) -> bool {
+    if !head.starts_with(b"MZ") {
+        return false;
+    }
+    let Some(offset) = head.get(0x3c..0x40) else {
+        return false;
+    };
+    let offset = u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize;
+    offset.checked_add(4).and_then(|end| head.get(offset..end)) == Some(b"PE\0\0")
+}
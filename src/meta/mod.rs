@@ -1,10 +1,17 @@
 mod access_control;
+pub mod acl;
+mod capabilities;
 mod date;
+mod diff_status;
+mod digest;
+mod filesystem;
 mod filetype;
 pub mod git_file_status;
 mod indicator;
 mod inode;
+mod inode_flags;
 mod links;
+mod magic;
 mod locale;
 pub mod name;
 pub mod owner;
@@ -12,6 +19,7 @@ mod permissions;
 pub mod permissions_or_attributes;
 mod size;
 mod symlink;
+mod xattrs;
 
 #[cfg(windows)]
 pub mod windows_attributes;
@@ -19,20 +27,28 @@ pub mod windows_attributes;
 pub mod windows_utils;
 
 pub use self::access_control::AccessControl;
-pub use self::date::Date;
+pub use self::acl::{Acl, AclEntry, AclTag};
+pub use self::capabilities::Capabilities;
+pub use self::date::{Date, Timestamps};
+pub use self::diff_status::DiffStatus;
+pub use self::digest::Digest;
+pub use self::filesystem::{FileSystem, Mount, MountTable};
 pub use self::filetype::FileType;
 pub use self::git_file_status::GitFileStatus;
 pub use self::indicator::Indicator;
 pub use self::inode::INode;
+pub use self::inode_flags::InodeFlags;
 pub use self::links::Links;
+pub use self::magic::{ContentClass, Magic};
 pub use self::name::Name;
 pub use self::owner::{Cache as OwnerCache, Owner};
 pub use self::permissions::Permissions;
 pub use self::permissions_or_attributes::PermissionsOrAttributes;
 pub use self::size::Size;
 pub use self::symlink::SymLink;
+pub use self::xattrs::Xattrs;
 
-use crate::flags::{Display, Flags, Layout, PermissionFlag};
+use crate::flags::{Display, Flags, Layout, PermissionFlag, TimeField};
 use crate::ExitCode;
 
 use crate::git::GitCache;
@@ -53,7 +69,9 @@ pub struct Meta {
     pub path: PathBuf,
     pub canonical_path: Option<PathBuf>,
     pub permissions_or_attributes: Option<PermissionsOrAttributes>,
+    /// The timestamp picked by `--time`
     pub date: Option<Date>,
+    pub timestamps: Option<Timestamps>,
     pub owner: Option<Owner>,
     pub file_type: FileType,
     pub size: Option<Size>,
@@ -63,6 +81,22 @@ pub struct Meta {
     pub links: Option<Links>,
     pub content: Option<Vec<Meta>>,
     pub access_control: Option<AccessControl>,
+    /// Only read when shown, by the xattrs block or `-@`
+    pub xattrs: Option<Xattrs>,
+    /// Only read when shown, by the caps block
+    pub capabilities: Option<Capabilities>,
+    /// Only read when shown, by the flags block
+    pub inode_flags: Option<InodeFlags>,
+    /// Only read when shown, by the fs block
+    pub filesystem: Option<FileSystem>,
+    /// Whether the entry is a directory something is mounted on
+    pub mount_point: bool,
+    /// Only sniffed when shown, by the mime block, or for the icon and color of extensionless files
+    pub magic: Option<Magic>,
+    /// Only computed when shown, by the hash block
+    pub digest: Option<Digest>,
+    /// Set by `--diff`, from comparing the entry with its counterpart in the other tree
+    pub diff_status: Option<DiffStatus>,
     pub git_status: Option<GitFileStatus>,
 }
 
@@ -302,7 +336,7 @@ impl Meta {
 
         // Create ".." entry
         let parent_path = self.path.join(Component::ParentDir);
-        let mut parent_meta = Self::from_path(&parent_path, flags.dereference.0, flags.permission)?;
+        let mut parent_meta = Self::from_path(&parent_path, flags.dereference.0, flags.permission, flags.time)?;
         "..".clone_into(&mut parent_meta.name.name);
         parent_meta.git_status = cache.and_then(|c| c.get(&parent_meta.path, parent_meta.canonical_path.as_ref(), true));
         entries.push(parent_meta);
@@ -310,68 +344,31 @@ impl Meta {
         Ok(entries)
     }
 
-    #[allow(dead_code)] // Used by old code path
-    pub fn calculate_total_size(&mut self) {
-        if self.size.is_none() || !matches!(self.file_type, FileType::Directory { .. }) {
-            return;
-        }
-
-        let base_size = self.size.as_ref().map_or(0, |s| s.get_bytes());
-
-        if let Some(ref mut metas) = self.content {
-            let total_size = metas
-                .iter_mut()
-                .filter(|meta| !matches!(meta.name.name.as_str(), "." | ".."))
-                .map(|meta| {
-                    meta.calculate_total_size();
-                    meta.size.as_ref().map_or(0, |s| s.get_bytes())
-                })
-                .fold(base_size, |acc, size| acc.saturating_add(size));
-
-            self.size = Some(Size::new(total_size));
-        } else {
-            // Depth limited the recursion in 'recurse_into'
-            self.size = Some(Size::new(Self::calculate_total_file_size(&self.path)));
-        }
-    }
-
-    #[allow(dead_code)] // Used by old code path
-    fn calculate_total_file_size(path: &Path) -> u64 {
-        let metadata = match path.symlink_metadata() {
-            Ok(meta) => meta,
-            Err(err) => {
-                log::error!("{}: {}", path.display(), err);
-                return 0;
+    /// Nest each entry under its parent when that is listed too, returning the roots in no
+    /// particular order
+    pub fn nest(mut entries: Vec<(PathBuf, Meta)>) -> Vec<Meta> {
+        // Deepest first, so each entry is complete when it is attached
+        let listed: std::collections::HashSet<PathBuf> = entries.iter().map(|(path, _)| path.clone()).collect();
+        entries.sort_by_key(|(path, _)| std::cmp::Reverse(path.components().count()));
+        let mut children: HashMap<PathBuf, Vec<Meta>> = HashMap::new();
+        let mut roots = Vec::new();
+        for (path, mut meta) in entries {
+            if let Some(content) = children.remove(&path) {
+                meta.content = Some(content);
             }
-        };
-
-        let file_type = metadata.file_type();
-        if file_type.is_file() {
-            metadata.len()
-        } else if file_type.is_dir() {
-            let mut size = metadata.len();
-
-            let entries = match path.read_dir() {
-                Ok(entries) => entries,
-                Err(err) => {
-                    log::error!("{}: {}", path.display(), err);
-                    return size;
-                }
-            };
-
-            for entry in entries.flatten() {
-                size = size.saturating_add(Self::calculate_total_file_size(&entry.path()));
+            match path.parent().filter(|parent| listed.contains(*parent)) {
+                Some(parent) => children.entry(parent.to_path_buf()).or_default().push(meta),
+                None => roots.push(meta),
             }
-            size
-        } else {
-            0
         }
+        roots
     }
 
     pub fn from_path(
         path: &Path,
         dereference: bool,
         permission_flag: PermissionFlag,
+        time_field: TimeField,
     ) -> io::Result<Self> {
         let mut metadata = path.symlink_metadata()?;
         let mut symlink_meta = None;
@@ -447,6 +444,7 @@ impl Meta {
                 symlink: SymLink::from(path),
                 size: None,
                 date: None,
+                timestamps: None,
                 indicator: Indicator::from(file_type),
                 owner,
                 permissions_or_attributes,
@@ -454,9 +452,18 @@ impl Meta {
                 file_type,
                 content: None,
                 access_control: None,
+                xattrs: None,
+                capabilities: None,
+                inode_flags: None,
+                filesystem: None,
+                mount_point: false,
+                magic: None,
+                digest: None,
+                diff_status: None,
                 git_status: None,
             })
         } else {
+            let timestamps = Timestamps::from(&metadata);
             Ok(Self {
                 inode: Some(INode::from(&metadata)),
                 links: Some(Links::from(&metadata)),
@@ -464,7 +471,8 @@ impl Meta {
                 canonical_path,
                 symlink: SymLink::from(path),
                 size: Some(Size::from(&metadata)),
-                date: Some(Date::from(&metadata)),
+                date: Some(timestamps.get(time_field).clone()),
+                timestamps: Some(timestamps),
                 indicator: Indicator::from(file_type),
                 owner,
                 permissions_or_attributes,
@@ -472,6 +480,14 @@ impl Meta {
                 file_type,
                 content: None,
                 access_control: Some(AccessControl::for_path(path)),
+                xattrs: None,
+                capabilities: None,
+                inode_flags: None,
+                filesystem: None,
+                mount_point: false,
+                magic: None,
+                digest: None,
+                diff_status: None,
                 git_status: None,
             })
         }
@@ -515,7 +531,7 @@ fn process_entry(
     }
 
     // Create meta for this entry
-    let mut entry_meta = match Meta::from_path(&path, flags.dereference.0, flags.permission) {
+    let mut entry_meta = match Meta::from_path(&path, flags.dereference.0, flags.permission, flags.time) {
         Ok(meta) => meta,
         Err(err) => {
             log::error!("{}: {}", path.display(), err);
@@ -1,8 +1,8 @@
-use crate::color::{ColoredString, Colors};
+use crate::color::{ColoredString, Colors, Elem};
 use crate::flags::HyperlinkOption;
 use crate::icon::Icons;
 use crate::meta::filetype::FileType;
-use crate::meta::GitFileStatus;
+use crate::meta::{GitFileStatus, Magic};
 use url::Url;
 use std::cmp::{Ordering, PartialOrd};
 use std::ffi::OsStr;
@@ -26,6 +26,7 @@ pub struct RenderBuilder<'a> {
     literal: bool,
     git_status: Option<&'a GitFileStatus>,
     cached_canonical: Option<&'a PathBuf>,
+    mount_point: bool,
 }
 
 impl<'a> RenderBuilder<'a> {
@@ -38,6 +39,7 @@ impl<'a> RenderBuilder<'a> {
             literal: false,
             git_status: None,
             cached_canonical: None,
+            mount_point: false,
         }
     }
 
@@ -60,6 +62,12 @@ impl<'a> RenderBuilder<'a> {
         self.cached_canonical = cached_canonical;
         self
     }
+
+    /// Color the name as a directory something is mounted on
+    pub fn mount_point(mut self, mount_point: bool) -> Self {
+        self.mount_point = mount_point;
+        self
+    }
 }
 
 /// Represents a file or directory name with associated metadata
@@ -69,6 +77,8 @@ pub struct Name {
     path: PathBuf,
     extension: Option<String>,
     file_type: FileType,
+    /// The sniffed content type, standing in for a missing extension
+    magic: Option<Magic>,
 }
 
 impl Name {
@@ -88,9 +98,16 @@ impl Name {
             path: PathBuf::from(path),
             extension,
             file_type,
+            magic: None,
         }
     }
 
+    /// Attach the type sniffed from the content, used when the name has no extension
+    pub fn with_magic(mut self, magic: Option<Magic>) -> Self {
+        self.magic = magic;
+        self
+    }
+
     /// Returns the file name as a string slice
     pub fn file_name(&self) -> &str {
         self.path
@@ -240,6 +257,7 @@ impl Name {
         let decision = colors.render_decision(
             &self.file_type,
             self.extension.as_deref(),
+            self.content_magic().and_then(|magic| magic.class()),
             git_status,
             false, // has_error - future feature
             false, // draw_attention - future feature
@@ -258,7 +276,11 @@ impl Name {
             String::new()
         };
         
-        let colored_name = decision.name_style.apply(&hyperlinked_name).to_string();
+        let colored_name = if builder.mount_point {
+            colors.colorize(hyperlinked_name, &Elem::MountPoint).to_string()
+        } else {
+            decision.name_style.apply(&hyperlinked_name).to_string()
+        };
         
         // Combine colored icon and colored name
         ColoredString::new(Colors::default_style(), format!("{colored_icon}{colored_name}"))
@@ -273,6 +295,11 @@ impl Name {
     pub fn file_type(&self) -> FileType {
         self.file_type
     }
+
+    /// Returns the sniffed content type when the name has no extension to go by
+    pub fn content_magic(&self) -> Option<&Magic> {
+        self.magic.as_ref().filter(|_| self.extension.is_none())
+    }
 }
 
 impl Ord for Name {
@@ -13,11 +13,44 @@ pub struct Cache {
     groups: UsersCache,
 }
 
+#[cfg(unix)]
+impl Cache {
+    /// The name of a user, or its uid when it has none
+    pub fn user_name(&self, uid: u32) -> String {
+        match self.users.get_user_by_uid(uid) {
+            Some(user) => user.name().to_string_lossy().to_string(),
+            None => uid.to_string(),
+        }
+    }
+
+    /// The name of a group, or its gid when it has none
+    pub fn group_name(&self, gid: u32) -> String {
+        match self.groups.get_group_by_gid(gid) {
+            Some(group) => group.name().to_string_lossy().to_string(),
+            None => gid.to_string(),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+impl Cache {
+    pub fn user_name(&self, uid: u32) -> String {
+        uid.to_string()
+    }
+
+    pub fn group_name(&self, gid: u32) -> String {
+        gid.to_string()
+    }
+}
+
 #[cfg(unix)]
 #[derive(Clone, Debug, Default)]
 pub struct Owner {
     user: u32,
     group: u32,
+    /// The names stored along with the ids, by archives
+    recorded_user: Option<String>,
+    recorded_group: Option<String>,
 }
 
 #[cfg(windows)]
@@ -32,6 +65,33 @@ impl Owner {
     pub fn new(user: String, group: String) -> Self {
         Self { user, group }
     }
+
+    /// The owner stored for an archive member, whose names are shown rather than the ones of
+    /// the ids on this system
+    #[cfg(unix)]
+    pub fn recorded(uid: u32, gid: u32, user: Option<String>, group: Option<String>) -> Self {
+        Self {
+            user: uid,
+            group: gid,
+            recorded_user: user,
+            recorded_group: group,
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn recorded(uid: u32, gid: u32, user: Option<String>, group: Option<String>) -> Self {
+        Self::new(user.unwrap_or_else(|| uid.to_string()), group.unwrap_or_else(|| gid.to_string()))
+    }
+
+    #[cfg(unix)]
+    pub fn uid(&self) -> u32 {
+        self.user
+    }
+
+    #[cfg(unix)]
+    pub fn gid(&self) -> u32 {
+        self.group
+    }
 }
 
 #[cfg(unix)]
@@ -42,6 +102,8 @@ impl From<&Metadata> for Owner {
         Self {
             user: meta.uid(),
             group: meta.gid(),
+            recorded_user: None,
+            recorded_group: None,
         }
     }
 }
@@ -64,18 +126,30 @@ fn truncate(input: &str, after: Option<usize>, marker: Option<String>) -> String
 impl Owner {
     // allow unused variables because cache is used in unix, maybe we can cache for windows in the future
     #[allow(unused_variables)]
-    pub fn render_user(&self, colors: &Colors, cache: &Cache, flags: &Flags) -> ColoredString {
+    pub fn user_name(&self, cache: &Cache) -> String {
         #[cfg(unix)]
-        let user = &match cache.users.get_user_by_uid(self.user) {
-            Some(user) => user.name().to_string_lossy().to_string(),
-            None => self.user.to_string(),
-        };
+        let name = self.recorded_user.clone().unwrap_or_else(|| cache.user_name(self.user));
         #[cfg(windows)]
-        let user = &self.user;
+        let name = self.user.clone();
+
+        name
+    }
 
+    // allow unused variables because cache is used in unix, maybe we can cache for windows in the future
+    #[allow(unused_variables)]
+    pub fn group_name(&self, cache: &Cache) -> String {
+        #[cfg(unix)]
+        let name = self.recorded_group.clone().unwrap_or_else(|| cache.group_name(self.group));
+        #[cfg(windows)]
+        let name = self.group.clone();
+
+        name
+    }
+
+    pub fn render_user(&self, colors: &Colors, cache: &Cache, flags: &Flags) -> ColoredString {
         colors.colorize(
             truncate(
-                user,
+                &self.user_name(cache),
                 flags.truncate_owner.after,
                 flags.truncate_owner.marker.clone(),
             ),
@@ -83,20 +157,10 @@ impl Owner {
         )
     }
 
-    // allow unused variables because cache is used in unix, maybe we can cache for windows in the future
-    #[allow(unused_variables)]
     pub fn render_group(&self, colors: &Colors, cache: &Cache, flags: &Flags) -> ColoredString {
-        #[cfg(unix)]
-        let group = &match cache.groups.get_group_by_gid(self.group) {
-            Some(group) => group.name().to_string_lossy().to_string(),
-            None => self.group.to_string(),
-        };
-        #[cfg(windows)]
-        let group = &self.group;
-
         colors.colorize(
             truncate(
-                group,
+                &self.group_name(cache),
                 flags.truncate_owner.after,
                 flags.truncate_owner.marker.clone(),
             ),
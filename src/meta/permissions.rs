@@ -26,7 +26,19 @@ impl From<&Metadata> for Permissions {
     fn from(meta: &Metadata) -> Self {
         use std::os::unix::fs::PermissionsExt;
 
-        let bits = meta.permissions().mode();
+        Self::from_mode(meta.permissions().mode())
+    }
+
+    #[cfg(windows)]
+    fn from(_: &Metadata) -> Self {
+        panic!("Cannot get permissions from metadata on Windows")
+    }
+}
+
+impl Permissions {
+    /// The permissions of the mode bits of an inode, which may include its file type
+    #[cfg(unix)]
+    pub fn from_mode(bits: u32) -> Self {
         let has_bit = |bit| bits & bit == bit;
 
         Self {
@@ -48,18 +60,11 @@ impl From<&Metadata> for Permissions {
         }
     }
 
-    #[cfg(windows)]
-    fn from(_: &Metadata) -> Self {
-        panic!("Cannot get permissions from metadata on Windows")
-    }
-}
-
-impl Permissions {
     fn bits_to_octal(r: bool, w: bool, x: bool) -> u8 {
         (r as u8) * 4 + (w as u8) * 2 + (x as u8)
     }
 
-    pub fn _mode(&self) -> u32 {
+    pub fn mode(&self) -> u32 {
         let user = Self::bits_to_octal(self.user_read, self.user_write, self.user_execute) as u32;
         let group = Self::bits_to_octal(self.group_read, self.group_write, self.group_execute) as u32;
         let other = Self::bits_to_octal(self.other_read, self.other_write, self.other_execute) as u32;
@@ -1,11 +1,10 @@
 use crate::color::{ColoredString, Colors, Elem};
-use crate::flags::{Flags, SizeFlag};
+use crate::flags::{BlockSize, Flags, SizeFlag};
 use std::fs::Metadata;
 
 const KB: u64 = 1024;
 const MB: u64 = 1024_u64.pow(2);
 const GB: u64 = 1024_u64.pow(3);
-const TB: u64 = 1024_u64.pow(4);
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Unit {
@@ -18,25 +17,68 @@ pub enum Unit {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Size {
+    /// The bytes shown and sorted by
     bytes: u64,
+    /// The apparent and allocated bytes of the entry itself, when read from its metadata
+    footprint: Option<(u64, u64)>,
 }
 
 impl From<&Metadata> for Size {
     fn from(meta: &Metadata) -> Self {
-        Self { bytes: meta.len() }
+        Self {
+            bytes: meta.len(),
+            footprint: Some((meta.len(), allocated_bytes(meta))),
+        }
     }
 }
 
+/// The space a file takes on disk, which holes make smaller than its length
+#[cfg(unix)]
+fn allocated_bytes(meta: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    // st_blocks is always counted in 512 byte units
+    meta.blocks().saturating_mul(512)
+}
+
+#[cfg(not(unix))]
+fn allocated_bytes(meta: &Metadata) -> u64 {
+    meta.len()
+}
+
 impl Size {
-    #[allow(dead_code)] // Used by old code path
     pub fn new(bytes: u64) -> Self {
-        Self { bytes }
+        Self { bytes, footprint: None }
+    }
+
+    /// Show and sort by the allocated bytes instead of the apparent ones
+    pub fn on_disk(self) -> Self {
+        match self.footprint {
+            Some((_, allocated)) => Self { bytes: allocated, ..self },
+            None => self,
+        }
+    }
+
+    /// Replace the bytes shown, keeping the footprint of the entry itself
+    pub fn with_bytes(self, bytes: u64) -> Self {
+        Self { bytes, ..self }
     }
 
     pub fn get_bytes(&self) -> u64 {
         self.bytes
     }
 
+    /// The bytes allocated to the entry itself
+    pub fn allocated(&self) -> Option<u64> {
+        self.footprint.map(|(_, allocated)| allocated)
+    }
+
+    /// Whether at least 1 MiB long with less than half of it allocated
+    pub fn is_sparse(&self) -> bool {
+        self.footprint
+            .is_some_and(|(apparent, allocated)| apparent >= MB && allocated < apparent / 2)
+    }
+
     fn format_size(&self, number: f64) -> String {
         if number < 10.0 {
             format!("{:.1}", number)
@@ -46,19 +88,31 @@ impl Size {
     }
 
     fn get_unit(&self, flags: &Flags) -> Unit {
+        match flags.block_size {
+            BlockSize::Kilo => return Unit::Kilo,
+            BlockSize::Mega => return Unit::Mega,
+            BlockSize::Giga => return Unit::Giga,
+            BlockSize::Default | BlockSize::Si => {}
+        }
         if flags.size == SizeFlag::Bytes {
             return Unit::Byte;
         }
 
+        let base = Self::base(flags);
         match self.bytes {
-            b if b < KB => Unit::Byte,
-            b if b < MB => Unit::Kilo,
-            b if b < GB => Unit::Mega,
-            b if b < TB => Unit::Giga,
+            b if b < base => Unit::Byte,
+            b if b < base.pow(2) => Unit::Kilo,
+            b if b < base.pow(3) => Unit::Mega,
+            b if b < base.pow(4) => Unit::Giga,
             _ => Unit::Tera,
         }
     }
 
+    /// The factor between two units, 1000 with `--block-size=SI` and 1024 otherwise
+    fn base(flags: &Flags) -> u64 {
+        if flags.block_size == BlockSize::Si { 1000 } else { KB }
+    }
+
     pub fn render(
         &self,
         colors: &Colors,
@@ -100,7 +154,9 @@ impl Size {
     fn paint(&self, colors: &Colors, content: String) -> ColoredString {
         let bytes = self.get_bytes();
 
-        let elem = if bytes >= GB {
+        let elem = if self.is_sparse() {
+            &Elem::FileSparse
+        } else if bytes >= GB {
             &Elem::FileLarge
         } else if bytes >= MB {
             &Elem::FileMedium
@@ -117,17 +173,36 @@ impl Size {
     }
 
     pub fn value_string(&self, flags: &Flags) -> String {
-        let unit = self.get_unit(flags);
+        let base = Self::base(flags);
+        let divisor = match self.get_unit(flags) {
+            Unit::Byte => return self.bytes.to_string(),
+            Unit::Kilo => base,
+            Unit::Mega => base.pow(2),
+            Unit::Giga => base.pow(3),
+            Unit::Tera => base.pow(4),
+        };
 
-        match unit {
-            Unit::Byte => self.bytes.to_string(),
-            Unit::Kilo => self.format_size(self.bytes as f64 / KB as f64),
-            Unit::Mega => self.format_size(self.bytes as f64 / MB as f64),
-            Unit::Giga => self.format_size(self.bytes as f64 / GB as f64),
-            Unit::Tera => self.format_size(self.bytes as f64 / TB as f64),
+        match flags.block_size {
+            // A fixed unit rounds up, like `ls --block-size`
+            BlockSize::Kilo | BlockSize::Mega | BlockSize::Giga => self.bytes.div_ceil(divisor).to_string(),
+            BlockSize::Default | BlockSize::Si => self.format_size(self.bytes as f64 / divisor as f64),
         }
     }
 
+    /// The allocated space of the entry itself counted in blocks, like `ls -s`.
+    ///
+    /// Blocks are 1 KiB unless `--block-size` says otherwise.
+    pub fn render_blocks(&self, colors: &Colors, flags: &Flags) -> Option<ColoredString> {
+        let block = match flags.block_size {
+            BlockSize::Default | BlockSize::Kilo => KB,
+            BlockSize::Mega => MB,
+            BlockSize::Giga => GB,
+            BlockSize::Si => 1000,
+        };
+        self.allocated()
+            .map(|allocated| self.paint(colors, allocated.div_ceil(block).to_string()))
+    }
+
     pub fn render_unit(&self, colors: &Colors, flags: &Flags) -> ColoredString {
         let content = self.unit_string(flags);
         self.paint(colors, content)
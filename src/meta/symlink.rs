@@ -3,7 +3,7 @@ use crate::flags::Flags;
 use std::fs::read_link;
 use std::path::{Path, PathBuf};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct SymLink {
     target: Option<PathBuf>,
     valid: bool,
@@ -36,6 +36,18 @@ impl From<&Path> for SymLink {
 }
 
 impl SymLink {
+    /// A link whose target is known without reading it, such as an archive member's
+    pub fn new(target: PathBuf, valid: bool) -> Self {
+        Self {
+            target: Some(target),
+            valid,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
     pub fn symlink_string(&self) -> Option<String> {
         self.target
             .as_ref()
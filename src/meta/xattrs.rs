@@ -0,0 +1,94 @@
+use crate::color::{ColoredString, Colors, Elem};
+use std::path::Path;
+
+/// Longest value shown by `-@`, in characters of text or bytes of hex
+const VALUE_PREVIEW: usize = 32;
+
+/// The extended attributes of an entry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Xattrs {
+    /// Names and values, or [None] when they cannot be listed
+    attributes: Option<Vec<(String, Vec<u8>)>>,
+}
+
+impl Xattrs {
+    /// List the attributes of `path`, without following a symlink.
+    ///
+    /// Values are only read `with_values`, as counting them only needs the names.
+    #[cfg(unix)]
+    pub fn for_path(path: &Path, with_values: bool) -> Self {
+        let attributes = xattr::list(path).ok().map(|names| {
+            names
+                .map(|name| {
+                    let value = with_values
+                        .then(|| xattr::get(path, &name).ok().flatten())
+                        .flatten()
+                        .unwrap_or_default();
+                    (name.to_string_lossy().into_owned(), value)
+                })
+                .collect()
+        });
+        Self { attributes }
+    }
+
+    #[cfg(not(unix))]
+    pub fn for_path(_: &Path, _with_values: bool) -> Self {
+        Self { attributes: None }
+    }
+
+    pub fn count(&self) -> Option<usize> {
+        self.attributes.as_ref().map(Vec::len)
+    }
+
+    pub fn render_count(&self, colors: &Colors) -> ColoredString {
+        match self.count() {
+            Some(count) => colors.colorize(count.to_string(), &Elem::Context),
+            None => colors.colorize('?', &Elem::NoAccess),
+        }
+    }
+
+    /// One line per attribute with its name, the size of its value and a preview of it, like
+    /// `ls -@`
+    pub fn render_lines(&self, colors: &Colors) -> Vec<String> {
+        let Some(attributes) = &self.attributes else {
+            return Vec::new();
+        };
+        let name_width = attributes.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        let size_width = attributes.iter().map(|(_, value)| value.len().to_string().len()).max().unwrap_or(0);
+
+        attributes
+            .iter()
+            .map(|(name, value)| {
+                format!(
+                    "    {}{}  {:>size_width$}  {}",
+                    colors.colorize(name.as_str(), &Elem::Context),
+                    " ".repeat(name_width - name.len()),
+                    value.len(),
+                    preview(value),
+                )
+            })
+            .collect()
+    }
+}
+
+/// A value as quoted text when it decodes to printable UTF-8, or as hex otherwise
+fn preview(value: &[u8]) -> String {
+    // C strings are often stored with their terminator
+    let text = value.strip_suffix(&[0]).unwrap_or(value);
+    match std::str::from_utf8(text) {
+        Ok(text) if !text.chars().any(char::is_control) => {
+            let mut shown: String = text.chars().take(VALUE_PREVIEW).collect();
+            if shown.len() < text.len() {
+                shown.push('…');
+            }
+            format!("{shown:?}")
+        }
+        _ => {
+            let mut hex: String = value.iter().take(VALUE_PREVIEW).map(|byte| format!("{byte:02x}")).collect();
+            if value.len() > VALUE_PREVIEW {
+                hex.push('…');
+            }
+            format!("0x{hex}")
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! JSON records for `--output json` and `--output ndjson`
+//!
+//! Records carry raw values (bytes, octal modes, RFC 3339 dates) rather than the
+//! human-readable renderings, so scripts do not depend on the text layout flags.
+
+use serde::Serialize;
+
+use crate::meta::{acl, Acl, Date, DiffStatus, FileType, GitFileStatus, Meta, OwnerCache, PermissionsOrAttributes};
+use crate::stream::DuplicateSet;
+
+/// A serializable view of a [Meta]
+#[derive(Debug, Serialize)]
+pub struct JsonEntry {
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "type")]
+    pub file_type: &'static str,
+    pub size: Option<u64>,
+    pub modified: Option<String>,
+    pub owner: Option<JsonOwner>,
+    pub permissions: Option<String>,
+    pub inode: Option<u64>,
+    pub links: Option<u64>,
+    pub symlink: Option<JsonSymlink>,
+    pub git_status: Option<GitFileStatus>,
+    /// The access ACL entries, for files that have one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acl: Option<Vec<JsonAclEntry>>,
+    /// The full content digest, for files when the hash block is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// How the entry compares between the trees of `--diff`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<DiffStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<Vec<JsonEntry>>,
+}
+
+/// A set of files with identical content, for `--duplicates`
+#[derive(Debug, Serialize)]
+pub struct JsonDuplicateSet {
+    /// The size of each copy in bytes
+    pub size: u64,
+    /// How many distinct inodes hold the content, hard links counting once
+    pub copies: usize,
+    /// The bytes freed by keeping a single copy
+    pub wasted: u64,
+    pub hash: String,
+    pub files: Vec<JsonEntry>,
+}
+
+impl JsonDuplicateSet {
+    /// The record of `set`, whose entries are given as `metas`
+    pub fn new(set: &DuplicateSet, metas: &[Meta], owner_cache: &OwnerCache) -> Self {
+        Self {
+            size: set.size,
+            copies: set.copies(),
+            wasted: set.wasted(),
+            hash: set.digest.hex().to_string(),
+            files: metas.iter().map(|meta| JsonEntry::from_meta(meta, owner_cache)).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonOwner {
+    pub user: String,
+    pub group: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonAclEntry {
+    pub tag: &'static str,
+    /// The name of a named user or group
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qualifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>,
+    pub permissions: String,
+    /// The permissions left once the mask is applied
+    pub effective: String,
+}
+
+impl JsonAclEntry {
+    fn from_acl(acl: &Acl, owner_cache: &OwnerCache) -> Vec<Self> {
+        acl.entries()
+            .iter()
+            .map(|entry| Self {
+                tag: entry.tag.name(),
+                qualifier: entry.id.map(|_| entry.qualifier(owner_cache)),
+                id: entry.id,
+                permissions: acl::rwx(entry.permissions),
+                effective: acl::rwx(acl.effective(entry)),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonSymlink {
+    pub target: String,
+    pub valid: bool,
+}
+
+impl JsonEntry {
+    pub fn from_meta(meta: &Meta, owner_cache: &OwnerCache) -> Self {
+        Self {
+            name: meta.name.file_name().to_string(),
+            path: meta.path.to_string_lossy().to_string(),
+            file_type: file_type_name(meta.file_type),
+            size: meta.size.as_ref().map(|size| size.get_bytes()),
+            modified: match meta.timestamps.as_ref().map(|timestamps| &timestamps.modified) {
+                Some(Date::Date(datetime)) => Some(datetime.to_rfc3339()),
+                Some(Date::Invalid) | None => None,
+            },
+            owner: meta.owner.as_ref().map(|owner| JsonOwner {
+                user: owner.user_name(owner_cache),
+                group: owner.group_name(owner_cache),
+                #[cfg(unix)]
+                uid: Some(owner.uid()),
+                #[cfg(unix)]
+                gid: Some(owner.gid()),
+                #[cfg(windows)]
+                uid: None,
+                #[cfg(windows)]
+                gid: None,
+            }),
+            permissions: match &meta.permissions_or_attributes {
+                Some(PermissionsOrAttributes::Permissions(permissions)) => {
+                    Some(format!("{:04o}", permissions.mode()))
+                }
+                #[cfg(windows)]
+                Some(PermissionsOrAttributes::WindowsAttributes(_)) => None,
+                None => None,
+            },
+            inode: meta.inode.and_then(|inode| inode.index()),
+            links: meta.links.and_then(|links| links.count()),
+            symlink: meta.symlink.symlink_string().map(|target| JsonSymlink {
+                target,
+                valid: meta.symlink.is_valid(),
+            }),
+            git_status: meta.git_status,
+            acl: meta
+                .access_control
+                .as_ref()
+                .and_then(|access| access.acl())
+                .map(|acl| JsonAclEntry::from_acl(acl, owner_cache)),
+            hash: meta.digest.as_ref().map(|digest| digest.hex().to_string()),
+            diff: meta.diff_status,
+            content: meta.content.as_ref().map(|content| {
+                content
+                    .iter()
+                    .map(|child| Self::from_meta(child, owner_cache))
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Render the entries as one pretty-printed JSON array.
+pub fn array(metas: &[Meta]) -> serde_json::Result<String> {
+    let owner_cache = OwnerCache::default();
+    let entries: Vec<JsonEntry> = metas
+        .iter()
+        .map(|meta| JsonEntry::from_meta(meta, &owner_cache))
+        .collect();
+
+    let mut output = serde_json::to_string_pretty(&entries)?;
+    output.push('\n');
+    Ok(output)
+}
+
+/// Render a single entry as one newline-terminated JSON line.
+pub fn line(meta: &Meta, owner_cache: &OwnerCache) -> serde_json::Result<String> {
+    let mut output = serde_json::to_string(&JsonEntry::from_meta(meta, owner_cache))?;
+    output.push('\n');
+    Ok(output)
+}
+
+/// Render duplicate sets as one pretty-printed JSON array, or as one JSON line per set
+pub fn duplicate_sets(sets: &[JsonDuplicateSet], lines: bool) -> serde_json::Result<String> {
+    let mut output = String::new();
+    if lines {
+        for set in sets {
+            output.push_str(&serde_json::to_string(set)?);
+            output.push('\n');
+        }
+    } else {
+        output = serde_json::to_string_pretty(sets)?;
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+pub(crate) fn file_type_name(file_type: FileType) -> &'static str {
+    match file_type {
+        FileType::BlockDevice => "block-device",
+        FileType::CharDevice => "char-device",
+        FileType::Directory { .. } => "directory",
+        FileType::File { .. } => "file",
+        FileType::SymLink { .. } => "symlink",
+        FileType::Pipe => "pipe",
+        FileType::Socket => "socket",
+        FileType::Special => "special",
+    }
+}
@@ -1 +1,3 @@
-//! Presentation layer - currently empty, kept for future extensions
\ No newline at end of file
+//! Presentation layer for output formats other than the text grid and tree
+
+pub mod json;
@@ -17,14 +17,16 @@ pub fn assemble_sorters(flags: &Flags) -> Vec<(SortOrder, SortFn)> {
         DirGrouping::None => {}
     };
 
-    match flags.sorting.column {
-        SortColumn::Name => sorters.push((flags.sorting.order, by_name)),
-        SortColumn::Size => sorters.push((flags.sorting.order, by_size)),
-        SortColumn::Time => sorters.push((flags.sorting.order, by_date)),
-        SortColumn::Version => sorters.push((flags.sorting.order, by_version)),
-        SortColumn::Extension => sorters.push((flags.sorting.order, by_extension)),
-        SortColumn::GitStatus => sorters.push((flags.sorting.order, by_git_status)),
-        SortColumn::None => {}
+    for key in &flags.sorting.keys {
+        match key.column {
+            SortColumn::Name => sorters.push((key.order, by_name)),
+            SortColumn::Size => sorters.push((key.order, by_size)),
+            SortColumn::Time => sorters.push((key.order, by_date)),
+            SortColumn::Version => sorters.push((key.order, by_version)),
+            SortColumn::Extension => sorters.push((key.order, by_extension)),
+            SortColumn::GitStatus => sorters.push((key.order, by_git_status)),
+            SortColumn::None => {}
+        }
     }
     sorters
 }
@@ -0,0 +1,409 @@
+//! Members of tar and zip archives, listed like the entries of a directory
+
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+use chrono::TimeZone;
+
+use crate::flags::Flags;
+use crate::meta::{Date, FileType, Indicator, Meta, Name, Owner, Size, SymLink, Timestamps};
+#[cfg(unix)]
+use crate::meta::{Permissions, PermissionsOrAttributes};
+
+/// The file type bits of a unix mode, as zip archives record them
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+const S_IFDIR: u32 = 0o040000;
+
+/// The formats read, known by the end of the archive's file name
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    TarZst,
+    Zip,
+}
+
+impl ArchiveFormat {
+    pub fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        [
+            (".tar", Self::Tar),
+            (".tar.gz", Self::TarGz),
+            (".tgz", Self::TarGz),
+            (".tar.zst", Self::TarZst),
+            (".tzst", Self::TarZst),
+            (".zip", Self::Zip),
+        ]
+        .into_iter()
+        .find(|(extension, _)| name.ends_with(extension))
+        .map(|(_, format)| format)
+    }
+}
+
+/// An input naming an archive, or a directory inside one after `//` as in
+/// `release.zip//docs/api`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArchivePath {
+    pub archive: PathBuf,
+    /// The directory listed, relative to the root of the archive, empty for the root
+    pub inner: PathBuf,
+    pub format: ArchiveFormat,
+}
+
+impl ArchivePath {
+    /// The archive named by `input`, when it is an archive file or a path into one:
+    /// `archive//dir` names a directory inside it
+    pub fn parse(input: &Path) -> Option<Self> {
+        let text = input.to_str()?;
+        let splits = text.match_indices("//").map(|(at, _)| (&text[..at], &text[at + 2..]));
+        std::iter::once((text, "")).chain(splits).find_map(|(archive, inner)| {
+            let archive = Path::new(archive);
+            let format = ArchiveFormat::of(archive)?;
+            archive.is_file().then(|| Self {
+                archive: archive.to_path_buf(),
+                inner: normalize(Path::new(inner)),
+                format,
+            })
+        })
+    }
+
+    /// The path a member is shown with: the archive, `//` and its path inside it
+    pub fn member_path(&self, member: &Path) -> PathBuf {
+        if member.as_os_str().is_empty() {
+            self.archive.clone()
+        } else {
+            PathBuf::from(format!("{}//{}", self.archive.display(), member.display()))
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    File,
+    Directory,
+    Symlink,
+    /// A tar entry sharing the content of an earlier one
+    HardLink,
+    CharDevice,
+    BlockDevice,
+    Pipe,
+}
+
+/// An entry of an archive, as its header records it
+#[derive(Clone, Debug)]
+struct Member {
+    kind: Kind,
+    size: u64,
+    /// The permission bits, without the file type
+    mode: u32,
+    /// The modification time in seconds since the epoch
+    mtime: Option<i64>,
+    /// The ids and names of the owner, which zip archives do not record
+    owner: Option<(u32, u32, Option<String>, Option<String>)>,
+    /// The target of links, as recorded
+    target: Option<PathBuf>,
+}
+
+impl Member {
+    /// A directory only known from the paths of the members inside it
+    fn implied_directory() -> Self {
+        Self {
+            kind: Kind::Directory,
+            size: 0,
+            mode: 0o755,
+            mtime: None,
+            owner: None,
+            target: None,
+        }
+    }
+}
+
+/// The members of an archive by their path inside it, the directories their paths imply
+/// included.
+pub struct Archive {
+    path: ArchivePath,
+    members: BTreeMap<PathBuf, Member>,
+}
+
+impl Archive {
+    /// Read the headers of every member, decompressing tar streams as needed. Zip members
+    /// are not decompressed, save for the targets of symlinks.
+    pub fn open(path: &ArchivePath) -> std::io::Result<Self> {
+        let file = std::fs::File::open(&path.archive)?;
+        let read = match path.format {
+            ArchiveFormat::Tar => read_tar(BufReader::new(file)),
+            ArchiveFormat::TarGz => read_tar(flate2::read::GzDecoder::new(BufReader::new(file))),
+            ArchiveFormat::TarZst => read_tar(zstd::stream::read::Decoder::new(file)?),
+            ArchiveFormat::Zip => read_zip(file),
+        }?;
+
+        let mut members = BTreeMap::new();
+        for (member_path, member) in read {
+            // The `./` some tars start with is the root itself
+            if member_path.as_os_str().is_empty() {
+                continue;
+            }
+            for parent in member_path.ancestors().skip(1).filter(|parent| !parent.as_os_str().is_empty()) {
+                members.entry(parent.to_path_buf()).or_insert_with(Member::implied_directory);
+            }
+            // Like extracting, a later member replaces an earlier one of the same path
+            members.insert(member_path, member);
+        }
+        Ok(Self {
+            path: path.clone(),
+            members,
+        })
+    }
+
+    /// Where the archive was asked to be listed from
+    pub fn path(&self) -> &ArchivePath {
+        &self.path
+    }
+
+    /// The directory listed and its members down to `depth`, keyed by their path relative to
+    /// it, the directory itself being the empty path. A member that is not a directory comes
+    /// alone.
+    ///
+    /// Members with a component that `keep` rejects are left out, along with what is below
+    /// them. With `totals`, directories are sized by the members below them, like
+    /// `--total-size` does.
+    pub fn entries(
+        &self,
+        depth: usize,
+        totals: bool,
+        keep: impl Fn(&OsStr) -> bool,
+        flags: &Flags,
+    ) -> std::io::Result<Vec<(PathBuf, Meta)>> {
+        let inner = &self.path.inner;
+        let root = if inner.as_os_str().is_empty() {
+            Meta::from_path(&self.path.archive, flags.dereference.0, flags.permission, flags.time)?
+        } else {
+            match self.members.get(inner) {
+                Some(member) if member.kind == Kind::Directory => self.to_meta(inner, member),
+                // Like a file given as input, a member that is not a directory is listed alone
+                Some(member) => return Ok(vec![(PathBuf::new(), self.to_meta(inner, member))]),
+                None => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("no '{}' in the archive", inner.display()),
+                    ));
+                }
+            }
+        };
+
+        let below = self
+            .members
+            .iter()
+            .filter_map(|(path, member)| Some((path.strip_prefix(inner).ok()?, path, member)))
+            .filter(|(relative, _, _)| !relative.as_os_str().is_empty());
+
+        let mut sizes: HashMap<&Path, u64> = HashMap::new();
+        let mut entries = vec![(PathBuf::new(), root)];
+        for (relative, path, member) in below.filter(|(relative, _, _)| relative.iter().all(&keep)) {
+            if totals && member.kind != Kind::Directory {
+                for ancestor in relative.ancestors().skip(1) {
+                    *sizes.entry(ancestor).or_default() += self.size(member);
+                }
+            }
+            if relative.components().count() <= depth {
+                entries.push((relative.to_path_buf(), self.to_meta(path, member)));
+            }
+        }
+
+        if totals {
+            for (relative, meta) in &mut entries {
+                if matches!(meta.file_type, FileType::Directory { .. }) || relative.as_os_str().is_empty() {
+                    meta.size = Some(Size::new(sizes.get(relative.as_path()).copied().unwrap_or_default()));
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// The size of a member, which for hard links is the one of the member they share
+    fn size(&self, member: &Member) -> u64 {
+        match (member.kind, &member.target) {
+            (Kind::HardLink, Some(target)) => self.members.get(&normalize(target)).map_or(0, |linked| linked.size),
+            _ => member.size,
+        }
+    }
+
+    fn to_meta(&self, path: &Path, member: &Member) -> Meta {
+        // Relative targets are resolved from the directory of the link
+        let linked = member
+            .target
+            .as_ref()
+            .filter(|_| member.kind == Kind::Symlink)
+            .filter(|target| target.is_relative())
+            .and_then(|target| self.members.get(&normalize(&path.parent().unwrap_or(Path::new("")).join(target))));
+
+        let exec = member.mode & 0o111 != 0;
+        let uid = member.mode & 0o4000 != 0;
+        let file_type = match member.kind {
+            Kind::File | Kind::HardLink => FileType::File { uid, exec },
+            Kind::Directory => FileType::Directory { uid },
+            Kind::Symlink => FileType::SymLink {
+                is_dir: linked.is_some_and(|linked| linked.kind == Kind::Directory),
+            },
+            Kind::CharDevice => FileType::CharDevice,
+            Kind::BlockDevice => FileType::BlockDevice,
+            Kind::Pipe => FileType::Pipe,
+        };
+        let symlink = match (&member.target, member.kind) {
+            (Some(target), Kind::Symlink) => SymLink::new(target.clone(), linked.is_some()),
+            _ => SymLink::default(),
+        };
+
+        #[cfg(unix)]
+        let permissions_or_attributes = Some(PermissionsOrAttributes::Permissions(Permissions::from_mode(member.mode)));
+        #[cfg(not(unix))]
+        let permissions_or_attributes = None;
+
+        // Archives only keep the modification time, which stands in whatever `--time` asks for
+        let modified = member
+            .mtime
+            .and_then(|seconds| chrono::DateTime::from_timestamp(seconds, 0))
+            .map_or(Date::Invalid, |datetime| Date::Date(datetime.with_timezone(&chrono::Local)));
+        let timestamps = Timestamps {
+            modified: modified.clone(),
+            accessed: Date::Invalid,
+            changed: Date::Invalid,
+            born: Date::Invalid,
+        };
+
+        let shown = self.path.member_path(path);
+        Meta {
+            name: Name::new(&shown, file_type),
+            path: shown,
+            canonical_path: None,
+            permissions_or_attributes,
+            date: Some(modified),
+            timestamps: Some(timestamps),
+            owner: member
+                .owner
+                .clone()
+                .map(|(uid, gid, user, group)| Owner::recorded(uid, gid, user, group)),
+            file_type,
+            size: Some(Size::new(self.size(member))),
+            symlink,
+            indicator: Indicator::from(file_type),
+            inode: None,
+            links: None,
+            content: None,
+            access_control: None,
+            xattrs: None,
+            capabilities: None,
+            inode_flags: None,
+            filesystem: None,
+            mount_point: false,
+            magic: None,
+            digest: None,
+            diff_status: None,
+            git_status: None,
+        }
+    }
+}
+
+/// A path inside an archive without `.`, `..` or leading `/`, which archives are not meant to
+/// have but some do
+pub(super) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => normalized.push(name),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+    normalized
+}
+
+fn read_tar(reader: impl Read) -> std::io::Result<Vec<(PathBuf, Member)>> {
+    use tar::EntryType;
+
+    let mut archive = tar::Archive::new(reader);
+    let mut members = Vec::new();
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let header = entry.header();
+        let kind = match header.entry_type() {
+            EntryType::Directory => Kind::Directory,
+            EntryType::Symlink => Kind::Symlink,
+            EntryType::Link => Kind::HardLink,
+            EntryType::Char => Kind::CharDevice,
+            EntryType::Block => Kind::BlockDevice,
+            EntryType::Fifo => Kind::Pipe,
+            // Metadata for the following entries, not members of their own
+            EntryType::XGlobalHeader | EntryType::XHeader | EntryType::GNULongName | EntryType::GNULongLink => {
+                continue;
+            }
+            _ => Kind::File,
+        };
+        let name = |name: Option<&str>| name.filter(|name| !name.is_empty()).map(str::to_string);
+        let owner = match (header.uid(), header.gid()) {
+            (Ok(uid), Ok(gid)) => Some((
+                uid as u32,
+                gid as u32,
+                name(header.username().ok().flatten()),
+                name(header.groupname().ok().flatten()),
+            )),
+            _ => None,
+        };
+        let member = Member {
+            kind,
+            size: entry.size(),
+            mode: header.mode().map_or(0o644, |mode| mode & 0o7777),
+            mtime: header.mtime().ok().and_then(|mtime| i64::try_from(mtime).ok()),
+            owner,
+            target: entry.link_name()?.map(|target| target.into_owned()),
+        };
+        members.push((normalize(&entry.path()?), member));
+    }
+    Ok(members)
+}
+
+fn read_zip(file: std::fs::File) -> std::io::Result<Vec<(PathBuf, Member)>> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(file))?;
+    let mut members = Vec::new();
+    for index in 0..archive.len() {
+        let (path, mut member) = {
+            let file = archive.by_index_raw(index)?;
+            let mode = file.unix_mode();
+            let kind = match mode.map(|mode| mode & S_IFMT) {
+                Some(S_IFLNK) => Kind::Symlink,
+                Some(S_IFDIR) => Kind::Directory,
+                _ if file.is_dir() => Kind::Directory,
+                _ => Kind::File,
+            };
+            let default_mode = if kind == Kind::Directory { 0o755 } else { 0o644 };
+            // DOS times are in local time
+            let mtime = file.last_modified().and_then(|time| {
+                let date = chrono::NaiveDate::from_ymd_opt(time.year().into(), time.month().into(), time.day().into())?;
+                let datetime = date.and_hms_opt(time.hour().into(), time.minute().into(), time.second().into())?;
+                Some(chrono::Local.from_local_datetime(&datetime).earliest()?.timestamp())
+            });
+            let member = Member {
+                kind,
+                size: file.size(),
+                mode: mode.map_or(default_mode, |mode| mode & 0o7777),
+                mtime,
+                owner: None,
+                target: None,
+            };
+            (normalize(Path::new(file.name())), member)
+        };
+        // The target of a symlink is its content
+        if member.kind == Kind::Symlink {
+            let mut target = String::new();
+            archive.by_index(index)?.read_to_string(&mut target)?;
+            member.target = Some(PathBuf::from(target));
+        }
+        members.push((path, member));
+    }
+    Ok(members)
+}
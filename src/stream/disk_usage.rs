@@ -0,0 +1,240 @@
+//! Directory sizes for `--total-size`
+
+use std::collections::{HashMap, HashSet};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::flags::{Display, Flags, IgnoreGlobs};
+use crate::meta::{FileType, Size};
+use crate::stream::{device_of, stay_on_device, FileEntry, IgnoreRules, IgnoreSources, WalkState};
+
+/// Adds up the size of directory trees, counting each hard-linked file once.
+///
+/// Directories whose children are part of the stream are totalled bottom-up from the
+/// entries themselves. Directories at the depth limit have no children in the stream, so
+/// they are measured with a parallel walk of their own. Both use the same hidden-file,
+/// ignore-glob and ignore-file rules as the listing, so a total is the size of what `-R`
+/// would show. With `--disk-usage` the allocated bytes are added up instead of the lengths.
+pub struct DiskUsage {
+    display: Display,
+    on_disk: bool,
+    /// Whether `-x` keeps the walks on the device of the directory they measure
+    one_file_system: bool,
+    ignore_globs: IgnoreGlobs,
+    ignore_sources: IgnoreSources,
+    /// `(device, inode)` of the multiply-linked files already counted
+    seen: Arc<Mutex<HashSet<(u64, u64)>>>,
+}
+
+impl DiskUsage {
+    pub fn new(flags: &Flags) -> Self {
+        Self {
+            display: flags.display,
+            on_disk: flags.allocated_size.0,
+            one_file_system: flags.one_file_system.0,
+            ignore_globs: flags.ignore_globs.clone(),
+            ignore_sources: IgnoreSources::from_flags(flags),
+            seen: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Whether a file below a directory adds to its total
+    fn is_counted(display: Display, ignore_globs: &IgnoreGlobs, name: &std::ffi::OsStr) -> bool {
+        let hidden = name.to_str().is_some_and(|name| name.starts_with('.'))
+            && matches!(display, Display::VisibleOnly | Display::DirectoryOnly);
+        !hidden && !ignore_globs.is_match(name)
+    }
+
+    /// The bytes a file adds to the total, or 0 for a hard link that was already counted
+    pub fn count(&self, metadata: &Metadata) -> u64 {
+        count(&self.seen, metadata, self.on_disk)
+    }
+
+    /// The size of a directory holding `below` bytes
+    pub fn total(&self, metadata: &Metadata, below: u64) -> Size {
+        let size = Size::from(metadata);
+        let own = if self.on_disk { size.allocated().unwrap_or(metadata.len()) } else { metadata.len() };
+        size.with_bytes(own.saturating_add(below))
+    }
+
+    /// The size of everything below `path`, not including the directory itself
+    pub fn walk(&self, path: &Path) -> u64 {
+        let (display, on_disk) = (self.display, self.on_disk);
+        let ignore_globs = self.ignore_globs.clone();
+        let seen = Arc::clone(&self.seen);
+        let device = self.one_file_system.then(|| device_of(path)).flatten();
+
+        jwalk::WalkDirGeneric::<WalkState>::new(path)
+            .min_depth(1)
+            .skip_hidden(false)
+            .follow_links(false)
+            .parallelism(jwalk::Parallelism::RayonNewPool(0))
+            .root_read_dir_state(IgnoreRules::for_root(path, self.ignore_sources))
+            .process_read_dir(move |_depth, dir, rules, children| {
+                rules.apply(dir, children);
+                stay_on_device(device, children);
+                children.retain(|child| {
+                    child
+                        .as_ref()
+                        .map(|child| Self::is_counted(display, &ignore_globs, &child.file_name))
+                        .unwrap_or(true)
+                });
+            })
+            .into_iter()
+            .filter_map(|entry| match entry.and_then(|entry| entry.metadata()) {
+                Ok(metadata) => Some(count(&seen, &metadata, on_disk)),
+                Err(e) => {
+                    log::debug!("Skipping an entry of '{}' in its total: {}", path.display(), e);
+                    None
+                }
+            })
+            .fold(0, u64::saturating_add)
+    }
+
+    /// The size of the files directly inside `path`, leaving out its subdirectories
+    fn loose_files(&self, path: &Path) -> u64 {
+        let Ok(children) = path.read_dir() else {
+            return 0;
+        };
+        children
+            .flatten()
+            .filter(|child| Self::is_counted(self.display, &self.ignore_globs, &child.file_name()))
+            .filter_map(|child| child.metadata().ok())
+            .filter(|metadata| !metadata.is_dir())
+            .map(|metadata| self.count(&metadata))
+            .fold(0, u64::saturating_add)
+    }
+
+    /// Give every directory in a fully buffered stream its total size.
+    ///
+    /// `entries` must be ordered deepest first, so a directory's children are all counted
+    /// before the directory itself.
+    pub fn fill(&self, entries: &mut [FileEntry], max_depth: usize) {
+        let mut below: HashMap<PathBuf, u64> = HashMap::new();
+
+        for entry in entries.iter_mut() {
+            let mut subtree = below.remove(&entry.path).unwrap_or(0);
+            if matches!(entry.file_type, FileType::Directory { .. }) {
+                if entry.depth >= max_depth {
+                    subtree = self.walk(&entry.path);
+                } else if self.display == Display::DirectoryOnly {
+                    // Files are not part of the stream in this mode, but still take space
+                    subtree = subtree.saturating_add(self.loose_files(&entry.path));
+                }
+                entry.size = Some(self.total(&entry.metadata, subtree));
+            }
+
+            if entry.depth > 0
+                && let Some(parent) = entry.path.parent()
+            {
+                let total = below.entry(parent.to_path_buf()).or_default();
+                *total = total
+                    .saturating_add(self.count(&entry.metadata))
+                    .saturating_add(subtree);
+            }
+        }
+    }
+}
+
+/// Totals the directories of a stream as it goes, for output that is written entry by entry.
+///
+/// Entries must come in walk order, each directory before its content. A directory is held
+/// back until an entry outside of it shows its subtree is complete, and is then given back
+/// with its total, after its content. One [DiskUsage] serves the whole stream, so a hard link
+/// is counted once across all of it.
+pub struct RunningTotals {
+    usage: DiskUsage,
+    max_depth: usize,
+    /// The directories whose content is still coming, innermost last, with the bytes below
+    /// them so far
+    open: Vec<(FileEntry, u64)>,
+}
+
+impl RunningTotals {
+    pub fn new(usage: DiskUsage, max_depth: usize) -> Self {
+        Self {
+            usage,
+            max_depth,
+            open: Vec::new(),
+        }
+    }
+
+    /// Take the next entry of the stream, giving back the entries ready to be written
+    pub fn push(&mut self, mut entry: FileEntry) -> Vec<FileEntry> {
+        let mut ready = Vec::new();
+        while let Some((dir, _)) = self.open.last() {
+            if entry.depth > dir.depth && entry.path.starts_with(&dir.path) {
+                break;
+            }
+            ready.push(self.close());
+        }
+
+        if !matches!(entry.file_type, FileType::Directory { .. }) {
+            let bytes = self.usage.count(&entry.metadata);
+            self.add_to_parent(&entry, bytes);
+            ready.push(entry);
+        } else if entry.depth >= self.max_depth {
+            // Its content is not part of the stream
+            let below = self.usage.walk(&entry.path);
+            entry.size = Some(self.usage.total(&entry.metadata, below));
+            let bytes = self.usage.count(&entry.metadata).saturating_add(below);
+            self.add_to_parent(&entry, bytes);
+            ready.push(entry);
+        } else {
+            self.open.push((entry, 0));
+        }
+        ready
+    }
+
+    /// Give back the directories still open once the stream is over
+    pub fn finish(&mut self) -> Vec<FileEntry> {
+        std::iter::from_fn(|| (!self.open.is_empty()).then(|| self.close())).collect()
+    }
+
+    /// Total the innermost open directory, whose content is complete
+    fn close(&mut self) -> FileEntry {
+        let (mut dir, mut below) = self.open.pop().expect("an open directory");
+        if self.usage.display == Display::DirectoryOnly {
+            // Files are not part of the stream in this mode, but still take space
+            below = below.saturating_add(self.usage.loose_files(&dir.path));
+        }
+        dir.size = Some(self.usage.total(&dir.metadata, below));
+        let bytes = self.usage.count(&dir.metadata).saturating_add(below);
+        self.add_to_parent(&dir, bytes);
+        dir
+    }
+
+    /// Count `bytes` in the directory holding `entry`, when it is open. Entries whose parent
+    /// was left out of the stream, such as the content of hidden directories, count nowhere.
+    fn add_to_parent(&mut self, entry: &FileEntry, bytes: u64) {
+        if let Some((parent, below)) = self.open.last_mut()
+            && entry.path.parent() == Some(parent.path.as_path())
+        {
+            *below = below.saturating_add(bytes);
+        }
+    }
+}
+
+fn count(seen: &Mutex<HashSet<(u64, u64)>>, metadata: &Metadata, on_disk: bool) -> u64 {
+    if !metadata.is_file() && !metadata.is_dir() {
+        return 0;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        if metadata.is_file() && metadata.nlink() > 1 {
+            let mut seen = seen.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if !seen.insert((metadata.dev(), metadata.ino())) {
+                return 0;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = seen;
+
+    let size = Size::from(metadata);
+    if on_disk { size.on_disk().get_bytes() } else { size.get_bytes() }
+}
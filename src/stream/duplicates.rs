@@ -0,0 +1,154 @@
+//! Search for regular files with identical content, for `--duplicates`
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use rayon::prelude::*;
+
+use crate::flags::HashAlgorithm;
+use crate::meta::{Digest, FileType};
+use crate::stream::FileEntry;
+
+/// How many leading bytes are compared before whole files are hashed
+const PARTIAL_LEN: u64 = 4096;
+
+/// What a file is stored as: its device and inode, shared by every hard link to it
+type Identity = (u64, u64);
+
+/// Files found to have the same content.
+///
+/// Hard links to one inode are listed together but count as a single copy, as they already
+/// share their storage.
+#[derive(Debug, Clone)]
+pub struct DuplicateSet {
+    /// The size of each copy in bytes
+    pub size: u64,
+    pub digest: Digest,
+    /// Every path to the content, hard links included, with their digest filled in
+    pub entries: Vec<FileEntry>,
+    copies: usize,
+}
+
+impl DuplicateSet {
+    /// How many distinct inodes hold the content
+    pub fn copies(&self) -> usize {
+        self.copies
+    }
+
+    /// The bytes freed by keeping a single copy
+    pub fn wasted(&self) -> u64 {
+        self.size * (self.copies as u64 - 1)
+    }
+}
+
+/// Group the regular files among `entries` into sets of identical content, most wasteful first.
+///
+/// Files are narrowed down by size, then by a hash of their first bytes, and only the
+/// remaining candidates are hashed whole with `algorithm`. Sets made of hard links to one
+/// inode alone are not duplicates and are left out. Files that cannot be read are skipped.
+pub fn find_duplicates(entries: Vec<FileEntry>, algorithm: HashAlgorithm) -> Vec<DuplicateSet> {
+    let mut by_size: HashMap<u64, Vec<(Identity, FileEntry)>> = HashMap::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let size = entry.metadata.len();
+        if entry.is_symlink || !matches!(entry.file_type, FileType::File { .. }) || size == 0 {
+            continue;
+        }
+        by_size.entry(size).or_default().push((identity(&entry, index), entry));
+    }
+    let candidates: Vec<(Identity, FileEntry)> =
+        by_size.into_values().filter(|group| copies(group) > 1).flatten().collect();
+
+    // Each inode is read once, through whichever of its paths comes first
+    let partial = hash_inodes(&candidates, partial_hash);
+    let candidates = narrow(candidates, |identity, entry| Some((entry.metadata.len(), *partial.get(identity)?)));
+    let full = hash_inodes(&candidates, |path| Digest::of_file(path, algorithm));
+
+    let mut groups: HashMap<(u64, Digest), Vec<(Identity, FileEntry)>> = HashMap::new();
+    for (identity, entry) in candidates {
+        if let Some(digest) = full.get(&identity) {
+            groups.entry((entry.metadata.len(), digest.clone())).or_default().push((identity, entry));
+        }
+    }
+
+    let mut sets: Vec<DuplicateSet> = groups
+        .into_iter()
+        .filter(|(_, group)| copies(group) > 1)
+        .map(|((size, digest), group)| {
+            let copies = copies(&group);
+            let mut entries: Vec<FileEntry> = group
+                .into_iter()
+                .map(|(_, mut entry)| {
+                    entry.digest = Some(digest.clone());
+                    entry
+                })
+                .collect();
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
+            DuplicateSet { size, digest, entries, copies }
+        })
+        .collect();
+
+    sets.sort_by(|a, b| b.wasted().cmp(&a.wasted()).then_with(|| a.entries[0].path.cmp(&b.entries[0].path)));
+    sets
+}
+
+#[cfg(unix)]
+fn identity(entry: &FileEntry, _: usize) -> Identity {
+    use std::os::unix::fs::MetadataExt;
+
+    (entry.metadata.dev(), entry.metadata.ino())
+}
+
+/// Without inode numbers, every path is taken for a copy of its own
+#[cfg(not(unix))]
+fn identity(_: &FileEntry, index: usize) -> Identity {
+    (0, index as u64)
+}
+
+/// How many distinct inodes are in a group
+fn copies(group: &[(Identity, FileEntry)]) -> usize {
+    let mut identities: Vec<Identity> = group.iter().map(|(identity, _)| *identity).collect();
+    identities.sort_unstable();
+    identities.dedup();
+    identities.len()
+}
+
+/// Regroup the candidates by `key`, keeping the groups still holding several inodes
+fn narrow<K: std::hash::Hash + Eq>(
+    candidates: Vec<(Identity, FileEntry)>,
+    key: impl Fn(&Identity, &FileEntry) -> Option<K>,
+) -> Vec<(Identity, FileEntry)> {
+    let mut groups: HashMap<K, Vec<(Identity, FileEntry)>> = HashMap::new();
+    for (identity, entry) in candidates {
+        if let Some(key) = key(&identity, &entry) {
+            groups.entry(key).or_default().push((identity, entry));
+        }
+    }
+    groups.into_values().filter(|group| copies(group) > 1).flatten().collect()
+}
+
+/// Hash every inode among the candidates on rayon, leaving out the ones that cannot be read
+fn hash_inodes<T: Send>(
+    candidates: &[(Identity, FileEntry)],
+    hash: impl Fn(&Path) -> std::io::Result<T> + Sync,
+) -> HashMap<Identity, T> {
+    let mut inodes: HashMap<Identity, &Path> = HashMap::new();
+    for (identity, entry) in candidates {
+        inodes.entry(*identity).or_insert(&entry.path);
+    }
+    inodes
+        .into_par_iter()
+        .filter_map(|(identity, path)| match hash(path) {
+            Ok(hash) => Some((identity, hash)),
+            Err(e) => {
+                log::debug!("Cannot hash '{}': {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn partial_hash(path: &Path) -> std::io::Result<u64> {
+    let mut head = Vec::with_capacity(PARTIAL_LEN as usize);
+    std::fs::File::open(path)?.take(PARTIAL_LEN).read_to_end(&mut head)?;
+    Ok(xxhash_rust::xxh3::xxh3_64(&head))
+}
@@ -0,0 +1,70 @@
+//! Predicate filter stage for the streaming pipeline
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use futures::Stream;
+
+use crate::flags::Filters;
+use crate::meta::FileType;
+use crate::stream::{FileEntry, StreamResult};
+
+/// Stream stage that drops the entries failing the `--min-size`, `--newer`, `--type`, ...
+/// predicates.
+///
+/// A directory that does not match is held back rather than dropped, and released right
+/// before the first of its descendants that matches, so the hierarchy above every listed
+/// entry stays intact. The walk is depth first, so the held directories always form the
+/// chain of ancestors of the current entry. Inputs (depth 0) are always passed through.
+pub struct FilterStage {
+    source: Pin<Box<dyn Stream<Item = StreamResult<FileEntry>> + Send>>,
+    filters: Filters,
+    /// Non-matching ancestors of the current entry, outermost first
+    held: Vec<FileEntry>,
+    ready: VecDeque<FileEntry>,
+}
+
+impl FilterStage {
+    pub fn new(
+        source: impl Stream<Item = StreamResult<FileEntry>> + Send + 'static,
+        filters: Filters,
+    ) -> Self {
+        Self {
+            source: Box::pin(source),
+            filters,
+            held: Vec::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    fn process(&mut self, entry: FileEntry) {
+        // Directories whose subtree has been left without a match are dropped
+        while self.held.last().is_some_and(|dir| dir.depth >= entry.depth) {
+            self.held.pop();
+        }
+
+        if entry.depth == 0 || self.filters.matches(&entry.metadata, entry.file_type) {
+            self.ready.extend(self.held.drain(..));
+            self.ready.push_back(entry);
+        } else if matches!(entry.file_type, FileType::Directory { .. }) {
+            self.held.push(entry);
+        }
+    }
+}
+
+impl Stream for FilterStage {
+    type Item = StreamResult<FileEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(entry) = self.ready.pop_front() {
+                return Poll::Ready(Some(Ok(entry)));
+            }
+
+            match self.source.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(entry))) => self.process(entry),
+                other => return other,
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Git status stage for the streaming pipeline
+
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use futures::Stream;
+
+use crate::git::GitCache;
+use crate::stream::{FileEntry, StreamResult};
+
+/// Stream stage that fills `FileEntry::git_status` from the repository of each input root.
+///
+/// Repositories are discovered once per root when the stage is built, so the cost of
+/// `gix` status collection is paid up front rather than per entry.
+pub struct GitStage {
+    source: Pin<Box<dyn Stream<Item = StreamResult<FileEntry>> + Send>>,
+    caches: Vec<(PathBuf, GitCache)>,
+}
+
+impl GitStage {
+    pub fn new(
+        source: impl Stream<Item = StreamResult<FileEntry>> + Send + 'static,
+        roots: &[PathBuf],
+    ) -> Self {
+        let mut caches: Vec<(PathBuf, GitCache)> = Vec::with_capacity(roots.len());
+        for root in roots {
+            if caches.iter().any(|(known, _)| known == root) {
+                continue;
+            }
+            caches.push((root.clone(), GitCache::new(root)));
+        }
+
+        // Longest roots first, so nested inputs resolve to their own repository
+        caches.sort_by_key(|(root, _)| std::cmp::Reverse(root.components().count()));
+
+        Self {
+            source: Box::pin(source),
+            caches,
+        }
+    }
+
+    fn cache_for(&self, entry: &FileEntry) -> Option<&GitCache> {
+        self.caches
+            .iter()
+            .find(|(root, _)| entry.path.starts_with(root))
+            .map(|(_, cache)| cache)
+    }
+
+    fn annotate(&self, mut entry: FileEntry) -> FileEntry {
+        if let Some(cache) = self.cache_for(&entry) {
+            let is_directory = entry.file_type.is_dirlike() && !entry.is_symlink;
+            entry.git_status = cache.get(&entry.path, entry.canonical_path.as_ref(), is_directory);
+        }
+        entry
+    }
+}
+
+impl Stream for GitStage {
+    type Item = StreamResult<FileEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.source.as_mut().poll_next(cx) {
+            Poll::Ready(Some(Ok(entry))) => Poll::Ready(Some(Ok(self.annotate(entry)))),
+            other => other,
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Grid accumulator emitting one sorted batch of rows per directory
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use futures::Stream;
+
+use crate::display::RowRenderer;
+use crate::flags::{Display, SortOrder};
+use crate::meta::{FileType, Meta};
+use crate::sort;
+use crate::stream::{Accumulator, AccumulatorAction, DiskUsage, FileEntry, OutputEvent, StreamResult};
+
+/// The children of one directory seen so far
+struct Batch {
+    path: PathBuf,
+    depth: usize,
+    entries: Vec<FileEntry>,
+    /// Whether the last child has been seen, so the listing can be sorted and rendered
+    has_all_children: bool,
+    /// The subdirectories whose sections are still to print, in print order, once the
+    /// listing is rendered
+    order: Option<VecDeque<PathBuf>>,
+    /// Finished subdirectory sections waiting for the ones printing before them
+    sections: HashMap<PathBuf, Vec<OutputEvent>>,
+    /// Whether everything printing before this directory's section has been emitted, so
+    /// its own output can be emitted as soon as it is ready
+    live: bool,
+    /// Index of this directory's own row in its parent's `entries`
+    row_in_parent: Option<usize>,
+    /// Size of everything below this directory, with `--total-size`
+    total: u64,
+}
+
+impl Batch {
+    fn new(entry: &FileEntry, live: bool) -> Self {
+        Self {
+            path: entry.path.clone(),
+            depth: entry.depth,
+            entries: Vec::new(),
+            has_all_children: false,
+            order: None,
+            sections: HashMap::new(),
+            live,
+            row_in_parent: None,
+            total: 0,
+        }
+    }
+
+    /// Take the finished sections that print next, in order
+    fn take_ready_sections(&mut self) -> Vec<OutputEvent> {
+        let mut events = Vec::new();
+        if let Some(order) = &mut self.order {
+            while let Some(section) = order.front().and_then(|path| self.sections.remove(path)) {
+                order.pop_front();
+                events.extend(section);
+            }
+        }
+        events
+    }
+}
+
+/// Accumulator for the grid and oneline layouts.
+///
+/// The walk is depth first, so a directory's children are all known once the stream leaves
+/// its subtree. Each directory is then sorted on its own and emitted as a `DirectoryHeader`
+/// followed by its `FileRow`s.
+///
+/// Sections come out in `ls -R` order: a directory's listing, then the sections of its
+/// subdirectories in the order they were listed. The walk marks the last child of each
+/// directory, so a listing is rendered as soon as that child is seen, before the
+/// subdirectories are walked. A section is emitted once every section printing before it
+/// is, and only the finished sections still waiting on an earlier one are held.
+///
+/// With `--total-size`, a directory's row gets its total once its own batch is closed, so
+/// listings wait for their whole subtree.
+pub struct GridAccumulator {
+    source: Pin<Box<dyn Stream<Item = StreamResult<FileEntry>> + Send>>,
+    renderer: RowRenderer,
+    sorters: Vec<(SortOrder, sort::SortFn)>,
+    max_depth: usize,
+    disk_usage: Option<DiskUsage>,
+    /// Directories whose children are still arriving, innermost last
+    open: Vec<Batch>,
+    /// Non-directory inputs, listed together without a header before any directory
+    inputs: Vec<FileEntry>,
+    pending: VecDeque<OutputEvent>,
+    total_files: usize,
+    total_dirs: usize,
+    is_complete: bool,
+}
+
+impl GridAccumulator {
+    pub fn new(
+        source: impl Stream<Item = StreamResult<FileEntry>> + Send + 'static,
+        renderer: RowRenderer,
+        sorters: Vec<(SortOrder, sort::SortFn)>,
+        max_depth: usize,
+    ) -> Self {
+        let flags = renderer.flags();
+        let disk_usage = flags.total_size.0.then(|| DiskUsage::new(flags));
+
+        Self {
+            source: Box::pin(source),
+            renderer,
+            sorters,
+            max_depth,
+            disk_usage,
+            open: Vec::new(),
+            inputs: Vec::new(),
+            pending: VecDeque::new(),
+            total_files: 0,
+            total_dirs: 0,
+            is_complete: false,
+        }
+    }
+
+    /// Whether the children of this entry will follow it in the stream
+    fn opens_batch(&self, entry: &FileEntry) -> bool {
+        let is_directory = matches!(entry.file_type, FileType::Directory { .. });
+        let listed_as_row = entry.depth == 0 && self.renderer.flags().display == Display::DirectoryOnly;
+        is_directory && entry.depth < self.max_depth && !listed_as_row
+    }
+
+    /// Measure the directories of a batch that were not expanded, returning their combined size
+    fn measure(&self, entries: &mut [FileEntry]) -> u64 {
+        let Some(disk_usage) = &self.disk_usage else {
+            return 0;
+        };
+
+        let mut measured = 0u64;
+        for entry in entries
+            .iter_mut()
+            .filter(|entry| matches!(entry.file_type, FileType::Directory { .. }) && entry.size.is_none())
+        {
+            let below = disk_usage.walk(&entry.path);
+            entry.size = Some(disk_usage.total(&entry.metadata, below));
+            measured = measured.saturating_add(below);
+        }
+        measured
+    }
+
+    /// Sort a finished batch and turn it into output events
+    fn complete(&mut self, path: Option<PathBuf>, entries: Vec<FileEntry>) -> Vec<OutputEvent> {
+        let mut rows: Vec<(FileEntry, Meta)> = entries
+            .into_iter()
+            .map(|entry| {
+                let meta = entry.to_meta(self.renderer.flags());
+                (entry, meta)
+            })
+            .collect();
+        rows.sort_unstable_by(|(_, a), (_, b)| sort::by_meta(&self.sorters, a, b));
+
+        let (entries, metas): (Vec<FileEntry>, Vec<Meta>) = rows.into_iter().unzip();
+        let dir_count = metas.iter().filter(|meta| meta.file_type.is_dirlike()).count();
+        let file_count = metas.len() - dir_count;
+        self.total_dirs += dir_count;
+        self.total_files += file_count;
+
+        let mut events = Vec::with_capacity(entries.len() + 1);
+        if let Some(path) = path {
+            events.push(OutputEvent::DirectoryHeader {
+                path,
+                file_count,
+                dir_count,
+                total_size: metas
+                    .iter()
+                    .filter_map(|meta| meta.size.as_ref())
+                    .map(|size| size.get_bytes())
+                    .sum(),
+            });
+        }
+
+        let formatted = self.renderer.render(&metas);
+        events.extend(
+            entries
+                .into_iter()
+                .zip(formatted)
+                .zip(&metas)
+                .map(|((entry, formatted), meta)| OutputEvent::FileRow {
+                    entry,
+                    formatted,
+                    details: self.renderer.details(meta),
+                }),
+        );
+        events
+    }
+
+    /// Sort and render the listing of a batch whose children are all known, which fixes
+    /// the order its subdirectory sections print in
+    fn list(&mut self, batch: &mut Batch) -> Vec<OutputEvent> {
+        let entries = std::mem::take(&mut batch.entries);
+        let events = self.complete(Some(batch.path.clone()), entries);
+        batch.order = Some(
+            events
+                .iter()
+                .filter_map(|event| match event {
+                    OutputEvent::FileRow { entry, .. } if self.opens_batch(entry) => Some(entry.path.clone()),
+                    _ => None,
+                })
+                .collect(),
+        );
+        events
+    }
+
+    /// Finish a directory, returning the events that can be emitted right away
+    fn close(&mut self, mut batch: Batch) -> Vec<OutputEvent> {
+        let mut events = Vec::new();
+        if batch.order.is_none() {
+            batch.total = batch.total.saturating_add(self.measure(&mut batch.entries));
+            if let (Some(parent), Some(row), Some(disk_usage)) =
+                (self.open.last_mut(), batch.row_in_parent, &self.disk_usage)
+            {
+                let directory = &mut parent.entries[row];
+                directory.size = Some(disk_usage.total(&directory.metadata, batch.total));
+                parent.total = parent.total.saturating_add(batch.total);
+            }
+            events = self.list(&mut batch);
+        }
+        // Every subdirectory is finished, so the sections left print one after the other
+        events.extend(batch.take_ready_sections());
+
+        match self.open.last_mut() {
+            Some(parent) if batch.live => {
+                if let Some(order) = &mut parent.order {
+                    order.pop_front();
+                }
+                events
+            }
+            Some(parent) => {
+                parent.sections.insert(batch.path, events);
+                Vec::new()
+            }
+            None => events,
+        }
+    }
+
+    /// Emit what the open directories can print now: the listings that are complete and
+    /// the finished sections next in line, handing the turn down to the subdirectory being
+    /// walked when its section is the next one
+    fn advance(&mut self) -> Vec<OutputEvent> {
+        let mut events = Vec::new();
+        let mut open = std::mem::take(&mut self.open);
+        for level in 0..open.len() {
+            let (batch, inner) = open[level..].split_first_mut().expect("a level of the stack");
+            if !batch.live {
+                break;
+            }
+            if batch.order.is_none() && batch.has_all_children {
+                events.extend(self.list(batch));
+            }
+            events.extend(batch.take_ready_sections());
+            match (batch.order.as_ref().and_then(|order| order.front()), inner.first_mut()) {
+                (Some(next), Some(child)) if *next == child.path => child.live = true,
+                _ => break,
+            }
+        }
+        self.open = open;
+        events
+    }
+
+    /// List the non-directory inputs collected so far
+    fn flush_inputs(&mut self) -> Vec<OutputEvent> {
+        if self.inputs.is_empty() {
+            return Vec::new();
+        }
+        let mut inputs = std::mem::take(&mut self.inputs);
+        self.measure(&mut inputs);
+        self.complete(None, inputs)
+    }
+
+    fn action(events: Vec<OutputEvent>) -> AccumulatorAction {
+        if events.is_empty() {
+            AccumulatorAction::Buffer
+        } else {
+            AccumulatorAction::Emit(events)
+        }
+    }
+
+    /// Flush everything still buffered once the source is exhausted
+    fn finish(&mut self) {
+        let mut events = self.flush_inputs();
+        while let Some(batch) = self.open.pop() {
+            events.extend(self.close(batch));
+            events.extend(self.advance());
+        }
+
+        self.pending.extend(events);
+        self.pending.push_back(OutputEvent::StreamComplete {
+            total_files: self.total_files,
+            total_dirs: self.total_dirs,
+        });
+    }
+}
+
+impl Accumulator for GridAccumulator {
+    fn process_entry(&mut self, entry: FileEntry) -> AccumulatorAction {
+        // Leaving the subtree of a directory means all of its children have been seen
+        let mut events = Vec::new();
+        while self.open.last().is_some_and(|batch| batch.depth >= entry.depth) {
+            if let Some(batch) = self.open.pop() {
+                events.extend(self.close(batch));
+                events.extend(self.advance());
+            }
+        }
+
+        // Inputs are walked one after the other, so an input's turn has come when it opens
+        let opens_batch = self.opens_batch(&entry);
+        let mut batch = opens_batch.then(|| Batch::new(&entry, entry.depth == 0));
+
+        if entry.depth == 0 {
+            if opens_batch {
+                events.extend(self.flush_inputs());
+            } else {
+                self.inputs.push(entry);
+            }
+        } else {
+            match self.open.last_mut() {
+                Some(parent)
+                    if entry.path.parent() == Some(parent.path.as_path()) && parent.order.is_none() =>
+                {
+                    if let Some(disk_usage) = &self.disk_usage {
+                        parent.total = parent.total.saturating_add(disk_usage.count(&entry.metadata));
+                    }
+                    if let Some(batch) = &mut batch {
+                        batch.row_in_parent = Some(parent.entries.len());
+                    }
+                    // Totals need the whole subtree, so the listing waits for the batch to close
+                    parent.has_all_children = entry.is_last_sibling && self.disk_usage.is_none();
+                    parent.entries.push(entry)
+                }
+                // The parent directory was filtered out, so is its content
+                _ => {
+                    log::debug!("Skipping '{}', its parent is not listed", entry.path.display());
+                    return Self::action(events);
+                }
+            }
+        }
+
+        if let Some(batch) = batch {
+            self.open.push(batch);
+        }
+        events.extend(self.advance());
+
+        Self::action(events)
+    }
+}
+
+impl Stream for GridAccumulator {
+    type Item = StreamResult<OutputEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+            if self.is_complete {
+                return Poll::Ready(None);
+            }
+
+            match self.source.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(entry))) => match self.process_entry(entry) {
+                    AccumulatorAction::Emit(events) => self.pending.extend(events),
+                    AccumulatorAction::Buffer | AccumulatorAction::Transform => {}
+                },
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => {
+                    self.is_complete = true;
+                    self.finish();
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
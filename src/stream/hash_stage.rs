@@ -0,0 +1,251 @@
+//! Content hashing stage for the streaming pipeline
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::Metadata;
+use std::io::Write;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use futures::Stream;
+use rayon::prelude::*;
+
+use crate::flags::HashAlgorithm;
+use crate::meta::{Digest, FileType};
+use crate::stream::{FileEntry, StreamResult};
+
+/// How many entries are hashed together
+const BATCH: usize = 64;
+
+/// `(device, inode, modification time in nanoseconds, size)`, which change along with the
+/// content
+type CacheKey = (u64, u64, u128, u64);
+
+/// Entries left unused for this many days are dropped when the cache is saved
+const MAX_AGE_DAYS: u64 = 30;
+
+/// The most entries kept, the least recently used ones going first
+const MAX_ENTRIES: usize = 200_000;
+
+/// A digest, with the day it was last looked up or computed
+#[derive(Debug)]
+struct CacheEntry {
+    digest: Digest,
+    used: u64,
+}
+
+/// Digests kept between runs by `--hash-cache`, one file per algorithm in the cache directory.
+///
+/// Each line holds the device, the inode, the modification time in nanoseconds, the size, the
+/// day the digest was last used and the digest of a file, separated by spaces. Saving drops
+/// the entries unused for [MAX_AGE_DAYS] days and keeps at most [MAX_ENTRIES].
+#[derive(Debug, Default)]
+pub struct DigestCache {
+    path: Option<PathBuf>,
+    digests: HashMap<CacheKey, CacheEntry>,
+    today: u64,
+    dirty: bool,
+}
+
+impl DigestCache {
+    /// Load the cache of `algorithm`, which starts empty when it does not exist yet
+    pub fn load(algorithm: HashAlgorithm) -> Self {
+        let path = dirs::cache_dir().map(|dir| dir.join("kgls").join(format!("hashes-{}", algorithm.name())));
+        let digests = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|text| text.lines().filter_map(parse_line).collect())
+            .unwrap_or_default();
+        let today = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs() / 86_400);
+        Self {
+            path,
+            digests,
+            today,
+            dirty: false,
+        }
+    }
+
+    pub fn get(&self, metadata: &Metadata) -> Option<&Digest> {
+        self.digests.get(&key(metadata)?).map(|entry| &entry.digest)
+    }
+
+    /// Record that the digest of `metadata` was used today, so it is kept longer
+    pub fn touch(&mut self, metadata: &Metadata) {
+        if let Some(entry) = key(metadata).and_then(|key| self.digests.get_mut(&key))
+            && entry.used != self.today
+        {
+            entry.used = self.today;
+            self.dirty = true;
+        }
+    }
+
+    pub fn insert(&mut self, metadata: &Metadata, digest: Digest) {
+        if let Some(key) = key(metadata) {
+            self.digests.insert(key, CacheEntry { digest, used: self.today });
+            self.dirty = true;
+        }
+    }
+
+    /// Write the cache back when it changed, replacing the file in one rename
+    pub fn save(&mut self) -> std::io::Result<()> {
+        let Some(path) = self.path.as_ref().filter(|_| self.dirty) else {
+            return Ok(());
+        };
+        let oldest = self.today.saturating_sub(MAX_AGE_DAYS);
+        self.digests.retain(|_, entry| entry.used >= oldest);
+        if self.digests.len() > MAX_ENTRIES {
+            let mut by_use: Vec<(u64, CacheKey)> = self.digests.iter().map(|(key, entry)| (entry.used, *key)).collect();
+            by_use.select_nth_unstable_by(MAX_ENTRIES, |a, b| b.0.cmp(&a.0));
+            for (_, key) in &by_use[MAX_ENTRIES..] {
+                self.digests.remove(key);
+            }
+        }
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(&partial)?);
+        for ((device, inode, modified, size), entry) in &self.digests {
+            writeln!(writer, "{device} {inode} {modified} {size} {} {}", entry.used, entry.digest.hex())?;
+        }
+        writer.flush()?;
+        std::fs::rename(&partial, path)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn key(metadata: &Metadata) -> Option<CacheKey> {
+    use std::os::unix::fs::MetadataExt;
+
+    let modified = metadata.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?;
+    Some((metadata.dev(), metadata.ino(), modified.as_nanos(), metadata.len()))
+}
+
+#[cfg(not(unix))]
+fn key(_: &Metadata) -> Option<CacheKey> {
+    None
+}
+
+fn parse_line(line: &str) -> Option<(CacheKey, CacheEntry)> {
+    let mut fields = line.split(' ');
+    let key = (
+        fields.next()?.parse().ok()?,
+        fields.next()?.parse().ok()?,
+        fields.next()?.parse().ok()?,
+        fields.next()?.parse().ok()?,
+    );
+    let used = fields.next()?.parse().ok()?;
+    let digest = Digest::from_hex(fields.next()?)?;
+    Some((key, CacheEntry { digest, used }))
+}
+
+/// Stream stage that fills `FileEntry::digest` for regular files.
+///
+/// Entries are gathered while the source has them ready and hashed as a batch on rayon.
+/// With a [DigestCache], unchanged files reuse their digest, and the new ones are saved once
+/// the source is exhausted.
+pub struct HashStage {
+    source: Pin<Box<dyn Stream<Item = StreamResult<FileEntry>> + Send>>,
+    algorithm: HashAlgorithm,
+    cache: Option<DigestCache>,
+    ready: VecDeque<StreamResult<FileEntry>>,
+    done: bool,
+}
+
+impl HashStage {
+    pub fn new(
+        source: impl Stream<Item = StreamResult<FileEntry>> + Send + 'static,
+        algorithm: HashAlgorithm,
+        cache: Option<DigestCache>,
+    ) -> Self {
+        Self {
+            source: Box::pin(source),
+            algorithm,
+            cache,
+            ready: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    fn wants(entry: &FileEntry) -> bool {
+        entry.digest.is_none() && matches!(entry.file_type, FileType::File { .. })
+    }
+
+    fn hash(&mut self) {
+        let (algorithm, cache) = (self.algorithm, &self.cache);
+        // The digests computed, and with [None] the ones the cache already had
+        let used: Vec<(Metadata, Option<Digest>)> = self
+            .ready
+            .par_iter_mut()
+            .filter_map(|result| {
+                let entry = result.as_mut().ok().filter(|entry| Self::wants(entry))?;
+                if let Some(digest) = cache.as_ref().and_then(|cache| cache.get(&entry.metadata)) {
+                    entry.digest = Some(digest.clone());
+                    return Some((entry.metadata.clone(), None));
+                }
+                match Digest::of_file(&entry.path, algorithm) {
+                    Ok(digest) => {
+                        entry.digest = Some(digest.clone());
+                        Some((entry.metadata.clone(), Some(digest)))
+                    }
+                    Err(e) => {
+                        log::debug!("Cannot hash '{}': {}", entry.path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        if let Some(cache) = self.cache.as_mut() {
+            for (metadata, digest) in used {
+                match digest {
+                    Some(digest) => cache.insert(&metadata, digest),
+                    None => cache.touch(&metadata),
+                }
+            }
+        }
+    }
+}
+
+impl Stream for HashStage {
+    type Item = StreamResult<FileEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(result) = self.ready.pop_front() {
+            return Poll::Ready(Some(result));
+        }
+
+        let mut pending = false;
+        while !self.done && self.ready.len() < BATCH {
+            match self.source.as_mut().poll_next(cx) {
+                Poll::Ready(Some(result)) => self.ready.push_back(result),
+                Poll::Ready(None) => {
+                    self.done = true;
+                }
+                Poll::Pending => {
+                    pending = true;
+                    break;
+                }
+            }
+        }
+
+        if self.ready.iter().flatten().any(Self::wants) {
+            self.hash();
+        }
+        if self.done
+            && let Some(cache) = self.cache.as_mut()
+            && let Err(e) = cache.save()
+        {
+            log::warn!("Cannot save the hash cache: {}", e);
+        }
+        match self.ready.pop_front() {
+            Some(result) => Poll::Ready(Some(result)),
+            None if pending => Poll::Pending,
+            None => Poll::Ready(None),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Ignore files honored during traversal, see `--git-ignore` and `--ignore-files`
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+
+use crate::flags::Flags;
+
+/// Ignore files read by `--ignore-files`, highest precedence first
+const IGNORE_FILES: [&str; 2] = [".kglsignore", ".ignore"];
+
+/// Which kinds of ignore files to honor
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IgnoreSources {
+    pub git: bool,
+    pub custom: bool,
+}
+
+impl IgnoreSources {
+    pub fn from_flags(flags: &Flags) -> Self {
+        Self {
+            git: flags.git_ignore.0,
+            custom: flags.ignore_files.0,
+        }
+    }
+
+    pub fn is_enabled(self) -> bool {
+        self.git || self.custom
+    }
+}
+
+/// The ignore files of one directory, highest precedence first
+#[derive(Debug)]
+struct Level {
+    matchers: Vec<Gitignore>,
+    parent: Option<Arc<Level>>,
+}
+
+/// The walk root, to give the matchers absolute paths wherever the walk started
+#[derive(Debug)]
+struct Root {
+    path: PathBuf,
+    absolute: PathBuf,
+}
+
+/// The ignore rules in effect for one directory of a walk.
+///
+/// This is the jwalk read-dir state: each directory extends its parent's rules with its own
+/// ignore files before its children are filtered, and its subdirectories inherit the result.
+/// Rules from deeper directories win, so a nested `!pattern` re-includes what an outer file
+/// ignored, while anything inside an ignored directory stays pruned since it is never read.
+#[derive(Clone, Debug, Default)]
+pub struct IgnoreRules {
+    sources: IgnoreSources,
+    root: Option<Arc<Root>>,
+    levels: Option<Arc<Level>>,
+}
+
+impl IgnoreRules {
+    /// The rules for a walk of `root`, including the ignore files of the directories above it
+    /// up to the enclosing git repository.
+    pub fn for_root(root: &Path, sources: IgnoreSources) -> Self {
+        if !sources.is_enabled() {
+            return Self::default();
+        }
+        let absolute = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+
+        // Outside of a repository, only the walked directories' own files apply
+        let ancestors: Vec<&Path> = absolute.ancestors().skip(1).collect();
+        let repository = if absolute.join(".git").exists() {
+            None
+        } else {
+            ancestors.iter().position(|dir| dir.join(".git").exists())
+        };
+
+        let mut rules = Self {
+            sources,
+            root: Some(Arc::new(Root {
+                path: root.to_path_buf(),
+                absolute: absolute.clone(),
+            })),
+            levels: None,
+        };
+        if let Some(repository) = repository {
+            for dir in ancestors[..=repository].iter().rev() {
+                rules = rules.with_files_of(dir, |name| dir.join(name).exists());
+            }
+        }
+        rules
+    }
+
+    fn absolute(&self, path: &Path) -> Option<PathBuf> {
+        let root = self.root.as_ref()?;
+        let relative = path.strip_prefix(&root.path).ok()?;
+        Some(if relative.as_os_str().is_empty() {
+            root.absolute.clone()
+        } else {
+            root.absolute.join(relative)
+        })
+    }
+
+    /// The rules for the children of `dir`, which contains the entries for which `has` is true
+    fn descend(&self, dir: &Path, has: impl Fn(&str) -> bool) -> Self {
+        match self.absolute(dir) {
+            Some(dir) => self.with_files_of(&dir, has),
+            None => self.clone(),
+        }
+    }
+
+    fn with_files_of(&self, dir: &Path, has: impl Fn(&str) -> bool) -> Self {
+        let mut candidates: Vec<PathBuf> = Vec::new();
+        if self.sources.custom {
+            candidates.extend(IGNORE_FILES.iter().filter(|name| has(name)).map(|name| dir.join(name)));
+        }
+        if self.sources.git {
+            if has(".gitignore") {
+                candidates.push(dir.join(".gitignore"));
+            }
+            if has(".git") {
+                let exclude = dir.join(".git/info/exclude");
+                if exclude.is_file() {
+                    candidates.push(exclude);
+                }
+            }
+        }
+
+        let matchers: Vec<Gitignore> = candidates
+            .into_iter()
+            .filter_map(|file| {
+                let mut builder = GitignoreBuilder::new(dir);
+                if let Some(e) = builder.add(&file) {
+                    log::warn!("Cannot fully read '{}': {}", file.display(), e);
+                }
+                builder
+                    .build()
+                    .inspect_err(|e| log::warn!("Cannot use '{}': {}", file.display(), e))
+                    .ok()
+            })
+            .filter(|matcher| !matcher.is_empty())
+            .collect();
+
+        if matchers.is_empty() {
+            return self.clone();
+        }
+        Self {
+            sources: self.sources,
+            root: self.root.clone(),
+            levels: Some(Arc::new(Level {
+                matchers,
+                parent: self.levels.clone(),
+            })),
+        }
+    }
+
+    /// Extend the rules with the ignore files of `dir`, then drop its ignored children
+    pub fn apply(&mut self, dir: &Path, children: &mut Vec<jwalk::Result<jwalk::DirEntry<super::WalkState>>>) {
+        if !self.sources.is_enabled() {
+            return;
+        }
+
+        let names: HashSet<&OsStr> = children
+            .iter()
+            .flatten()
+            .map(|child| child.file_name.as_os_str())
+            .collect();
+        let rules = self.descend(dir, |name| names.contains(OsStr::new(name)));
+
+        children.retain(|child| {
+            child
+                .as_ref()
+                .map(|child| !rules.is_ignored(&child.path(), child.file_type.is_dir()))
+                .unwrap_or(true)
+        });
+        *self = rules;
+    }
+
+    /// Whether an entry of the directory these rules belong to is ignored
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.levels.is_none() {
+            return false;
+        }
+        let Some(path) = self.absolute(path) else {
+            return false;
+        };
+
+        let mut level = self.levels.as_deref();
+        while let Some(current) = level {
+            for matcher in &current.matchers {
+                match matcher.matched(&path, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+            level = current.parent.as_deref();
+        }
+        false
+    }
+}
@@ -0,0 +1,83 @@
+//! Content sniffing stage for the streaming pipeline
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use futures::Stream;
+use rayon::prelude::*;
+
+use crate::meta::{FileType, Magic};
+use crate::stream::{FileEntry, StreamResult};
+
+/// How many entries are sniffed together
+const BATCH: usize = 256;
+
+/// Stream stage that fills `FileEntry::magic` from the leading bytes of each file.
+///
+/// Entries are gathered while the source has them ready and sniffed as a batch on rayon,
+/// so the opens and reads of a directory overlap. Only extensionless files are read unless
+/// `all` is set, as files with an extension already get their icon and color from it.
+pub struct MagicStage {
+    source: Pin<Box<dyn Stream<Item = StreamResult<FileEntry>> + Send>>,
+    all: bool,
+    ready: VecDeque<StreamResult<FileEntry>>,
+    done: bool,
+}
+
+impl MagicStage {
+    pub fn new(source: impl Stream<Item = StreamResult<FileEntry>> + Send + 'static, all: bool) -> Self {
+        Self {
+            source: Box::pin(source),
+            all,
+            ready: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    fn wants(all: bool, entry: &FileEntry) -> bool {
+        entry.magic.is_none()
+            && (all || (matches!(entry.file_type, FileType::File { .. }) && entry.path.extension().is_none()))
+    }
+
+    fn sniff(&mut self) {
+        let all = self.all;
+        self.ready.par_iter_mut().for_each(|result| {
+            if let Ok(entry) = result
+                && Self::wants(all, entry)
+            {
+                entry.magic = Magic::detect(&entry.path, entry.file_type);
+            }
+        });
+    }
+}
+
+impl Stream for MagicStage {
+    type Item = StreamResult<FileEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(result) = self.ready.pop_front() {
+            return Poll::Ready(Some(result));
+        }
+
+        let mut pending = false;
+        while !self.done && self.ready.len() < BATCH {
+            match self.source.as_mut().poll_next(cx) {
+                Poll::Ready(Some(result)) => self.ready.push_back(result),
+                Poll::Ready(None) => self.done = true,
+                Poll::Pending => {
+                    pending = true;
+                    break;
+                }
+            }
+        }
+
+        if self.ready.iter().flatten().any(|entry| Self::wants(self.all, entry)) {
+            self.sniff();
+        }
+        match self.ready.pop_front() {
+            Some(result) => Poll::Ready(Some(result)),
+            None if pending => Poll::Pending,
+            None => Poll::Ready(None),
+        }
+    }
+}
@@ -4,10 +4,38 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+mod archive;
+mod disk_usage;
+mod duplicates;
+mod filter_stage;
+mod git_stage;
+mod grid_accumulator;
+mod hash_stage;
+mod ignore_rules;
+mod magic_stage;
+mod revision;
+mod snapshot;
 mod tree_accumulator;
+mod tree_diff;
 
-use crate::git::GitStatusInfo;
-use crate::meta::FileType;
+pub use archive::{Archive, ArchiveFormat, ArchivePath};
+pub use disk_usage::{DiskUsage, RunningTotals};
+pub use duplicates::{find_duplicates, DuplicateSet};
+pub use filter_stage::FilterStage;
+pub use git_stage::GitStage;
+pub use grid_accumulator::GridAccumulator;
+pub use hash_stage::{DigestCache, HashStage};
+pub use ignore_rules::{IgnoreRules, IgnoreSources};
+pub use magic_stage::MagicStage;
+pub use revision::Revision;
+pub use snapshot::{Record, Snapshot};
+pub use tree_diff::{compare_trees, DiffEntry};
+
+use crate::meta::{Digest, FileType, GitFileStatus, Magic};
+
+/// jwalk client state: the ignore rules of the directory being read, and for each entry
+/// whether it is the last one kept from its directory
+pub(crate) type WalkState = (IgnoreRules, bool);
 
 /// A file system entry discovered during traversal
 #[derive(Debug, Clone)]
@@ -18,15 +46,22 @@ pub struct FileEntry {
     pub metadata: std::fs::Metadata,
     pub depth: usize,
     pub is_symlink: bool,
+    /// Whether the walk has no more entries of the same directory after this one, which
+    /// completes the listing of the directory before its subdirectories are walked
+    pub is_last_sibling: bool,
 
     // Cached canonical path captured at discovery time to avoid TOCTOU races
     pub canonical_path: Option<PathBuf>,
 
-    // Lazy-loaded fields populated by transformers (planned for future optimization)
-    pub git_status: Option<GitStatusInfo>,
+    // Lazy-loaded fields populated by transformers (see `GitStage`)
+    pub git_status: Option<GitFileStatus>,
+    /// The type sniffed from the content (see `MagicStage`)
+    pub magic: Option<Magic>,
+    /// The digest of the content (see `HashStage`)
+    pub digest: Option<Digest>,
     #[allow(dead_code)]
     pub permissions: Option<crate::meta::Permissions>,
-    #[allow(dead_code)]
+    /// Set for directories when `--total-size` replaces their own size with their content's
     pub size: Option<crate::meta::Size>,
     #[allow(dead_code)]
     pub modified: Option<crate::meta::Date>,
@@ -34,7 +69,7 @@ pub struct FileEntry {
 
 impl FileEntry {
     pub fn from_jwalk(
-        entry: DirEntry<((), ())>,
+        entry: DirEntry<WalkState>,
         base_depth: usize,
     ) -> Result<Self, std::io::Error> {
         let path = entry.path();
@@ -43,6 +78,7 @@ impl FileEntry {
         let file_type = FileType::new(&metadata, None, &path);
         let depth = entry.depth() - base_depth;
         let is_symlink = metadata.file_type().is_symlink();
+        let is_last_sibling = entry.client_state;
 
         // Capture canonical path early to avoid TOCTOU races
         // Silently handle failures (broken symlinks, permission denied, etc.)
@@ -55,20 +91,32 @@ impl FileEntry {
             metadata,
             depth,
             is_symlink,
+            is_last_sibling,
             canonical_path,
             git_status: None,
+            magic: None,
+            digest: None,
             permissions: None,
             size: None,
             modified: None,
         })
     }
 
+    /// Whether the entry is a directory something is mounted on
+    pub fn is_mount_point(&self) -> bool {
+        matches!(self.file_type, FileType::Directory { .. })
+            && self
+                .canonical_path
+                .as_deref()
+                .is_some_and(|canonical| crate::meta::MountTable::current().is_mount_point(canonical))
+    }
+
     /// Convert FileEntry to Meta using already-loaded metadata
-    pub fn to_meta(&self, permission_flag: crate::flags::PermissionFlag) -> crate::meta::Meta {
+    pub fn to_meta(&self, flags: &crate::flags::Flags) -> crate::meta::Meta {
         use crate::meta::*;
 
         #[cfg(unix)]
-        let (owner, permissions) = match permission_flag {
+        let (owner, permissions) = match flags.permission {
             crate::flags::PermissionFlag::Disable => (None, None),
             _ => (
                 Some(Owner::from(&self.metadata)),
@@ -79,7 +127,7 @@ impl FileEntry {
         let permissions_or_attributes = permissions.map(crate::meta::permissions_or_attributes::PermissionsOrAttributes::Permissions);
 
         #[cfg(windows)]
-        let (owner, permissions_or_attributes) = match permission_flag {
+        let (owner, permissions_or_attributes) = match flags.permission {
             crate::flags::PermissionFlag::Disable => (None, None),
             crate::flags::PermissionFlag::Attributes => {
                 use crate::meta::windows_attributes::get_attributes;
@@ -110,24 +158,52 @@ impl FileEntry {
             Name::new(&std::path::PathBuf::from(&self.name), self.file_type)
         } else {
             Name::new(&self.path, self.file_type)
-        };
+        }
+        .with_magic(self.magic);
 
+        let timestamps = Timestamps::from(&self.metadata);
+        let mount_point = self.is_mount_point();
         Meta {
             inode: Some(INode::from(&self.metadata)),
             links: Some(Links::from(&self.metadata)),
             path: self.path.clone(),
             canonical_path: self.canonical_path.clone(),
             symlink: SymLink::from(self.path.as_path()),
-            size: Some(Size::from(&self.metadata)),
-            date: Some(Date::from(&self.metadata)),
-            indicator: Indicator::from(self.file_type),
+            size: Some(self.size.clone().unwrap_or_else(|| {
+                let size = Size::from(&self.metadata);
+                if flags.allocated_size.0 { size.on_disk() } else { size }
+            })),
+            date: Some(timestamps.get(flags.time).clone()),
+            timestamps: Some(timestamps),
+            indicator: if mount_point { Indicator::mount_point() } else { Indicator::from(self.file_type) },
             owner,
             permissions_or_attributes,
             name,
             file_type: self.file_type,
             content: None,
             access_control: Some(AccessControl::for_path(&self.path)),
-            git_status: self.git_status.as_ref().map(GitFileStatus::from_gix_status),
+            xattrs: (flags.list_xattrs.0 || flags.blocks.0.contains(&crate::flags::blocks::Block::Xattrs))
+                .then(|| Xattrs::for_path(&self.path, flags.list_xattrs.0)),
+            capabilities: flags
+                .blocks
+                .0
+                .contains(&crate::flags::blocks::Block::Caps)
+                .then(|| Capabilities::for_path(&self.path)),
+            inode_flags: flags
+                .blocks
+                .0
+                .contains(&crate::flags::blocks::Block::Flags)
+                .then(|| InodeFlags::for_path(&self.path, &self.metadata)),
+            filesystem: flags
+                .blocks
+                .0
+                .contains(&crate::flags::blocks::Block::FileSystem)
+                .then(|| FileSystem::for_entry(&self.metadata, self.canonical_path.as_deref())),
+            mount_point,
+            magic: self.magic,
+            digest: self.digest.clone(),
+            diff_status: None,
+            git_status: self.git_status,
         }
     }
 }
@@ -149,6 +225,37 @@ pub enum StreamError {
     Traversal(String),
 }
 
+/// The device holding `path`, which `-x` keeps a walk on
+#[cfg(unix)]
+pub(crate) fn device_of(path: &std::path::Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn device_of(_: &std::path::Path) -> Option<u64> {
+    None
+}
+
+/// Keep a walk from descending into the directories on another device than `device`.
+///
+/// The directories themselves are still listed, like mount points are by `find -xdev`.
+pub(crate) fn stay_on_device(device: Option<u64>, children: &mut [jwalk::Result<DirEntry<WalkState>>]) {
+    #[cfg(unix)]
+    if let Some(device) = device {
+        use std::os::unix::fs::MetadataExt;
+
+        for child in children.iter_mut().flatten() {
+            if child.read_children_path.is_some() && child.metadata().is_ok_and(|metadata| metadata.dev() != device) {
+                child.read_children_path = None;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (device, children);
+}
+
 /// Core file stream that produces entries from directory traversal
 pub struct FileStream {
     inner: Pin<Box<dyn Stream<Item = StreamResult<FileEntry>> + Send>>,
@@ -160,7 +267,9 @@ impl FileStream {
         paths: Vec<PathBuf>,
         max_depth: usize,
         ignore_globs: &crate::flags::IgnoreGlobs,
+        ignore_sources: IgnoreSources,
         display: crate::flags::Display,
+        one_file_system: bool,
     ) -> Self {
         let ignore_globs = ignore_globs.clone();
         
@@ -172,13 +281,19 @@ impl FileStream {
                 
                 // Create jwalk walker for this path
                 let ignore_globs_for_callback = ignore_globs.clone();
-                let walker = jwalk::WalkDir::new(&path)
+                let device = one_file_system.then(|| device_of(&path)).flatten();
+                let walker = jwalk::WalkDirGeneric::<WalkState>::new(&path)
                     .max_depth(max_depth)
                     .sort(true)
                     .skip_hidden(false)
                     .follow_links(false)
                     .parallelism(jwalk::Parallelism::RayonNewPool(0))
-                    .process_read_dir(move |_depth, _path, _state, children| {
+                    .root_read_dir_state(IgnoreRules::for_root(&path, ignore_sources))
+                    .process_read_dir(move |_depth, dir, rules, children| {
+                        // Ignored subtrees are dropped here, so they are never read
+                        rules.apply(dir, children);
+                        stay_on_device(device, children);
+
                         // Filter out ignored entries during traversal (prevents descending)
                         children.retain(|dir_entry_result| {
                             dir_entry_result.as_ref().map(|dir_entry| {
@@ -187,6 +302,9 @@ impl FileStream {
                                     .unwrap_or(true)
                             }).unwrap_or(true)
                         });
+                        if let Some(last) = children.iter_mut().rev().flatten().next() {
+                            last.client_state = true;
+                        }
                     });
                 
                 // jwalk always starts at depth 0 for the root path
@@ -205,7 +323,9 @@ impl FileStream {
                                 match entry_result {
                                     Ok(entry) => {
                                         // Apply display mode filter (ignore_globs now in process_read_dir)
-                                        if let Some(name) = entry.file_name().to_str() {
+                                        // Inputs given by the user (depth 0) are always listed, even hidden ones
+                                        if entry.depth() > 0
+                                            && let Some(name) = entry.file_name().to_str() {
                                             // Apply display mode filter
                                             use crate::flags::Display;
                                             match display_mode {
@@ -253,6 +373,34 @@ impl FileStream {
             inner: Box::pin(stream),
         }
     }
+
+    /// Drop the entries failing the predicate filters, keeping the ancestors of those that pass
+    pub fn with_filters(self, filters: crate::flags::Filters) -> Self {
+        FileStream {
+            inner: Box::pin(FilterStage::new(self.inner, filters)),
+        }
+    }
+
+    /// Fill in the content type of every entry, or only of the extensionless files
+    pub fn with_magic(self, all: bool) -> Self {
+        FileStream {
+            inner: Box::pin(MagicStage::new(self.inner, all)),
+        }
+    }
+
+    /// Fill in the digest of every regular file, reusing the ones in `cache`
+    pub fn with_hashes(self, algorithm: crate::flags::HashAlgorithm, cache: Option<DigestCache>) -> Self {
+        FileStream {
+            inner: Box::pin(HashStage::new(self.inner, algorithm, cache)),
+        }
+    }
+
+    /// Fill in git status for every entry, discovering one repository per input root
+    pub fn with_git_status(self, roots: &[PathBuf]) -> Self {
+        FileStream {
+            inner: Box::pin(GitStage::new(self.inner, roots)),
+        }
+    }
 }
 
 impl Stream for FileStream {
@@ -263,8 +411,7 @@ impl Stream for FileStream {
     }
 }
 
-/// Output events emitted by accumulators (planned for future streaming optimization)
-#[allow(dead_code)]
+/// Output events emitted by accumulators
 #[derive(Debug)]
 pub enum OutputEvent {
     /// Header for a directory in human-readable output
@@ -279,6 +426,8 @@ pub enum OutputEvent {
     FileRow {
         entry: FileEntry,
         formatted: Vec<String>, // Pre-formatted columns
+        /// Lines printed under the row, such as the extended attributes of `-@`
+        details: Vec<String>,
     },
 
     /// Tree node with hierarchy information
@@ -295,15 +444,13 @@ pub enum OutputEvent {
     },
 }
 
-/// Trait for accumulator implementations (planned for future streaming optimization)
-#[allow(dead_code)]
+/// Trait for accumulator implementations, see `GridAccumulator`
 pub trait Accumulator: Stream<Item = StreamResult<OutputEvent>> + Unpin {
     /// Process a file entry
     fn process_entry(&mut self, entry: FileEntry) -> AccumulatorAction;
 }
 
-/// Actions an accumulator can take when processing an entry (planned for future streaming optimization)
-#[allow(dead_code)]
+/// Actions an accumulator can take when processing an entry
 #[derive(Debug)]
 pub enum AccumulatorAction {
     /// Buffer the entry for later emission
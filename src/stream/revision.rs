@@ -0,0 +1,463 @@
+//! The tree of a git revision, listed like a directory of the working tree
+
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use gix::bstr::ByteSlice;
+use gix::object::tree::EntryKind;
+use gix::ObjectId;
+
+use super::archive::normalize;
+use crate::flags::Flags;
+use crate::meta::{Date, DiffStatus, FileType, Indicator, Meta, Name, Size, SymLink, Timestamps};
+#[cfg(unix)]
+use crate::meta::{Permissions, PermissionsOrAttributes};
+
+/// An entry of a tree object
+#[derive(Clone, Debug)]
+struct Object {
+    kind: EntryKind,
+    id: ObjectId,
+    /// The size of blobs, zero for trees and submodules
+    size: u64,
+    /// The target of symlinks, which is the content of their blob
+    target: Option<PathBuf>,
+}
+
+/// The part of a revision's tree below one path of the working tree
+pub struct Revision {
+    /// The revision as given, which prefixes the paths shown
+    spec: String,
+    workdir: PathBuf,
+    /// The path listed, relative to the root of the repository
+    prefix: PathBuf,
+    /// The commit time, which stands in for the times git does not record
+    time: Option<i64>,
+    root: Option<Object>,
+    /// The objects below the path listed, by their path relative to it
+    objects: BTreeMap<PathBuf, Object>,
+    /// When compared with the working tree, the files that differ from the revision, by their
+    /// path relative to the path listed
+    changes: Option<BTreeMap<PathBuf, DiffStatus>>,
+}
+
+impl Revision {
+    /// Resolve `spec` in the repository of `path` and read its tree below `path`, down to
+    /// `depth`. The path need not exist in the working tree anymore. With `compare`, the
+    /// files of the working tree that differ from the revision are found as well.
+    pub fn open(spec: &str, path: &Path, depth: usize, compare: bool) -> std::io::Result<Self> {
+        // The closest ancestor still there finds the repository and the prefix of the rest
+        let existing = path
+            .ancestors()
+            .find(|ancestor| ancestor.as_os_str().is_empty() || ancestor.exists())
+            .unwrap_or(Path::new(""));
+        let missing = path.strip_prefix(existing).unwrap_or(Path::new(""));
+        let existing = std::fs::canonicalize(if existing.as_os_str().is_empty() { Path::new(".") } else { existing })?;
+
+        let directory = if existing.is_dir() { existing.as_path() } else { existing.parent().unwrap_or(&existing) };
+        let repo = gix::discover(directory).map_err(std::io::Error::other)?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| std::io::Error::other("the repository has no working tree"))
+            .and_then(std::fs::canonicalize)?;
+        let prefix = existing
+            .strip_prefix(&workdir)
+            .map_err(|_| std::io::Error::other("the path is outside of the working tree"))?
+            .join(missing);
+
+        let id = repo.rev_parse_single(spec).map_err(std::io::Error::other)?;
+        let object = id.object().map_err(std::io::Error::other)?;
+        let time = object
+            .clone()
+            .peel_to_commit()
+            .ok()
+            .and_then(|commit| commit.time().ok())
+            .map(|time| time.seconds);
+        let tree = object.peel_to_tree().map_err(std::io::Error::other)?;
+
+        let mut revision = Self {
+            spec: spec.to_string(),
+            workdir,
+            prefix,
+            time,
+            root: None,
+            objects: BTreeMap::new(),
+            changes: None,
+        };
+
+        revision.root = if revision.prefix.as_os_str().is_empty() {
+            Some(Object {
+                kind: EntryKind::Tree,
+                id: tree.id,
+                size: 0,
+                target: None,
+            })
+        } else {
+            tree.lookup_entry_by_path(&revision.prefix)
+                .map_err(std::io::Error::other)?
+                .map(|entry| revision.object(&repo, entry.mode().kind(), entry.object_id()))
+                .transpose()?
+        };
+        let Some(root) = revision.root.clone() else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no '{}' in {}", revision.prefix.display(), spec),
+            ));
+        };
+        if root.kind == EntryKind::Tree {
+            revision.read_tree(&repo, root.id, Path::new(""), depth)?;
+        }
+
+        if compare {
+            revision.changes = Some(Self::changes(&repo, tree.id, &revision.prefix)?);
+        }
+        Ok(revision)
+    }
+
+    /// The files below `prefix` that differ between the tree `id` and the working tree, the
+    /// way git status sees them: through the index, whose stat data spares reading the files
+    /// that did not change, and after the filters of .gitattributes
+    fn changes(repo: &gix::Repository, id: ObjectId, prefix: &Path) -> std::io::Result<BTreeMap<PathBuf, DiffStatus>> {
+        use gix::diff::index::Change as Staged;
+        use gix::index::entry::Mode;
+        use gix::status::plumbing::index_as_worktree::{Change, EntryStatus};
+
+        // Without a trailing separator, which would only match directories
+        let components: Vec<_> = prefix.iter().map(|component| component.to_string_lossy()).collect();
+        let patterns: Vec<gix::bstr::BString> = if components.is_empty() {
+            Vec::new()
+        } else {
+            vec![components.join("/").into()]
+        };
+        let items = repo
+            .status(gix::progress::Discard)
+            .map_err(std::io::Error::other)?
+            .head_tree(id)
+            .untracked_files(gix::status::UntrackedFiles::None)
+            .tree_index_track_renames(gix::status::tree_index::TrackRenames::Disabled)
+            .index_worktree_rewrites(None)
+            .index_worktree_submodules(None)
+            .into_iter(patterns)
+            .map_err(std::io::Error::other)?;
+
+        let mut changes = BTreeMap::new();
+        for item in items {
+            let item = item.map_err(std::io::Error::other)?;
+            let status = match &item {
+                // The revision against the index
+                gix::status::Item::TreeIndex(change) => match change {
+                    Staged::Addition { .. } => DiffStatus::Added,
+                    Staged::Deletion { .. } => DiffStatus::Removed,
+                    // Files and executables are the same type, as on disk
+                    Staged::Modification { previous_entry_mode, entry_mode, .. }
+                        if previous_entry_mode.intersection(Mode::SYMLINK | Mode::COMMIT)
+                            != entry_mode.intersection(Mode::SYMLINK | Mode::COMMIT) =>
+                    {
+                        DiffStatus::TypeChanged
+                    }
+                    Staged::Modification { .. } | Staged::Rewrite { .. } => DiffStatus::Modified,
+                },
+                // The index against the working tree
+                gix::status::Item::IndexWorktree(gix::status::index_worktree::Item::Modification { status, .. }) => {
+                    match status {
+                        EntryStatus::Change(Change::Removed) => DiffStatus::Removed,
+                        EntryStatus::Change(Change::Type { .. }) => DiffStatus::TypeChanged,
+                        EntryStatus::NeedsUpdate(_) => continue,
+                        _ => DiffStatus::Modified,
+                    }
+                }
+                gix::status::Item::IndexWorktree(_) => continue,
+            };
+            let location = PathBuf::from(item.location().to_str_lossy().as_ref());
+            let Ok(relative) = location.strip_prefix(prefix) else {
+                continue;
+            };
+            // A file gone from the working tree is gone whatever the index says, and one the
+            // index added stays added however it changed since
+            changes
+                .entry(relative.to_path_buf())
+                .and_modify(|current| {
+                    *current = match (*current, status) {
+                        (_, DiffStatus::Removed) | (DiffStatus::Removed, _) => DiffStatus::Removed,
+                        (DiffStatus::Added, _) | (_, DiffStatus::Added) => DiffStatus::Added,
+                        (DiffStatus::TypeChanged, _) | (_, DiffStatus::TypeChanged) => DiffStatus::TypeChanged,
+                        _ => DiffStatus::Modified,
+                    }
+                })
+                .or_insert(status);
+        }
+        Ok(changes)
+    }
+
+    fn object(&self, repo: &gix::Repository, kind: EntryKind, id: ObjectId) -> std::io::Result<Object> {
+        let (size, target) = match kind {
+            EntryKind::Link => {
+                let blob = repo.find_blob(id).map_err(std::io::Error::other)?;
+                (blob.data.len() as u64, Some(PathBuf::from(blob.data.to_str_lossy().as_ref())))
+            }
+            EntryKind::Blob | EntryKind::BlobExecutable => {
+                (repo.find_header(id).map_err(std::io::Error::other)?.size(), None)
+            }
+            EntryKind::Tree | EntryKind::Commit => (0, None),
+        };
+        Ok(Object { kind, id, size, target })
+    }
+
+    fn read_tree(&mut self, repo: &gix::Repository, id: ObjectId, relative: &Path, depth: usize) -> std::io::Result<()> {
+        if depth == 0 {
+            return Ok(());
+        }
+        let tree = repo.find_tree(id).map_err(std::io::Error::other)?;
+        let entries: Vec<(PathBuf, EntryKind, ObjectId)> = tree
+            .decode()
+            .map_err(std::io::Error::other)?
+            .entries
+            .iter()
+            .map(|entry| {
+                let name = entry.filename.to_str_lossy();
+                (relative.join(name.as_ref()), entry.mode.kind(), entry.oid.to_owned())
+            })
+            .collect();
+        for (path, kind, id) in entries {
+            let object = self.object(repo, kind, id)?;
+            if kind == EntryKind::Tree {
+                self.read_tree(repo, id, &path, depth - 1)?;
+            }
+            self.objects.insert(path, object);
+        }
+        Ok(())
+    }
+
+    /// The path listed and its entries down to `depth`, keyed by their path relative to it,
+    /// the path itself being the empty path.
+    ///
+    /// Entries with a component that `keep` rejects are left out, along with what is below
+    /// them. With `totals`, trees are sized by the blobs below them. When opened to compare,
+    /// each entry is marked with how the working tree differs from it, and the files the
+    /// index has since added are listed along. Both need the tree read below `depth`.
+    pub fn entries(
+        &self,
+        depth: usize,
+        totals: bool,
+        keep: impl Fn(&OsStr) -> bool,
+        flags: &Flags,
+    ) -> Vec<(PathBuf, Meta)> {
+        let root = self.root.as_ref().expect("an opened revision has a root");
+        let mut entries = vec![(PathBuf::new(), self.to_meta(Path::new(""), root))];
+        entries.extend(
+            self.objects
+                .iter()
+                .filter(|(relative, _)| relative.iter().all(&keep))
+                .map(|(relative, object)| (relative.clone(), self.to_meta(relative, object))),
+        );
+
+        if totals {
+            let mut sizes: HashMap<PathBuf, u64> = HashMap::new();
+            for (relative, meta) in &entries {
+                if !matches!(meta.file_type, FileType::Directory { .. }) {
+                    for ancestor in relative.ancestors().skip(1) {
+                        *sizes.entry(ancestor.to_path_buf()).or_default() += meta.size.as_ref().map_or(0, Size::get_bytes);
+                    }
+                }
+            }
+            for (relative, meta) in &mut entries {
+                if matches!(meta.file_type, FileType::Directory { .. }) {
+                    meta.size = Some(Size::new(sizes.get(relative).copied().unwrap_or_default()));
+                }
+            }
+        }
+
+        if let Some(changes) = &self.changes {
+            self.compare(changes, &mut entries, &keep, flags);
+        }
+        entries.retain(|(relative, _)| relative.components().count() <= depth);
+        entries
+    }
+
+    /// Mark the entries with how the working tree differs from them, adding the files of the
+    /// index the revision does not have
+    fn compare(
+        &self,
+        changes: &BTreeMap<PathBuf, DiffStatus>,
+        entries: &mut Vec<(PathBuf, Meta)>,
+        keep: &impl Fn(&OsStr) -> bool,
+        flags: &Flags,
+    ) {
+        let root = self.root.as_ref().expect("an opened revision has a root");
+        let mut statuses: HashMap<PathBuf, DiffStatus> = std::iter::once((PathBuf::new(), root))
+            .chain(self.objects.iter().map(|(relative, object)| (relative.clone(), object)))
+            .map(|(relative, object)| {
+                let status = self.status(&relative, object, changes);
+                (relative, status)
+            })
+            .collect();
+
+        // The index knows the files added since, untracked ones are left out
+        let root_is_tree = root.kind == EntryKind::Tree;
+        let added = changes.iter().filter(|(_, status)| **status == DiffStatus::Added);
+        for (added, _) in added.filter(|_| root_is_tree) {
+            if self.objects.contains_key(added) {
+                continue;
+            }
+            for path in added.ancestors().filter(|path| !path.as_os_str().is_empty()) {
+                if self.objects.contains_key(path) || statuses.contains_key(path) {
+                    continue;
+                }
+                statuses.insert(path.to_path_buf(), DiffStatus::Added);
+                if path.iter().all(keep)
+                    && let Ok(mut meta) = Meta::from_path(
+                        &self.worktree(path),
+                        false,
+                        flags.permission,
+                        flags.time,
+                    )
+                {
+                    meta.name = self.name(path, meta.file_type);
+                    meta.path = self.shown(path);
+                    meta.content = None;
+                    entries.push((path.to_path_buf(), meta));
+                }
+            }
+        }
+
+        // Trees are modified by any change below them
+        let changed: Vec<PathBuf> = statuses
+            .iter()
+            .filter(|(_, status)| status.is_changed())
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in changed {
+            for ancestor in path.ancestors().skip(1) {
+                if let Some(status @ DiffStatus::Unchanged) = statuses.get_mut(ancestor) {
+                    *status = DiffStatus::Modified;
+                }
+            }
+        }
+
+        for (relative, meta) in entries {
+            let status = statuses.get(relative.as_path()).copied().unwrap_or(DiffStatus::Unchanged);
+            meta.diff_status = Some(status);
+            meta.git_status = Some(status.git_status());
+        }
+    }
+
+    /// How the working tree differs from an object of the revision, leaving the changes
+    /// below trees to their entries
+    fn status(&self, relative: &Path, object: &Object, changes: &BTreeMap<PathBuf, DiffStatus>) -> DiffStatus {
+        match object.kind {
+            // Directories are not in the index, only whether one is still there tells
+            EntryKind::Tree | EntryKind::Commit if !changes.contains_key(relative) => {
+                match self.worktree(relative).symlink_metadata() {
+                    Ok(metadata) if metadata.is_dir() => DiffStatus::Unchanged,
+                    Ok(_) => DiffStatus::TypeChanged,
+                    Err(_) => DiffStatus::Removed,
+                }
+            }
+            _ => match changes.get(relative) {
+                None => DiffStatus::Unchanged,
+                // Left out of the index, the file may still be in the working tree
+                Some(DiffStatus::Removed) if self.worktree(relative).symlink_metadata().is_ok() => DiffStatus::Modified,
+                Some(status) => *status,
+            },
+        }
+    }
+
+    /// The path of an entry in the working tree
+    fn worktree(&self, relative: &Path) -> PathBuf {
+        // Joining an empty path would add a trailing separator, which files do not take
+        self.workdir.join(&self.prefix).components().chain(relative.components()).collect()
+    }
+
+    /// The name of an entry, which is the one of its path in the repository save for the
+    /// root of the repository, named after the revision
+    fn name(&self, relative: &Path, file_type: FileType) -> Name {
+        let path = self.prefix.join(relative);
+        if path.as_os_str().is_empty() {
+            Name::new(&self.shown(relative), file_type)
+        } else {
+            Name::new(&path, file_type)
+        }
+    }
+
+    /// The path an entry is shown with: the revision, `:` and its path in the repository,
+    /// the way git names objects
+    fn shown(&self, relative: &Path) -> PathBuf {
+        let path = self.prefix.join(relative);
+        let path: Vec<_> = path.iter().map(|component| component.to_string_lossy()).collect();
+        PathBuf::from(format!("{}:{}", self.spec, path.join("/")))
+    }
+
+    fn to_meta(&self, relative: &Path, object: &Object) -> Meta {
+        // Relative targets are resolved from the directory of the link
+        let linked = object
+            .target
+            .as_ref()
+            .filter(|target| target.is_relative())
+            .map(|target| normalize(&relative.parent().unwrap_or(Path::new("")).join(target)))
+            .and_then(|target| self.objects.get(&target));
+
+        let (file_type, mode) = match object.kind {
+            EntryKind::Tree | EntryKind::Commit => (FileType::Directory { uid: false }, 0o755),
+            EntryKind::Blob => (FileType::File { uid: false, exec: false }, 0o644),
+            EntryKind::BlobExecutable => (FileType::File { uid: false, exec: true }, 0o755),
+            EntryKind::Link => (
+                FileType::SymLink {
+                    is_dir: linked.is_some_and(|linked| linked.kind == EntryKind::Tree),
+                },
+                0o777,
+            ),
+        };
+        let symlink = match &object.target {
+            Some(target) => SymLink::new(target.clone(), linked.is_some()),
+            None => SymLink::default(),
+        };
+
+        #[cfg(unix)]
+        let permissions_or_attributes = Some(PermissionsOrAttributes::Permissions(Permissions::from_mode(mode)));
+        #[cfg(not(unix))]
+        let permissions_or_attributes = {
+            let _ = mode;
+            None
+        };
+
+        // Git keeps no times of its own, the commit's is the one a checkout would get
+        let modified = self
+            .time
+            .and_then(|seconds| chrono::DateTime::from_timestamp(seconds, 0))
+            .map_or(Date::Invalid, |datetime| Date::Date(datetime.with_timezone(&chrono::Local)));
+        let timestamps = Timestamps {
+            modified: modified.clone(),
+            accessed: Date::Invalid,
+            changed: Date::Invalid,
+            born: Date::Invalid,
+        };
+
+        Meta {
+            name: self.name(relative, file_type),
+            path: self.shown(relative),
+            canonical_path: None,
+            permissions_or_attributes,
+            date: Some(modified),
+            timestamps: Some(timestamps),
+            owner: None,
+            file_type,
+            size: Some(Size::new(object.size)),
+            symlink,
+            indicator: Indicator::from(file_type),
+            inode: None,
+            links: None,
+            content: None,
+            access_control: None,
+            xattrs: None,
+            capabilities: None,
+            inode_flags: None,
+            filesystem: None,
+            mount_point: false,
+            magic: None,
+            digest: None,
+            diff_status: None,
+            git_status: None,
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Indexes of a walk saved by `--snapshot`, and compared with by `--since-snapshot`
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::flags::HashAlgorithm;
+use crate::meta::{
+    DiffStatus, Date, Digest, FileType, Indicator, Meta, Name, Permissions, PermissionsOrAttributes, Size, SymLink,
+};
+use crate::presentation::json::file_type_name;
+use crate::stream::tree_diff::changed_parents;
+use crate::stream::FileEntry;
+
+/// Bumped when the records change in a way older versions cannot read
+const VERSION: u32 = 2;
+
+/// The first line of a snapshot file
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    version: u32,
+    /// The digest of the files, for snapshots saved with `--checksum`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hash: Option<HashAlgorithm>,
+}
+
+/// What a snapshot keeps of an entry, one JSON line each
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    /// The path below the input root, see [Snapshot::of]
+    pub path: PathBuf,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub size: u64,
+    /// The modification time in nanoseconds since the epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<i64>,
+    /// The permission bits, without the file type
+    pub mode: u32,
+    pub inode: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// The target of symlinks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<PathBuf>,
+}
+
+impl Record {
+    fn of(path: PathBuf, entry: &FileEntry, algorithm: Option<HashAlgorithm>, hashed: Option<HashAlgorithm>) -> Self {
+        let mtime = entry
+            .metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .and_then(|since| i64::try_from(since.as_nanos()).ok());
+        let hash = algorithm
+            .filter(|_| matches!(entry.file_type, FileType::File { .. }))
+            .and_then(|algorithm| match &entry.digest {
+                // The walk hashed the file already, maybe through the digest cache
+                Some(digest) if hashed == Some(algorithm) => Some(digest.hex().to_string()),
+                _ => match Digest::of_file(&entry.path, algorithm) {
+                    Ok(digest) => Some(digest.hex().to_string()),
+                    Err(e) => {
+                        log::debug!("Cannot hash '{}': {}", entry.path.display(), e);
+                        None
+                    }
+                },
+            });
+        let target = matches!(entry.file_type, FileType::SymLink { .. })
+            .then(|| std::fs::read_link(&entry.path).ok())
+            .flatten();
+        let (mode, inode) = mode_and_inode(&entry.metadata);
+        Self {
+            path,
+            kind: file_type_name(entry.file_type).to_string(),
+            size: entry.metadata.len(),
+            mtime,
+            mode,
+            inode,
+            hash,
+            target,
+        }
+    }
+
+    /// How `self` changed into `new`.
+    ///
+    /// Files are compared by digest when both records have one, and by modification time and
+    /// inode otherwise, which tells apart a file replaced by a copy.
+    fn compare(&self, new: &Record) -> DiffStatus {
+        if self.kind != new.kind {
+            return DiffStatus::TypeChanged;
+        }
+        let content_changed = match (&self.hash, &new.hash) {
+            (Some(old), Some(new)) => old != new,
+            _ => self.kind == "file" && (self.mtime != new.mtime || self.inode != new.inode),
+        };
+        if self.size != new.size || self.mode != new.mode || self.target != new.target || content_changed {
+            DiffStatus::Modified
+        } else {
+            DiffStatus::Unchanged
+        }
+    }
+
+    /// The entry as it was, shown at `path`, for listing what was removed since
+    pub fn to_meta(&self, path: &Path) -> Meta {
+        let exec = self.mode & 0o111 != 0;
+        let uid = self.mode & 0o4000 != 0;
+        let file_type = match self.kind.as_str() {
+            "file" => FileType::File { uid, exec },
+            "directory" => FileType::Directory { uid },
+            "symlink" => FileType::SymLink { is_dir: false },
+            "pipe" => FileType::Pipe,
+            "socket" => FileType::Socket,
+            "char-device" => FileType::CharDevice,
+            "block-device" => FileType::BlockDevice,
+            _ => FileType::Special,
+        };
+        #[cfg(unix)]
+        let permissions_or_attributes = Some(PermissionsOrAttributes::Permissions(Permissions::from_mode(self.mode)));
+        #[cfg(not(unix))]
+        let permissions_or_attributes = None;
+        let modified = self
+            .mtime
+            .and_then(|nanos| u64::try_from(nanos).ok())
+            .map(|nanos| Date::from(std::time::UNIX_EPOCH + std::time::Duration::from_nanos(nanos)));
+
+        Meta {
+            name: Name::new(path, file_type),
+            path: path.to_path_buf(),
+            canonical_path: None,
+            permissions_or_attributes,
+            date: modified,
+            timestamps: None,
+            owner: None,
+            file_type,
+            size: Some(Size::new(self.size)),
+            symlink: SymLink::from(path),
+            indicator: Indicator::from(file_type),
+            inode: None,
+            links: None,
+            content: None,
+            access_control: None,
+            xattrs: None,
+            capabilities: None,
+            inode_flags: None,
+            filesystem: None,
+            mount_point: false,
+            magic: None,
+            digest: self.hash.as_deref().and_then(Digest::from_hex),
+            diff_status: None,
+            git_status: None,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn mode_and_inode(metadata: &std::fs::Metadata) -> (u32, u64) {
+    use std::os::unix::fs::MetadataExt;
+
+    (metadata.mode() & 0o7777, metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn mode_and_inode(_: &std::fs::Metadata) -> (u32, u64) {
+    (0, 0)
+}
+
+/// The records of every entry of a walk, by path below the input root.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    algorithm: Option<HashAlgorithm>,
+    /// The inputs of the walk, with the prefix their records are filed under. Empty for a
+    /// loaded snapshot, whose paths are shown from the roots of the current one.
+    roots: Vec<(PathBuf, PathBuf)>,
+    records: BTreeMap<PathBuf, Record>,
+}
+
+impl Snapshot {
+    /// Index the entries walked from `roots`, hashing the regular files with `algorithm` on
+    /// rayon. Files the walk gave a digest made with `hashed` keep that one when it is the
+    /// same algorithm.
+    ///
+    /// Records are filed by their path below their root, so the same tree reached through
+    /// another spelling of its path, such as `./d` for `d`, has the same records. With several
+    /// roots, the paths start with the name of their root.
+    pub fn of(
+        roots: &[PathBuf],
+        entries: &[FileEntry],
+        algorithm: Option<HashAlgorithm>,
+        hashed: Option<HashAlgorithm>,
+    ) -> Self {
+        let mut roots: Vec<(PathBuf, PathBuf)> = match roots {
+            [root] => vec![(PathBuf::new(), root.clone())],
+            roots => roots.iter().map(|root| (root_name(root), root.clone())).collect(),
+        };
+        // Longest roots first, so nested inputs file their entries under their own name
+        roots.sort_by_key(|(_, root)| std::cmp::Reverse(root.components().count()));
+
+        let records = entries
+            .par_iter()
+            .map(|entry| {
+                let path = roots
+                    .iter()
+                    .find_map(|(prefix, root)| Some(below(prefix, entry.path.strip_prefix(root).ok()?)))
+                    .unwrap_or_else(|| entry.path.clone());
+                (path.clone(), Record::of(path, entry, algorithm, hashed))
+            })
+            .collect();
+        Self { algorithm, roots, records }
+    }
+
+    /// Where the entry filed under `path` is in the walk of this snapshot
+    pub fn shown(&self, path: &Path) -> PathBuf {
+        self.roots
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.components().count())
+            .map_or_else(
+                || path.to_path_buf(),
+                |(prefix, root)| below(root, path.strip_prefix(prefix).unwrap_or(path)),
+            )
+    }
+
+    /// The digest of the records, if they have one
+    pub fn algorithm(&self) -> Option<HashAlgorithm> {
+        self.algorithm
+    }
+
+    pub fn record(&self, path: &Path) -> Option<&Record> {
+        self.records.get(path)
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let invalid = |e: serde_json::Error| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+
+        let mut lines = std::io::BufReader::new(std::fs::File::open(path)?).lines();
+        let header: Header = match lines.next() {
+            Some(line) => serde_json::from_str(&line?).map_err(invalid)?,
+            None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "empty snapshot")),
+        };
+        if header.version != VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unsupported snapshot version {}", header.version),
+            ));
+        }
+        let mut records = BTreeMap::new();
+        for line in lines {
+            let record: Record = serde_json::from_str(&line?).map_err(invalid)?;
+            records.insert(record.path.clone(), record);
+        }
+        Ok(Self {
+            algorithm: header.hash,
+            roots: Vec::new(),
+            records,
+        })
+    }
+
+    /// Write the snapshot to `path`, replacing any previous one in one rename
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(&partial)?);
+        let header = Header {
+            version: VERSION,
+            hash: self.algorithm,
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writeln!(writer)?;
+        for record in self.records.values() {
+            serde_json::to_writer(&mut writer, record)?;
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        std::fs::rename(&partial, path)
+    }
+
+    /// How every path of either snapshot changed from `self` to `current`, in path order.
+    ///
+    /// The paths are the ones records are filed under, which [Snapshot::shown] turns into
+    /// paths of the current walk.
+    ///
+    /// Directories in both are modified when anything below them changed.
+    pub fn changes(&self, current: &Snapshot) -> Vec<(PathBuf, DiffStatus)> {
+        let paths: BTreeSet<&PathBuf> = self.records.keys().chain(current.records.keys()).collect();
+        let mut changes: Vec<(PathBuf, DiffStatus)> = paths
+            .into_iter()
+            .map(|path| {
+                let status = match (self.records.get(path), current.records.get(path)) {
+                    (Some(old), Some(new)) => old.compare(new),
+                    (None, _) => DiffStatus::Added,
+                    (_, None) => DiffStatus::Removed,
+                };
+                (path.clone(), status)
+            })
+            .collect();
+
+        let changed_below = changed_parents(changes.iter().map(|(path, status)| (path.as_path(), *status)));
+        for (path, status) in &mut changes {
+            if *status == DiffStatus::Unchanged && changed_below.contains(path) {
+                *status = DiffStatus::Modified;
+            }
+        }
+        changes
+    }
+}
+
+/// The name records below `root` are filed under when a walk has several roots
+fn root_name(root: &Path) -> PathBuf {
+    std::fs::canonicalize(root)
+        .ok()
+        .and_then(|root| root.file_name().map(PathBuf::from))
+        .unwrap_or_else(|| root.to_path_buf())
+}
+
+/// `base` joined with `relative`, without the trailing separator joining an empty path adds
+fn below(base: &Path, relative: &Path) -> PathBuf {
+    if relative.as_os_str().is_empty() {
+        base.to_path_buf()
+    } else {
+        base.join(relative)
+    }
+}
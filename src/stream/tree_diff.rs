@@ -0,0 +1,122 @@
+//! Comparison of two walked trees, for `--diff`
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use rayon::prelude::*;
+
+use crate::flags::HashAlgorithm;
+use crate::meta::{DiffStatus, Digest, FileType};
+use crate::stream::FileEntry;
+
+/// An entry of either tree, with its counterpart in the other one when there is one
+#[derive(Debug, Clone)]
+pub struct DiffEntry {
+    /// The path below the root of both trees, empty for the roots themselves
+    pub relative: PathBuf,
+    pub old: Option<FileEntry>,
+    pub new: Option<FileEntry>,
+    pub status: DiffStatus,
+}
+
+impl DiffEntry {
+    /// The entry shown for the comparison: the new one, unless it was removed
+    pub fn entry(&self) -> &FileEntry {
+        self.new
+            .as_ref()
+            .or(self.old.as_ref())
+            .expect("a diff entry is in at least one tree")
+    }
+}
+
+/// Match the entries walked below `old_root` and `new_root` by their relative path, in path
+/// order.
+///
+/// Files are modified when their size or modification time differ, or with `checksum`, when
+/// their size or content digest differ. Directories present in both trees are modified when
+/// anything below them changed.
+pub fn compare_trees(
+    old_root: &Path,
+    old: Vec<FileEntry>,
+    new_root: &Path,
+    new: Vec<FileEntry>,
+    checksum: Option<HashAlgorithm>,
+) -> Vec<DiffEntry> {
+    let mut pairs: BTreeMap<PathBuf, (Option<FileEntry>, Option<FileEntry>)> = BTreeMap::new();
+    for entry in old {
+        let relative = relative(old_root, &entry.path);
+        pairs.entry(relative).or_default().0 = Some(entry);
+    }
+    for entry in new {
+        let relative = relative(new_root, &entry.path);
+        pairs.entry(relative).or_default().1 = Some(entry);
+    }
+
+    // Comparing content reads both files, so pairs are compared on rayon
+    let mut entries: Vec<DiffEntry> = pairs
+        .into_par_iter()
+        .map(|(relative, (old, new))| {
+            let status = match (&old, &new) {
+                (Some(old), Some(new)) => compare(old, new, checksum),
+                (None, _) => DiffStatus::Added,
+                (_, None) => DiffStatus::Removed,
+            };
+            DiffEntry { relative, old, new, status }
+        })
+        .collect();
+    entries.sort_by(|a, b| a.relative.cmp(&b.relative));
+
+    let changed_below = changed_parents(entries.iter().map(|entry| (entry.relative.as_path(), entry.status)));
+    for entry in &mut entries {
+        if entry.status == DiffStatus::Unchanged && changed_below.contains(&entry.relative) {
+            entry.status = DiffStatus::Modified;
+        }
+    }
+    entries
+}
+
+/// The directories with a change somewhere below them
+pub(crate) fn changed_parents<'a>(statuses: impl Iterator<Item = (&'a Path, DiffStatus)>) -> HashSet<PathBuf> {
+    statuses
+        .filter(|(_, status)| status.is_changed())
+        .flat_map(|(path, _)| path.ancestors().skip(1).map(Path::to_path_buf))
+        .collect()
+}
+
+fn relative(root: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(root).unwrap_or(path).to_path_buf()
+}
+
+fn compare(old: &FileEntry, new: &FileEntry, checksum: Option<HashAlgorithm>) -> DiffStatus {
+    if std::mem::discriminant(&old.file_type) != std::mem::discriminant(&new.file_type) {
+        return DiffStatus::TypeChanged;
+    }
+    let modified = match old.file_type {
+        FileType::File { .. } => {
+            old.metadata.len() != new.metadata.len()
+                || match checksum {
+                    Some(algorithm) => !same_content(&old.path, &new.path, algorithm),
+                    None => old.metadata.modified().ok() != new.metadata.modified().ok(),
+                }
+        }
+        FileType::SymLink { .. } => std::fs::read_link(&old.path).ok() != std::fs::read_link(&new.path).ok(),
+        _ => false,
+    };
+    if modified {
+        DiffStatus::Modified
+    } else {
+        DiffStatus::Unchanged
+    }
+}
+
+/// Whether two files have the same digest, taking unreadable files for different ones
+fn same_content(old: &Path, new: &Path, algorithm: HashAlgorithm) -> bool {
+    let digest = |path: &Path| {
+        Digest::of_file(path, algorithm)
+            .map_err(|e| log::debug!("Cannot hash '{}': {}", path.display(), e))
+            .ok()
+    };
+    match (digest(old), digest(new)) {
+        (Some(old), Some(new)) => old == new,
+        _ => false,
+    }
+}
@@ -187,6 +187,8 @@ pub struct Dir {
     pub uid: Color,
     #[serde(deserialize_with = "deserialize_color")]
     pub no_uid: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub mount_point: Color,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -228,6 +230,8 @@ pub struct Size {
     pub medium: Color,
     #[serde(deserialize_with = "deserialize_color")]
     pub large: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub sparse: Color,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -330,6 +334,7 @@ impl FileType {
             dir: Dir {
                 uid: Color::Rgb { r: 194, g: 97, b: 195 },     // CYRUP accent #c261c3
                 no_uid: Color::Rgb { r: 179, g: 172, b: 255 }, // CYRUP hint #b3acff
+                mount_point: Color::Rgb { r: 255, g: 177, b: 0 }, // CYRUP yellow #ffb100
             },
             symlink: Symlink {
                 default: Color::Rgb { r: 125, g: 211, b: 252 },     // CYRUP bright cyan #7dd3fc
@@ -359,6 +364,7 @@ impl Default for Dir {
         Dir {
             uid: Color::AnsiValue(33),    // DodgerBlue1
             no_uid: Color::AnsiValue(33), // DodgerBlue1
+            mount_point: Color::AnsiValue(172), // Orange3
         }
     }
 }
@@ -397,6 +403,7 @@ impl Default for Size {
             small: Color::AnsiValue(229),  // Wheat1
             medium: Color::AnsiValue(216), // LightSalmon1
             large: Color::AnsiValue(172),  // Orange3
+            sparse: Color::AnsiValue(117), // SkyBlue1
         }
     }
 }
@@ -408,6 +415,7 @@ impl Size {
             small: Color::Rgb { r: 249, g: 249, b: 249 },    // CYRUP foreground #F9F9F9
             medium: Color::Rgb { r: 255, g: 177, b: 0 },     // CYRUP yellow #ffb100
             large: Color::Rgb { r: 255, g: 0, b: 158 },      // CYRUP bright magenta #ff009e
+            sparse: Color::Rgb { r: 0, g: 209, b: 255 },     // CYRUP cyan #00d1ff
         }
     }
 }
@@ -536,7 +544,7 @@ impl ColorTheme {
     fn default_render_rules() -> Vec<super::render::RenderRule> {
         use super::render::*;
         use crate::git::GitStatus;
-        use crate::meta::FileType;
+        use crate::meta::{ContentClass, FileType};
         
         vec![
             // Modified directories - show with bright colors
@@ -576,6 +584,12 @@ impl ColorTheme {
                     ..Default::default()
                 },
             },
+            // Extensionless files, by the class of their sniffed content
+            Self::content_rule(ContentClass::Executable, 0, 255, 117), // CYRUP bright green #00ff75
+            Self::content_rule(ContentClass::Script, 125, 211, 252),   // CYRUP bright cyan #7dd3fc
+            Self::content_rule(ContentClass::Archive, 255, 0, 158),    // CYRUP bright magenta #ff009e
+            Self::content_rule(ContentClass::Image, 194, 97, 195),     // CYRUP accent #c261c3
+            Self::content_rule(ContentClass::Document, 255, 177, 0),   // CYRUP yellow #ffb100
             // All files - default muted
             RenderRule {
                 matchers: RuleMatchers::default(), // No conditions = matches all
@@ -586,4 +600,21 @@ impl ColorTheme {
             },
         ]
     }
+
+    /// A rule coloring the icon and name of the files whose content is of `class`
+    fn content_rule(class: crate::meta::ContentClass, r: u8, g: u8, b: u8) -> super::render::RenderRule {
+        use super::render::*;
+
+        RenderRule {
+            matchers: RuleMatchers {
+                contents: Some(vec![class]),
+                ..Default::default()
+            },
+            display: DisplaySettings {
+                icon_color: Some(ExtendedColor::Rgba { r, g, b, a: 1.0 }),
+                name_color: Some(ExtendedColor::Rgba { r, g, b, a: 1.0 }),
+                ..Default::default()
+            },
+        }
+    }
 }
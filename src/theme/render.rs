@@ -1,5 +1,5 @@
 use crate::git::GitStatus;
-use crate::meta::FileType;
+use crate::meta::{ContentClass, FileType};
 use crossterm::style::Color;
 use serde::Deserialize;
 
@@ -48,6 +48,8 @@ pub struct RenderRule {
 pub struct RuleMatchers {
     pub file_types: Option<Vec<FileType>>,
     pub extensions: Option<Vec<String>>,
+    /// Classes of sniffed content, known for extensionless files
+    pub contents: Option<Vec<ContentClass>>,
     pub git_statuses: Option<Vec<GitStatus>>,
     pub error_status: Option<ErrorStatus>,
     pub highlight: Option<Highlight>,
@@ -84,6 +86,7 @@ impl RenderRule {
         &self,
         file_type: &FileType,
         extension: Option<&str>,
+        content: Option<ContentClass>,
         git_status: Option<GitStatus>,
         error_status: ErrorStatus,
         highlight: Highlight,
@@ -106,6 +109,18 @@ impl RenderRule {
             }
         }
 
+        // Check content class match
+        if let Some(ref classes) = self.matchers.contents {
+            match content {
+                Some(class) => {
+                    if !classes.contains(&class) {
+                        return false;
+                    }
+                }
+                None => return false,
+            }
+        }
+
         // Check git status match
         if let Some(ref statuses) = self.matchers.git_statuses {
             match git_status {
@@ -0,0 +1,295 @@
+//! File system subscriptions, highlights and in-place redraws for `--watch`
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossterm::{cursor, queue, terminal};
+use notify::{EventKind, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::core::Core;
+use crate::display::{self, RowRenderer};
+use crate::flags::{Display, Layout};
+use crate::meta::{DiffStatus, FileType, Meta};
+use crate::stream::{FileEntry, Record, Snapshot};
+use crate::{print_output, ExitCode};
+
+/// How long the file system has to stay quiet before the view is redrawn
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// How long changed entries stay highlighted
+const HIGHLIGHT: Duration = Duration::from_secs(3);
+
+/// Why [FsEvents::wait] returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wake {
+    /// Something changed, and nothing more for [DEBOUNCE]
+    Changed,
+    /// The timeout elapsed first
+    Timeout,
+    /// The subscription ended
+    Closed,
+}
+
+/// List `paths` down to `depth` with `renderer`, then list them again after each burst of
+/// changes below them, until interrupted.
+///
+/// The frames are redrawn in place on a terminal, and printed one after the other to
+/// `output` or a stdout that is not one. Failing to watch or to draw returns the message to
+/// report.
+pub async fn watch(
+    core: &mut Core,
+    renderer: &RowRenderer,
+    paths: &[PathBuf],
+    depth: usize,
+    mut output: Option<Box<dyn Write + Send>>,
+) -> Result<ExitCode, String> {
+    use std::io::IsTerminal;
+
+    // Polled once so the handler is in place before the screen is switched: the default one
+    // would end the process without leaving the alternate screen
+    let interrupted = tokio::signal::ctrl_c();
+    tokio::pin!(interrupted);
+    if futures::poll!(&mut interrupted).is_ready() {
+        return Ok(ExitCode::OK);
+    }
+
+    let mut events = FsEvents::subscribe(paths, depth > 1).map_err(|e| format!("cannot watch: {}", e))?;
+    // Without a terminal to redraw in, each frame is printed after the previous one
+    let mut screen = if output.is_none() && std::io::stdout().is_terminal() {
+        Some(Screen::open().map_err(|e| format!("cannot draw on the terminal: {}", e))?)
+    } else {
+        None
+    };
+
+    let mut exit_code = ExitCode::OK;
+    let mut recent = RecentChanges::default();
+    let mut previous: Option<Snapshot> = None;
+    loop {
+        let file_stream = core.file_stream(paths, depth);
+        // Walking a large tree takes a while, and can be interrupted too
+        let walked = tokio::select! {
+            _ = &mut interrupted => None,
+            entries = core.collect_entries(file_stream, &mut exit_code) => Some(entries),
+        };
+        let Some(entries) = walked else {
+            return Ok(exit_code);
+        };
+        let current = Snapshot::of(paths, &entries, None, None);
+        if let Some(previous) = &previous {
+            recent.update(previous, &current, Instant::now());
+        }
+        previous = Some(current);
+
+        let frame = frame(core, renderer, paths, &entries, &recent);
+        match (&mut screen, &mut output) {
+            (Some(screen), _) => screen
+                .draw(&frame)
+                .map_err(|e| format!("cannot draw on the terminal: {}", e))?,
+            (None, Some(output)) => {
+                let _ = writeln!(output, "{frame}");
+                let _ = output.flush();
+            }
+            (None, None) => {
+                print_output!("{frame}\n");
+            }
+        }
+
+        // Highlights fading out need a redraw too
+        let wake = tokio::select! {
+            _ = &mut interrupted => Wake::Closed,
+            wake = events.wait(recent.next_expiry(Instant::now())) => wake,
+        };
+        if wake == Wake::Closed {
+            return Ok(exit_code);
+        }
+    }
+}
+
+/// One view of the watched inputs, with the recent changes marked by the git status block
+/// of `renderer` and the entries removed recently still listed
+fn frame(core: &Core, renderer: &RowRenderer, paths: &[PathBuf], entries: &[FileEntry], recent: &RecentChanges) -> String {
+    let flags = renderer.flags();
+    let mark = |path: &Path, mut meta: Meta| {
+        let status = recent.status(path).unwrap_or(DiffStatus::Unchanged);
+        meta.diff_status = Some(status);
+        meta.git_status = Some(status.git_status());
+        (path.to_path_buf(), meta)
+    };
+    let mut listed: Vec<(PathBuf, Meta)> = entries.iter().map(|entry| mark(&entry.path, entry.to_meta(flags))).collect();
+    listed.extend(recent.removed().map(|(path, record)| mark(path, record.to_meta(path))));
+
+    if flags.layout == Layout::Tree {
+        let mut roots = Meta::nest(listed);
+        core.sort(&mut roots);
+        return renderer.tree(&roots);
+    }
+
+    // Like a single listing, directories given as inputs stand for their content
+    let mut metas: Vec<Meta> = listed
+        .into_iter()
+        .filter(|(path, meta)| {
+            flags.display == Display::DirectoryOnly
+                || !(paths.contains(path) && matches!(meta.file_type, FileType::Directory { .. }))
+        })
+        .map(|(_, meta)| meta)
+        .collect();
+    if metas.is_empty() {
+        return String::new();
+    }
+    core.sort(&mut metas);
+
+    let rendered = if flags.recursion.enabled || paths.len() > 1 {
+        renderer.render_paths(&metas)
+    } else {
+        renderer.render(&metas)
+    };
+    let rows = rendered.into_iter().zip(metas.iter().map(|meta| renderer.details(meta))).collect();
+    display::rows(rows, flags)
+}
+
+/// A subscription to the changes below the inputs, through inotify on Linux.
+pub struct FsEvents {
+    // Dropping the watcher ends the subscription
+    _watcher: notify::RecommendedWatcher,
+    receiver: mpsc::UnboundedReceiver<()>,
+}
+
+impl FsEvents {
+    pub fn subscribe(paths: &[PathBuf], recursive: bool) -> notify::Result<Self> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            // Reading the files to list them would wake the watch again
+            Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+            Ok(_) => {
+                let _ = sender.send(());
+            }
+            Err(e) => log::warn!("Watch error: {}", e),
+        })?;
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        for path in paths {
+            watcher.watch(path, mode)?;
+        }
+        Ok(Self {
+            _watcher: watcher,
+            receiver,
+        })
+    }
+
+    /// Wait for a burst of changes to settle, or for `timeout` to elapse
+    pub async fn wait(&mut self, timeout: Option<Duration>) -> Wake {
+        let first = match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, self.receiver.recv()).await {
+                Ok(event) => event,
+                Err(_) => return Wake::Timeout,
+            },
+            None => self.receiver.recv().await,
+        };
+        if first.is_none() {
+            return Wake::Closed;
+        }
+        while let Ok(Some(())) = tokio::time::timeout(DEBOUNCE, self.receiver.recv()).await {}
+        Wake::Changed
+    }
+}
+
+/// An entry that changed between two walks, and when
+struct Change {
+    status: DiffStatus,
+    at: Instant,
+    /// What a removed entry was, to keep listing it while it is highlighted
+    removed: Option<Record>,
+}
+
+/// The entries changed in the last [HIGHLIGHT].
+#[derive(Default)]
+pub struct RecentChanges {
+    changes: HashMap<PathBuf, Change>,
+}
+
+impl RecentChanges {
+    /// Remember what changed from `previous` to `current`, and forget the old changes
+    pub fn update(&mut self, previous: &Snapshot, current: &Snapshot, now: Instant) {
+        for (filed, status) in previous.changes(current) {
+            if !status.is_changed() {
+                continue;
+            }
+            let removed = (status == DiffStatus::Removed)
+                .then(|| previous.record(&filed).cloned())
+                .flatten();
+            self.changes.insert(current.shown(&filed), Change { status, at: now, removed });
+        }
+        self.changes.retain(|_, change| now.duration_since(change.at) < HIGHLIGHT);
+    }
+
+    /// How an entry changed, if it did recently
+    pub fn status(&self, path: &Path) -> Option<DiffStatus> {
+        self.changes.get(path).map(|change| change.status)
+    }
+
+    /// The entries removed recently, which the walk no longer finds, with where they were
+    pub fn removed(&self) -> impl Iterator<Item = (&Path, &Record)> {
+        self.changes
+            .iter()
+            .filter_map(|(path, change)| Some((path.as_path(), change.removed.as_ref()?)))
+    }
+
+    /// How long until the oldest highlight fades, and the view must be redrawn
+    pub fn next_expiry(&self, now: Instant) -> Option<Duration> {
+        self.changes
+            .values()
+            .map(|change| (change.at + HIGHLIGHT).saturating_duration_since(now))
+            .min()
+    }
+}
+
+/// The terminal the view is redrawn in, switched to its alternate screen while watching.
+pub struct Screen {
+    stdout: std::io::Stdout,
+}
+
+impl Screen {
+    pub fn open() -> std::io::Result<Self> {
+        let mut stdout = std::io::stdout();
+        queue!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        stdout.flush()?;
+        Ok(Self { stdout })
+    }
+
+    /// Replace the view with `frame` in place, clearing what is left of each line instead of
+    /// the whole screen so it does not flicker. Lines past the bottom are cut.
+    pub fn draw(&mut self, frame: &str) -> std::io::Result<()> {
+        let rows = terminal::size().map_or(usize::MAX, |(_, rows)| rows as usize);
+        let lines: Vec<&str> = frame.lines().collect();
+        let (shown, hidden) = if lines.len() > rows {
+            (&lines[..rows.saturating_sub(1)], lines.len() + 1 - rows)
+        } else {
+            (&lines[..], 0)
+        };
+
+        queue!(self.stdout, cursor::MoveTo(0, 0))?;
+        for line in shown {
+            write!(self.stdout, "{line}")?;
+            queue!(self.stdout, terminal::Clear(terminal::ClearType::UntilNewLine))?;
+            write!(self.stdout, "\r\n")?;
+        }
+        if hidden > 0 {
+            write!(self.stdout, "… {hidden} more lines")?;
+        }
+        queue!(self.stdout, terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        self.stdout.flush()
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = queue!(self.stdout, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = self.stdout.flush();
+    }
+}
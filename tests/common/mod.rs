@@ -0,0 +1,59 @@
+//! Helpers shared by the integration tests, each test binary using its own part of them
+#![allow(dead_code)]
+
+use assert_fs::prelude::*;
+use std::ffi::OsStr;
+use std::path::Path;
+
+/// kgls without colors or icons, which the arguments of a test can still turn on
+pub fn kgls_cmd() -> assert_cmd::Command {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("kgls");
+    cmd.args(["--color", "never", "--icon", "never"]);
+    cmd
+}
+
+/// What a run that has to succeed printed
+pub fn stdout(cmd: &mut assert_cmd::Command) -> String {
+    let output = cmd.output().expect("run kgls");
+    assert!(output.status.success(), "kgls failed: {output:?}");
+    String::from_utf8(output.stdout).expect("utf-8 output")
+}
+
+/// The output of kgls listing `path` with `args`
+pub fn kgls_stdout(args: &[&str], path: impl AsRef<OsStr>) -> String {
+    stdout(kgls_cmd().args(args).arg(path))
+}
+
+/// The output of kgls run from `dir` with `args`, inputs included
+pub fn kgls_stdout_in(dir: &Path, args: &[&str]) -> String {
+    stdout(kgls_cmd().current_dir(dir).args(args))
+}
+
+/// The lines kgls prints listing `path` with `args`, trimmed and without the blank ones
+pub fn kgls_lines(args: &[&str], path: impl AsRef<OsStr>) -> Vec<String> {
+    non_blank_lines(&kgls_stdout(args, path))
+}
+
+/// The lines of an output, trimmed and without the blank ones
+pub fn non_blank_lines(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// The lines of an output without their trailing padding, to compare with a layout
+pub fn lines(stdout: &str) -> Vec<&str> {
+    stdout.lines().map(str::trim_end).collect()
+}
+
+/// A `listing` directory in `temp` holding `files`, by path and content
+pub fn listing(temp: &assert_fs::TempDir, files: &[(&str, &str)]) -> assert_fs::fixture::ChildPath {
+    let root = temp.child("listing");
+    root.create_dir_all().expect("create listing dir");
+    for (path, content) in files {
+        root.child(path).write_str(content).expect("write file");
+    }
+    root
+}
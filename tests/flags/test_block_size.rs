@@ -0,0 +1,48 @@
+use clap::Parser;
+
+use kgls::flags::block_size::BlockSize;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, BlockSize::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_units() {
+    for (value, expected) in [
+        ("K", BlockSize::Kilo),
+        ("M", BlockSize::Mega),
+        ("G", BlockSize::Giga),
+        ("SI", BlockSize::Si),
+    ] {
+        let cli = Cli::try_parse_from(["lsd", "--block-size", value]).unwrap();
+        assert_eq!(Some(expected), BlockSize::from_cli(&cli));
+    }
+}
+
+#[test]
+fn test_from_cli_invalid() {
+    assert!(Cli::try_parse_from(["lsd", "--block-size", "T"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, BlockSize::from_config(&Config::with_none()));
+}
+
+#[test]
+fn test_from_config_value() {
+    let c: Config = serde_yaml::from_str("block-size: SI").unwrap();
+    assert_eq!(Some(BlockSize::Si), BlockSize::from_config(&c));
+}
+
+#[test]
+fn test_default() {
+    assert_eq!(BlockSize::Default, BlockSize::default());
+}
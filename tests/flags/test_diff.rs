@@ -0,0 +1,35 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+use kgls::flags::diff::Diff;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, Diff::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_paths() {
+    let argv = ["lsd", "--diff", "release-1", "release-2"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(
+        Some(Diff(Some((PathBuf::from("release-1"), PathBuf::from("release-2"))))),
+        Diff::from_cli(&cli)
+    );
+}
+
+#[test]
+fn test_from_cli_needs_two_paths() {
+    assert!(Cli::try_parse_from(["lsd", "--diff", "release-1"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, Diff::from_config(&Config::with_none()));
+}
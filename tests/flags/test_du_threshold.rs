@@ -0,0 +1,44 @@
+use clap::Parser;
+
+use kgls::flags::du_threshold::DuThreshold;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_none");
+    assert_eq!(None, DuThreshold::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_value() {
+    let argv = ["lsd", "--du-threshold", "5"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_value");
+    assert_eq!(Some(DuThreshold(5)), DuThreshold::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_out_of_range() {
+    let argv = ["lsd", "--du-threshold", "101"];
+    assert!(Cli::try_parse_from(argv).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, DuThreshold::from_config(&Config::with_none()));
+}
+
+#[test]
+fn test_from_config_value() {
+    let mut c = Config::with_none();
+    c.du_threshold = Some(0);
+    assert_eq!(Some(DuThreshold(0)), DuThreshold::from_config(&c));
+}
+
+#[test]
+fn test_default() {
+    assert_eq!(DuThreshold(1), DuThreshold::default());
+}
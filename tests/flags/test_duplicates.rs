@@ -0,0 +1,31 @@
+use clap::Parser;
+
+use kgls::flags::duplicates::Duplicates;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, Duplicates::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_true() {
+    let argv = ["lsd", "--duplicates"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(Duplicates(true)), Duplicates::from_cli(&cli));
+}
+
+#[test]
+fn test_conflicts_with_tree() {
+    assert!(Cli::try_parse_from(["lsd", "--duplicates", "--tree"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, Duplicates::from_config(&Config::with_none()));
+}
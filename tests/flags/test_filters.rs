@@ -0,0 +1,68 @@
+use clap::Parser;
+use std::time::Duration;
+
+use kgls::flags::filters::{Filters, TypeFilter};
+
+use kgls::app::Cli;
+
+#[test]
+fn test_configuration_from_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_configuration_from_none");
+    let filters = Filters::configure_from(&cli).expect("no filters");
+    assert!(!filters.is_active());
+}
+
+#[test]
+fn test_configuration_from_args() {
+    let argv = ["lsd", "--min-size", "1K", "--max-size", "2M", "--type", "f,l", "--newer", "2d"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_configuration_from_args");
+    let filters = Filters::configure_from(&cli).expect("valid filters");
+    assert!(filters.is_active());
+    assert_eq!(filters.min_size, Some(1024));
+    assert_eq!(filters.max_size, Some(2 * 1024 * 1024));
+    assert_eq!(filters.types, [TypeFilter::File, TypeFilter::SymLink]);
+    assert!(filters.newer.is_some());
+}
+
+#[test]
+fn test_configuration_from_invalid_size() {
+    let argv = ["lsd", "--min-size", "12Q"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_configuration_from_invalid_size");
+    assert!(Filters::configure_from(&cli).is_err());
+}
+
+#[test]
+fn test_configuration_from_missing_reference_file() {
+    let argv = ["lsd", "--older", "/nonexistent/reference"];
+    let cli = Cli::try_parse_from(argv)
+        .expect("Failed to parse CLI arguments for test_configuration_from_missing_reference_file");
+    assert!(Filters::configure_from(&cli).is_err());
+}
+
+#[test]
+fn test_invalid_type_is_rejected() {
+    let argv = ["lsd", "--type", "x"];
+    assert!(Cli::try_parse_from(argv).is_err());
+}
+
+#[test]
+fn test_size_from() {
+    assert_eq!(Filters::size_from("512").ok(), Some(512));
+    assert_eq!(Filters::size_from("10k").ok(), Some(10 * 1024));
+    assert_eq!(Filters::size_from("1.5M").ok(), Some(1536 * 1024));
+    assert_eq!(Filters::size_from("2GiB").ok(), Some(2 << 30));
+    assert_eq!(Filters::size_from("3KB").ok(), Some(3 * 1024));
+    assert!(Filters::size_from("M").is_err());
+    assert!(Filters::size_from("-1K").is_err());
+}
+
+#[test]
+fn test_duration_from() {
+    assert_eq!(Filters::duration_from("45s"), Some(Duration::from_secs(45)));
+    assert_eq!(Filters::duration_from("2d"), Some(Duration::from_secs(2 * 86_400)));
+    assert_eq!(Filters::duration_from("1d12h"), Some(Duration::from_secs(36 * 3_600)));
+    assert_eq!(Filters::duration_from("1w"), Some(Duration::from_secs(7 * 86_400)));
+    assert_eq!(Filters::duration_from("12"), None);
+    assert_eq!(Filters::duration_from("./file"), None);
+}
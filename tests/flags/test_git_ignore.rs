@@ -0,0 +1,33 @@
+use clap::Parser;
+
+use kgls::flags::git_ignore::GitIgnore;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_none");
+    assert_eq!(None, GitIgnore::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_true() {
+    let argv = ["lsd", "--git-ignore"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_true");
+    assert_eq!(Some(GitIgnore(true)), GitIgnore::from_cli(&cli));
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, GitIgnore::from_config(&Config::with_none()));
+}
+
+#[test]
+fn test_from_config_true() {
+    let mut c = Config::with_none();
+    c.git_ignore = Some(true);
+    assert_eq!(Some(GitIgnore(true)), GitIgnore::from_config(&c));
+}
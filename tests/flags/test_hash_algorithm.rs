@@ -0,0 +1,42 @@
+use clap::Parser;
+
+use kgls::flags::hash_algorithm::HashAlgorithm;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_none");
+    assert_eq!(None, HashAlgorithm::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_blake3() {
+    let argv = ["lsd", "--hash", "blake3"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_blake3");
+    assert_eq!(Some(HashAlgorithm::Blake3), HashAlgorithm::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_invalid() {
+    assert!(Cli::try_parse_from(["lsd", "--hash", "sha256"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, HashAlgorithm::from_config(&Config::with_none()));
+}
+
+#[test]
+fn test_from_config_value() {
+    let c: Config = serde_yaml::from_str("hash: blake3").expect("Failed to parse config");
+    assert_eq!(Some(HashAlgorithm::Blake3), HashAlgorithm::from_config(&c));
+}
+
+#[test]
+fn test_default() {
+    assert_eq!(HashAlgorithm::Xxh3, HashAlgorithm::default());
+}
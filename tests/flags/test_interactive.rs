@@ -0,0 +1,36 @@
+use clap::Parser;
+
+use kgls::flags::interactive::Interactive;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, Interactive::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_true() {
+    let argv = ["lsd", "--interactive"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(Interactive(true)), Interactive::from_cli(&cli));
+}
+
+#[test]
+fn test_conflicts_with_json() {
+    assert!(Cli::try_parse_from(["lsd", "--interactive", "--output", "json"]).is_err());
+}
+
+#[test]
+fn test_conflicts_with_watch() {
+    assert!(Cli::try_parse_from(["lsd", "--interactive", "--watch"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, Interactive::from_config(&Config::with_none()));
+}
@@ -20,6 +20,13 @@ fn test_from_cli_tree() {
     assert_eq!(Some(Layout::Tree), Layout::from_cli(&cli));
 }
 
+#[test]
+fn test_from_cli_du() {
+    let argv = ["lsd", "--du"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(Layout::Du), Layout::from_cli(&cli));
+}
+
 #[test]
 fn test_from_cli_oneline() {
     let argv = ["lsd", "--oneline"];
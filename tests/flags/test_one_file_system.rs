@@ -0,0 +1,33 @@
+use clap::Parser;
+
+use kgls::flags::one_file_system::OneFileSystem;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, OneFileSystem::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_true() {
+    let argv = ["lsd", "-x"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(OneFileSystem(true)), OneFileSystem::from_cli(&cli));
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, OneFileSystem::from_config(&Config::with_none()));
+}
+
+#[test]
+fn test_from_config_true() {
+    let mut c = Config::with_none();
+    c.one_file_system = Some(true);
+    assert_eq!(Some(OneFileSystem(true)), OneFileSystem::from_config(&c));
+}
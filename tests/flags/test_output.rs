@@ -0,0 +1,54 @@
+use clap::Parser;
+
+use kgls::flags::output::OutputFormat;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, OutputFormat::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_json() {
+    let argv = ["lsd", "--output", "json"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(OutputFormat::Json), OutputFormat::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_ndjson() {
+    let argv = ["lsd", "--output", "ndjson"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(OutputFormat::Ndjson), OutputFormat::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_invalid() {
+    let argv = ["lsd", "--output", "xml"];
+    assert!(Cli::try_parse_from(argv).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, OutputFormat::from_config(&Config::with_none()));
+}
+
+#[test]
+fn test_from_config_ndjson() {
+    let mut c = Config::with_none();
+    c.output = Some(OutputFormat::Ndjson);
+    assert_eq!(Some(OutputFormat::Ndjson), OutputFormat::from_config(&c));
+}
+
+#[test]
+fn test_builtin_config_is_text() {
+    assert_eq!(
+        Some(OutputFormat::Text),
+        OutputFormat::from_config(&Config::builtin())
+    );
+}
@@ -0,0 +1,33 @@
+use clap::Parser;
+
+use kgls::flags::reuse_hashes::ReuseHashes;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, ReuseHashes::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_true() {
+    let argv = ["lsd", "--hash-cache"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(ReuseHashes(true)), ReuseHashes::from_cli(&cli));
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, ReuseHashes::from_config(&Config::with_none()));
+}
+
+#[test]
+fn test_from_config_true() {
+    let mut c = Config::with_none();
+    c.hash_cache = Some(true);
+    assert_eq!(Some(ReuseHashes(true)), ReuseHashes::from_config(&c));
+}
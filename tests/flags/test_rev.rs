@@ -0,0 +1,31 @@
+use clap::Parser;
+
+use kgls::flags::rev::Rev;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, Rev::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_rev() {
+    let argv = ["lsd", "--rev", "HEAD~3", "src"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(Rev(Some("HEAD~3".to_string()))), Rev::from_cli(&cli));
+}
+
+#[test]
+fn test_conflicts_with_watch() {
+    assert!(Cli::try_parse_from(["lsd", "--rev", "v1.0", "--watch"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, Rev::from_config(&Config::with_none()));
+}
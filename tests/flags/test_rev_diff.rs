@@ -0,0 +1,31 @@
+use clap::Parser;
+
+use kgls::flags::rev_diff::RevDiff;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd", "--rev", "HEAD"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, RevDiff::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_true() {
+    let argv = ["lsd", "--rev", "HEAD", "--rev-diff"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(RevDiff(true)), RevDiff::from_cli(&cli));
+}
+
+#[test]
+fn test_requires_rev() {
+    assert!(Cli::try_parse_from(["lsd", "--rev-diff"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, RevDiff::from_config(&Config::with_none()));
+}
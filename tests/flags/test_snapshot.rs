@@ -0,0 +1,47 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+use kgls::flags::snapshot::Snapshot;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, Snapshot::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_save() {
+    let argv = ["lsd", "--snapshot", "before.kgls"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    let snapshot = Snapshot::from_cli(&cli).unwrap();
+    assert_eq!(snapshot.save, Some(PathBuf::from("before.kgls")));
+    assert_eq!(snapshot.since, None);
+}
+
+#[test]
+fn test_from_cli_since_and_save() {
+    let argv = ["lsd", "--since-snapshot", "before.kgls", "--snapshot", "after.kgls"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(
+        Some(Snapshot {
+            save: Some(PathBuf::from("after.kgls")),
+            since: Some(PathBuf::from("before.kgls")),
+        }),
+        Snapshot::from_cli(&cli)
+    );
+}
+
+#[test]
+fn test_conflicts_with_diff() {
+    assert!(Cli::try_parse_from(["lsd", "--snapshot", "a", "--diff", "old", "new"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, Snapshot::from_config(&Config::with_none()));
+}
@@ -1,6 +1,6 @@
 use clap::Parser;
 
-use kgls::flags::sorting::{SortColumn, SortOrder, DirGrouping};
+use kgls::flags::sorting::{SortColumn, SortKey, SortOrder, DirGrouping};
 
 use kgls::app::Cli;
 use kgls::config_file::{Config, Sorting};
@@ -112,6 +112,7 @@ mod test_sort_column {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: None,
+            keys: None,
             reverse: None,
             dir_grouping: None,
         });
@@ -124,6 +125,7 @@ mod test_sort_column {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: Some(SortColumn::Extension),
+            keys: None,
             reverse: None,
             dir_grouping: None,
         });
@@ -135,6 +137,7 @@ mod test_sort_column {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: Some(SortColumn::Name),
+            keys: None,
             reverse: None,
             dir_grouping: None,
         });
@@ -146,6 +149,7 @@ mod test_sort_column {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: Some(SortColumn::Time),
+            keys: None,
             reverse: None,
             dir_grouping: None,
         });
@@ -157,6 +161,7 @@ mod test_sort_column {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: Some(SortColumn::Size),
+            keys: None,
             reverse: None,
             dir_grouping: None,
         });
@@ -168,6 +173,7 @@ mod test_sort_column {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: Some(SortColumn::Version),
+            keys: None,
             reverse: None,
             dir_grouping: None,
         });
@@ -179,6 +185,7 @@ mod test_sort_column {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: Some(SortColumn::GitStatus),
+            keys: None,
             reverse: None,
             dir_grouping: None,
         });
@@ -221,6 +228,7 @@ mod test_sort_order {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: None,
+            keys: None,
             reverse: None,
             dir_grouping: None,
         });
@@ -232,6 +240,7 @@ mod test_sort_order {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: None,
+            keys: None,
             reverse: Some(true),
             dir_grouping: None,
         });
@@ -243,6 +252,7 @@ mod test_sort_order {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: None,
+            keys: None,
             reverse: Some(false),
             dir_grouping: None,
         });
@@ -318,6 +328,7 @@ mod test_dir_grouping {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: None,
+            keys: None,
             reverse: None,
             dir_grouping: Some(DirGrouping::First),
         });
@@ -329,6 +340,7 @@ mod test_dir_grouping {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: None,
+            keys: None,
             reverse: None,
             dir_grouping: Some(DirGrouping::Last),
         });
@@ -340,6 +352,7 @@ mod test_dir_grouping {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: None,
+            keys: None,
             reverse: None,
             dir_grouping: None,
         });
@@ -351,6 +364,7 @@ mod test_dir_grouping {
         let mut c = Config::with_none();
         c.sorting = Some(Sorting {
             column: None,
+            keys: None,
             reverse: None,
             dir_grouping: Some(DirGrouping::Last),
         });
@@ -358,3 +372,86 @@ mod test_dir_grouping {
         assert_eq!(Some(DirGrouping::None), DirGrouping::from_config(&c));
     }
 }
+
+mod test_sort_key {
+    use super::*;
+
+    #[test]
+    fn test_natural_direction() {
+        assert_eq!(Ok(SortKey::new(SortColumn::Name)), SortKey::from_arg_str("name"));
+        assert_eq!(Ok(SortKey::new(SortColumn::Size)), SortKey::from_arg_str("size:desc"));
+        assert_eq!(Ok(SortKey::new(SortColumn::Extension)), SortKey::from_arg_str("ext:asc"));
+    }
+
+    #[test]
+    fn test_opposite_direction() {
+        let key = SortKey::from_arg_str("time:asc").unwrap();
+        assert_eq!((SortColumn::Time, SortOrder::Reverse), (key.column, key.order));
+        let key = SortKey::from_arg_str("version:desc").unwrap();
+        assert_eq!((SortColumn::Version, SortOrder::Reverse), (key.column, key.order));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(SortKey::from_arg_str("colour").is_err());
+        assert!(SortKey::from_arg_str("size:up").is_err());
+        assert!(SortKey::chain_from_str("ext,,name").is_err());
+    }
+}
+
+mod test_sorting_chain {
+    use super::*;
+
+    use kgls::flags::Sorting as SortingFlags;
+
+    fn keys(argv: &[&str], config: &Config) -> Vec<SortKey> {
+        let cli = Cli::try_parse_from(argv).unwrap();
+        SortingFlags::configure_from(&cli, config).unwrap().keys
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(vec![SortKey::new(SortColumn::Name)], keys(&["lsd"], &Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_cli_chain() {
+        assert_eq!(
+            SortKey::chain_from_str("ext,size:desc,name").unwrap(),
+            keys(&["lsd", "--sort", "ext,size:desc,name"], &Config::with_none())
+        );
+    }
+
+    #[test]
+    fn test_reverse_flips_every_key() {
+        let reversed = keys(&["lsd", "--sort", "ext,size:asc", "--reverse"], &Config::with_none());
+        assert_eq!(SortKey::chain_from_str("ext:desc,size:desc").unwrap(), reversed);
+    }
+
+    #[test]
+    fn test_from_config_keys() {
+        let mut c = Config::with_none();
+        c.sorting = Some(Sorting {
+            column: Some(SortColumn::Time),
+            keys: Some(SortKey::chain_from_str("git,name").unwrap()),
+            reverse: None,
+            dir_grouping: None,
+        });
+        assert_eq!(SortKey::chain_from_str("git,name").unwrap(), keys(&["lsd"], &c));
+        assert_eq!(vec![SortKey::new(SortColumn::Size)], keys(&["lsd", "-S"], &c));
+    }
+
+    #[test]
+    fn test_from_cli_chain_with_spaces() {
+        assert_eq!(
+            SortKey::chain_from_str("ext,size:desc").unwrap(),
+            keys(&["lsd", "--sort", "ext, size : desc"], &Config::with_none())
+        );
+    }
+
+    #[test]
+    fn test_invalid_cli_chain() {
+        let cli = Cli::try_parse_from(["lsd", "--sort", "size:sideways"]).unwrap();
+        assert!(SortingFlags::configure_from(&cli, &Config::with_none()).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+use clap::Parser;
+
+use kgls::flags::summary::Summary;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_none");
+    assert_eq!(None, Summary::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_true() {
+    let argv = ["lsd", "--summary"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_true");
+    assert_eq!(Some(Summary(true)), Summary::from_cli(&cli));
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, Summary::from_config(&Config::with_none()));
+}
+
+#[test]
+fn test_from_config_true() {
+    let mut c = Config::with_none();
+    c.summary = Some(true);
+    assert_eq!(Some(Summary(true)), Summary::from_config(&c));
+}
@@ -0,0 +1,59 @@
+use clap::Parser;
+
+use kgls::flags::time_field::TimeField;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_none");
+    assert_eq!(None, TimeField::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_time() {
+    let argv = ["lsd", "--time", "birth"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_time");
+    assert_eq!(Some(TimeField::Birth), TimeField::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_access_and_change_shortcuts() {
+    let cli = Cli::try_parse_from(["lsd", "-u"]).expect("Failed to parse CLI arguments for -u");
+    assert_eq!(Some(TimeField::Access), TimeField::from_cli(&cli));
+
+    let cli = Cli::try_parse_from(["lsd", "-c"]).expect("Failed to parse CLI arguments for -c");
+    assert_eq!(Some(TimeField::Change), TimeField::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_last_one_wins() {
+    let argv = ["lsd", "-u", "--time", "change", "-u"];
+    let cli = Cli::try_parse_from(argv).expect("Failed to parse CLI arguments for test_from_cli_last_one_wins");
+    assert_eq!(Some(TimeField::Access), TimeField::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_invalid() {
+    assert!(Cli::try_parse_from(["lsd", "--time", "creation"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, TimeField::from_config(&Config::with_none()));
+}
+
+#[test]
+fn test_from_config_value() {
+    let mut c = Config::with_none();
+    c.time = Some(TimeField::Change);
+    assert_eq!(Some(TimeField::Change), TimeField::from_config(&c));
+}
+
+#[test]
+fn test_default() {
+    assert_eq!(TimeField::Modified, TimeField::default());
+}
@@ -0,0 +1,31 @@
+use clap::Parser;
+
+use kgls::flags::watch::Watch;
+
+use kgls::app::Cli;
+use kgls::config_file::Config;
+use kgls::flags::Configurable;
+
+#[test]
+fn test_from_cli_none() {
+    let argv = ["lsd"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(None, Watch::from_cli(&cli));
+}
+
+#[test]
+fn test_from_cli_true() {
+    let argv = ["lsd", "--watch", "--tree"];
+    let cli = Cli::try_parse_from(argv).unwrap();
+    assert_eq!(Some(Watch(true)), Watch::from_cli(&cli));
+}
+
+#[test]
+fn test_conflicts_with_json() {
+    assert!(Cli::try_parse_from(["lsd", "--watch", "--output", "json"]).is_err());
+}
+
+#[test]
+fn test_from_config_none() {
+    assert_eq!(None, Watch::from_config(&Config::with_none()));
+}
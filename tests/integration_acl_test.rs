@@ -0,0 +1,106 @@
+#![cfg(unix)]
+
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_stdout;
+use kgls::meta::{Acl, AclTag};
+use serde_json::Value;
+
+const UNDEFINED_ID: u32 = u32::MAX;
+
+/// An ACL in the `system.posix_acl_access` format
+fn encode(entries: &[(u16, u16, u32)]) -> Vec<u8> {
+    let mut bytes = 2u32.to_le_bytes().to_vec();
+    for (tag, permissions, id) in entries {
+        bytes.extend(tag.to_le_bytes());
+        bytes.extend(permissions.to_le_bytes());
+        bytes.extend(id.to_le_bytes());
+    }
+    bytes
+}
+
+/// Owner rw, uid 4321 rwx, owning group r, mask r-x and others nothing
+fn shared_acl() -> Vec<u8> {
+    encode(&[
+        (0x01, 6, UNDEFINED_ID),
+        (0x02, 7, 4321),
+        (0x04, 4, UNDEFINED_ID),
+        (0x10, 5, UNDEFINED_ID),
+        (0x20, 0, UNDEFINED_ID),
+    ])
+}
+
+/// A file with an ACL next to one without, or [None] when the file system has no ACLs.
+fn listing(temp: &assert_fs::TempDir) -> Option<assert_fs::fixture::ChildPath> {
+    let root = common::listing(temp, &[("shared.txt", "x"), ("private.txt", "x")]);
+    xattr::set(root.child("shared.txt").path(), "system.posix_acl_access", &shared_acl()).ok()?;
+    Some(root)
+}
+
+#[test]
+fn test_decode_acl() {
+    let acl = Acl::from_xattr(&shared_acl()).expect("valid ACL");
+    let entries = acl.entries();
+    assert_eq!(entries.len(), 5);
+    assert_eq!((entries[1].tag, entries[1].id, entries[1].permissions), (AclTag::User, Some(4321), 7));
+    assert_eq!(entries[0].id, None);
+    // The mask takes write from the named user, and leaves the owner alone
+    assert_eq!(acl.effective(&entries[1]), 5);
+    assert_eq!(acl.effective(&entries[0]), 6);
+
+    assert_eq!(Acl::from_xattr(&[1, 0, 0, 0]), None);
+    assert_eq!(Acl::from_xattr(&shared_acl()[..10]), None);
+}
+
+/// `--acl` prints the entries under each file, like `getfacl`.
+#[test]
+fn test_acl_lines() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let Some(root) = listing(&temp) else {
+        return;
+    };
+
+    let stdout = kgls_stdout(&["-1", "--acl"], root.path());
+    let lines: Vec<&str> = stdout.lines().map(str::trim).collect();
+    assert_eq!(
+        lines,
+        [
+            "private.txt",
+            "shared.txt",
+            "user::rw-",
+            "user:4321:rwx  #effective:r-x",
+            "group::r--",
+            "mask::r-x",
+            "other::---",
+        ]
+    );
+
+    let stdout = kgls_stdout(&["-1"], root.path());
+    assert_eq!(stdout.lines().count(), 2, "{stdout}");
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// JSON records carry the entries with their effective rights.
+#[test]
+fn test_acl_json() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let Some(root) = listing(&temp) else {
+        return;
+    };
+
+    let json: Value = serde_json::from_str(&kgls_stdout(&["--output", "json"], root.path())).expect("valid JSON");
+    let entries = json.as_array().expect("top-level array");
+    let shared = entries.iter().find(|entry| entry["name"] == "shared.txt").expect("shared.txt listed");
+    let named = &shared["acl"][1];
+    assert_eq!(named["tag"], "user");
+    assert_eq!(named["id"], 4321);
+    assert_eq!(named["permissions"], "rwx");
+    assert_eq!(named["effective"], "r-x");
+
+    let private = entries.iter().find(|entry| entry["name"] == "private.txt").expect("private.txt listed");
+    assert!(private.get("acl").is_none(), "{private}");
+
+    temp.close().expect("cleanup temp dir");
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_lines;
+
+/// The columns of the line ending with `name`.
+fn columns<'a>(lines: &'a [String], name: &str) -> Vec<&'a str> {
+    lines
+        .iter()
+        .find(|line| line.ends_with(name))
+        .unwrap_or_else(|| panic!("{name} missing from {lines:?}"))
+        .split_whitespace()
+        .collect()
+}
+
+/// A 64 MiB file with nothing written to it, and a small file with data.
+fn listing(temp: &assert_fs::TempDir) -> assert_fs::fixture::ChildPath {
+    let root = common::listing(temp, &[("data.dat", &"x".repeat(3000))]);
+    std::fs::File::create(root.child("image.raw").path())
+        .and_then(|file| file.set_len(64 << 20))
+        .expect("create sparse file");
+    root
+}
+
+/// The blocks column counts the allocated KiB, and `--disk-usage` shows the allocated bytes.
+#[cfg(unix)]
+#[test]
+fn test_allocated_sizes() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    let args = ["--color", "never", "--size", "bytes", "--blocks", "blocks,size,name"];
+    let lines = kgls_lines(&args, root.path());
+    assert_eq!(columns(&lines, "image.raw"), ["0", "67108864", "image.raw"]);
+    let data = columns(&lines, "data.dat");
+    assert_eq!(data[1], "3000");
+    let blocks: u64 = data[0].parse().expect("blocks count");
+    assert!(blocks >= 3, "{lines:?}");
+
+    let lines = kgls_lines(&[&args[..], &["--disk-usage"]].concat(), root.path());
+    assert_eq!(columns(&lines, "image.raw")[1], "0");
+    assert_eq!(columns(&lines, "data.dat")[1], (blocks * 1024).to_string());
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `--block-size` scales both columns, rounding up.
+#[test]
+fn test_block_size_scales() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    let args = ["--color", "never", "--size", "short", "--blocks", "size,name"];
+    let lines = kgls_lines(&[&args[..], &["--block-size", "K"]].concat(), root.path());
+    assert_eq!(columns(&lines, "data.dat"), ["3K", "data.dat"]);
+    assert_eq!(columns(&lines, "image.raw"), ["65536K", "image.raw"]);
+
+    let lines = kgls_lines(&[&args[..], &["--block-size", "SI"]].concat(), root.path());
+    assert_eq!(columns(&lines, "data.dat"), ["3.0K", "data.dat"]);
+    assert_eq!(columns(&lines, "image.raw"), ["67M", "image.raw"]);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Sparse files get a color of their own.
+#[cfg(unix)]
+#[test]
+fn test_sparse_files_are_highlighted() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+    let dense = root.child("dense.raw");
+    dense.write_binary(&vec![1; 2 << 20]).expect("write file");
+
+    let lines = kgls_lines(&["--color", "always", "--blocks", "size,name"], root.path());
+    let size_color = |name: &str| {
+        let line = lines.iter().find(|line| line.contains(name)).expect("listed");
+        line.split('m').next().map(str::to_string).expect("color sequence")
+    };
+    assert_ne!(size_color("image.raw"), size_color("dense.raw"), "{lines:?}");
+
+    temp.close().expect("cleanup temp dir");
+}
@@ -0,0 +1,238 @@
+mod common;
+
+use common::{kgls_cmd, kgls_stdout, lines, stdout};
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 2023-11-14T22:13:20Z
+const MTIME: u64 = 1_700_000_000;
+
+/// A tar of a small project, owned by names that need not exist here
+fn tar(writer: impl Write) {
+    let mut builder = tar::Builder::new(writer);
+    let mut append = |path: &str, entry_type: tar::EntryType, mode: u32, data: &[u8]| {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(entry_type);
+        header.set_mode(mode);
+        header.set_size(data.len() as u64);
+        header.set_mtime(MTIME);
+        header.set_uid(4242);
+        header.set_gid(4343);
+        header.set_username("alice").unwrap();
+        header.set_groupname("staff").unwrap();
+        if entry_type == tar::EntryType::Symlink {
+            header.set_link_name("bin/tool").unwrap();
+        }
+        header.set_cksum();
+        builder.append_data(&mut header, path, data).unwrap();
+    };
+    append("project/", tar::EntryType::Directory, 0o755, b"");
+    append("project/bin/tool", tar::EntryType::Regular, 0o750, &[0; 1000]);
+    append("project/README.md", tar::EntryType::Regular, 0o644, b"readme\n");
+    append("project/.profile", tar::EntryType::Regular, 0o600, b"KEY=1\n");
+    append("project/run", tar::EntryType::Symlink, 0o777, b"");
+    builder.into_inner().unwrap().flush().unwrap();
+}
+
+fn tar_gz(dir: &Path) -> PathBuf {
+    let path = dir.join("project.tar.gz");
+    let mut encoder = flate2::write::GzEncoder::new(std::fs::File::create(&path).unwrap(), flate2::Compression::default());
+    tar(&mut encoder);
+    encoder.finish().unwrap();
+    path
+}
+
+fn tar_zst(dir: &Path) -> PathBuf {
+    let path = dir.join("project.tar.zst");
+    let mut encoder = zstd::stream::write::Encoder::new(std::fs::File::create(&path).unwrap(), 0).unwrap();
+    tar(&mut encoder);
+    encoder.finish().unwrap();
+    path
+}
+
+fn zip(dir: &Path) -> PathBuf {
+    let path = dir.join("site.zip");
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    let options = zip::write::SimpleFileOptions::default().unix_permissions(0o640);
+    // The directories are only implied by the paths of the files
+    writer.start_file("docs/guide.md", options).unwrap();
+    writer.write_all(b"# Guide\n").unwrap();
+    writer.start_file("docs/api/index.md", options).unwrap();
+    writer.write_all(b"# API\n").unwrap();
+    writer.start_file("index.md", options).unwrap();
+    writer.write_all(b"home\n").unwrap();
+    writer.add_symlink("latest", "docs", options).unwrap();
+    writer.finish().unwrap();
+    path
+}
+
+#[test]
+fn test_long_view_shows_member_headers() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let archive = tar_gz(temp.path());
+    let inner = PathBuf::from(format!("{}//project", archive.display()));
+    let stdout = kgls_stdout(&["-l", "--size", "bytes", "--date", "+%Y-%m-%d"], inner);
+    let lines = lines(&stdout);
+    assert_eq!(lines.len(), 3, "{stdout}");
+    // The directory only implied by the path of bin/tool carries no owner
+    for (line, mode, owner, size, name) in [
+        (lines[0], "rwxr-xr-x", "? ?", "0", "bin"),
+        (lines[1], "rw-r--r--", "alice staff", "7", "README.md"),
+        (lines[2], "rwxrwxrwx", "alice staff", "0", "run ⇒ bin/tool"),
+    ] {
+        assert!(line.contains(mode), "{line}");
+        assert!(line.split_whitespace().collect::<Vec<_>>().join(" ").contains(owner), "{line}");
+        assert!(line.contains(&format!(" {size}\u{2009}")) || line.contains(&format!(" {size} ")), "{line}");
+        assert!(line.ends_with(name), "{line}");
+    }
+    assert!(lines[1].contains("2023-11-14") || lines[1].contains("2023-11-15"), "{}", lines[1]);
+}
+
+#[test]
+fn test_tree_of_compressed_tar() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let archive = tar_zst(temp.path());
+    let stdout = kgls_stdout(&["--tree", "-a"], &archive);
+    let expected = "\
+project.tar.zst
+└── project
+    ├── .profile
+    ├── bin
+    │   └── tool
+    ├── README.md
+    └── run ⇒ bin/tool
+";
+    assert_eq!(lines(&stdout), expected.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_total_size_adds_up_members() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let archive = tar_gz(temp.path());
+    let stdout = kgls_stdout(
+        &["--tree", "--total-size", "--size", "bytes", "--blocks", "size,name"],
+        format!("{}//project", archive.display()),
+    );
+    let sizes: Vec<&str> = lines(&stdout).iter().map(|line| line.split_whitespace().next().unwrap()).collect();
+    // The hidden .profile is left out of the totals as it is of the listing
+    assert_eq!(sizes, ["1007", "1000", "1000", "7", "0"], "{stdout}");
+}
+
+#[test]
+fn test_directory_inside_zip() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let archive = zip(temp.path());
+    let stdout = kgls_stdout(&["-1"], format!("{}//", archive.display()));
+    assert_eq!(lines(&stdout), ["docs", "index.md", "latest ⇒ docs"]);
+
+    let stdout = kgls_stdout(&["-1"], format!("{}//docs", archive.display()));
+    assert_eq!(lines(&stdout), ["api", "guide.md"]);
+
+    let stdout = kgls_stdout(&["-l", "--blocks", "permission,name"], format!("{}//docs/guide.md", archive.display()));
+    assert!(stdout.contains("rw-r-----"), "{stdout}");
+}
+
+#[test]
+fn test_recursive_listing_names_each_directory() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let archive = zip(temp.path());
+    let stdout = kgls_stdout(&["-1", "-R"], &archive);
+    let name = archive.display();
+    let expected = format!(
+        "\
+{name}:
+docs
+index.md
+latest ⇒ docs
+
+{name}//docs:
+api
+guide.md
+
+{name}//docs/api:
+index.md
+"
+    );
+    assert_eq!(lines(&stdout), expected.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_json_output_of_members() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let archive = tar_gz(temp.path());
+    let inner = format!("{}//project/bin", archive.display());
+    let stdout = kgls_stdout(&["--output", "json"], &inner);
+    let entries: Value = serde_json::from_str(&stdout).unwrap();
+    let tool = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["name"] == "tool")
+        .expect("the member is listed");
+    assert_eq!(tool["path"], format!("{inner}/tool"));
+    assert_eq!(tool["size"], 1000);
+    assert_eq!(tool["permissions"], "0750");
+    assert_eq!(tool["owner"]["user"], "alice");
+    assert_eq!(tool["owner"]["uid"], 4242);
+    assert_eq!(tool["modified"].as_str().unwrap().get(..4), Some("2023"));
+}
+
+#[test]
+fn test_missing_member_is_reported() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let archive = zip(temp.path());
+    let output = kgls_cmd().arg(format!("{}//nowhere", archive.display())).output().expect("run kgls");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no 'nowhere' in the archive"));
+}
+
+#[test]
+fn test_directory_flag_lists_the_archive_file() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let archive = tar_zst(temp.path());
+    let stdout = kgls_stdout(&["-1", "-d"], &archive);
+    assert_eq!(lines(&stdout), ["project.tar.zst"]);
+}
+
+#[test]
+fn test_unreadable_archive_falls_back_to_the_file() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let bogus = temp.path().join("bogus.tar.zst");
+    std::fs::write(&bogus, b"not zstd").unwrap();
+    let output = kgls_cmd().arg("-1").arg(&bogus).output().expect("run kgls");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot read archive"));
+    assert_eq!(lines(&String::from_utf8(output.stdout).unwrap()), ["bogus.tar.zst"]);
+}
+
+#[test]
+fn test_archives_take_their_place_among_directories() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let archive = zip(temp.path());
+    let first = temp.path().join("first");
+    let last = temp.path().join("last");
+    std::fs::create_dir(&first).unwrap();
+    std::fs::create_dir(&last).unwrap();
+    std::fs::write(first.join("a"), b"").unwrap();
+    std::fs::write(last.join("z"), b"").unwrap();
+    let docs = PathBuf::from(format!("{}//docs", archive.display()));
+    let stdout = stdout(kgls_cmd().arg("-1").args([&first, &docs, &last]));
+    let expected = format!(
+        "\
+{}:
+a
+
+{}//docs:
+api
+guide.md
+
+{}:
+z
+",
+        first.display(),
+        archive.display(),
+        last.display()
+    );
+    assert_eq!(lines(&stdout), expected.lines().collect::<Vec<_>>());
+}
@@ -0,0 +1,80 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_stdout;
+use kgls::meta::{Capabilities, InodeFlags};
+use std::os::unix::io::AsRawFd;
+
+const FS_NODUMP_FL: libc::c_int = 0x40;
+const FS_NOATIME_FL: libc::c_int = 0x80;
+
+/// A `vfs_cap_data` value of revision 2
+fn encode(permitted: u64, inheritable: u64, effective: bool) -> Vec<u8> {
+    let mut bytes = (0x0200_0000u32 | u32::from(effective)).to_le_bytes().to_vec();
+    for half in 0..2 {
+        bytes.extend(((permitted >> (32 * half)) as u32).to_le_bytes());
+        bytes.extend(((inheritable >> (32 * half)) as u32).to_le_bytes());
+    }
+    bytes
+}
+
+/// Set the flags of `path` like `chattr`, or return false when the file system has none.
+fn set_flags(path: &std::path::Path, flags: libc::c_int) -> bool {
+    let file = std::fs::File::open(path).expect("open file");
+    unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_SETFLAGS, &flags) == 0 }
+}
+
+#[test]
+fn test_decode_capabilities() {
+    let bind = Capabilities::from_xattr(&encode(1 << 10, 0, true));
+    assert_eq!(bind.text().as_deref(), Some("cap_net_bind_service=ep"));
+
+    // Capabilities in the same sets are grouped, and the upper word is decoded
+    let mixed = Capabilities::from_xattr(&encode((1 << 12) | (1 << 13) | (1 << 39), 1 << 21, false));
+    assert_eq!(mixed.text().as_deref(), Some("cap_net_admin,cap_net_raw,cap_bpf=p cap_sys_admin=i"));
+
+    assert_eq!(Capabilities::from_xattr(&[0xff; 4]).text(), None);
+    assert_eq!(Capabilities::from_xattr(&[]).text(), None);
+}
+
+#[test]
+fn test_inode_flags_text() {
+    let flags = InodeFlags::from_bits(0x10 | 0x80);
+    assert!(flags.is_immutable());
+    assert!(!flags.is_append_only());
+    assert!(flags.is_noatime());
+    assert_eq!(flags.text().as_deref(), Some("i--A"));
+    assert_eq!(InodeFlags::default().text(), None);
+}
+
+#[test]
+fn test_caps_block() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("server").write_str("x").unwrap();
+    temp.child("plain").write_str("x").unwrap();
+    let capability = encode(1 << 10, 0, true);
+    if xattr::set(temp.child("server").path(), "security.capability", &capability).is_err() {
+        // Setting file capabilities needs CAP_SETFCAP
+        return;
+    }
+
+    let stdout = kgls_stdout(&["--blocks", "name,caps"], temp.path());
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0].split_whitespace().collect::<Vec<_>>(), ["plain", "-"]);
+    assert_eq!(lines[1].split_whitespace().collect::<Vec<_>>(), ["server", "cap_net_bind_service=ep"]);
+}
+
+#[test]
+fn test_flags_block() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("archive").write_str("x").unwrap();
+    temp.child("plain").write_str("x").unwrap();
+    if !set_flags(temp.child("archive").path(), FS_NODUMP_FL | FS_NOATIME_FL) {
+        return;
+    }
+
+    let stdout = kgls_stdout(&["--blocks", "flags,name"], temp.path());
+    assert_eq!(stdout, "--dA  archive\n----  plain\n");
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::{kgls_cmd, stdout};
+use serde_json::Value;
+use std::time::{Duration, SystemTime};
+
+/// kgls comparing `old` with `new`
+fn diff(args: &[&str], old: &std::path::Path, new: &std::path::Path) -> assert_cmd::Command {
+    let mut cmd = kgls_cmd();
+    cmd.args(args).arg("--diff").args([old, new]);
+    cmd
+}
+
+/// Write a file with a fixed modification time, so copies in both trees look unchanged
+fn write(child: &assert_fs::fixture::ChildPath, content: &str) {
+    child.write_str(content).unwrap();
+    let file = std::fs::File::options().write(true).open(child.path()).unwrap();
+    file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)).unwrap();
+}
+
+fn releases() -> assert_fs::TempDir {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write(&temp.child("old/src/main.rs"), "fn main() {}\n");
+    write(&temp.child("new/src/main.rs"), "fn main() {}\n");
+    write(&temp.child("old/README.md"), "v1\n");
+    write(&temp.child("new/README.md"), "v1.1\n");
+    write(&temp.child("old/retired/notes.txt"), "gone\n");
+    write(&temp.child("new/CHANGELOG.md"), "added\n");
+    write(&temp.child("old/config"), "a file\n");
+    temp.child("new/config").create_dir_all().unwrap();
+    temp
+}
+
+#[test]
+fn test_merged_tree() {
+    let temp = releases();
+    let stdout = stdout(&mut diff(&["--summary"], &temp.path().join("old"), &temp.path().join("new")));
+    let expected = "\
+│M│  new
+│?│  ├── CHANGELOG.md
+│T│  ├── config
+│M│  ├── README.md
+│D│  ├── retired
+│D│  │   └── notes.txt
+     └── src
+         └── main.rs
+1 added, 2 removed, 1 modified, 1 type changed
+";
+    let lines: Vec<&str> = stdout.lines().map(str::trim_end).collect();
+    assert_eq!(lines, expected.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_checksum_ignores_the_modification_time() {
+    let temp = releases();
+    let (old, new) = (temp.path().join("old"), temp.path().join("new"));
+    let file = std::fs::File::options().write(true).open(temp.child("new/src/main.rs").path()).unwrap();
+    file.set_modified(SystemTime::now()).unwrap();
+
+    let by_time: Value = serde_json::from_str(&stdout(&mut diff(&["--output", "json"], &old, &new))).unwrap();
+    let by_content: Value =
+        serde_json::from_str(&stdout(&mut diff(&["--output", "json", "--checksum"], &old, &new))).unwrap();
+    let src = |tree: &Value| {
+        let content = tree[0]["content"].as_array().unwrap();
+        content.iter().find(|entry| entry["name"] == "src").unwrap()["content"][0]["diff"].clone()
+    };
+    assert_eq!(src(&by_time), "modified");
+    assert_eq!(src(&by_content), "unchanged");
+}
+
+#[test]
+fn test_ndjson_lists_every_entry() {
+    let temp = releases();
+    let stdout = stdout(&mut diff(&["--output", "ndjson"], &temp.path().join("old"), &temp.path().join("new")));
+    let diffs: Vec<(String, String)> = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .map(|entry| (entry["name"].as_str().unwrap().to_string(), entry["diff"].as_str().unwrap().to_string()))
+        .collect();
+    assert!(diffs.contains(&("notes.txt".to_string(), "removed".to_string())));
+    assert!(diffs.contains(&("CHANGELOG.md".to_string(), "added".to_string())));
+    assert!(diffs.contains(&("config".to_string(), "type-changed".to_string())));
+    assert_eq!(diffs.len(), 8);
+}
+
+#[test]
+fn test_missing_tree() {
+    let temp = releases();
+    let output = diff(&[], &temp.path().join("old"), &temp.path().join("missing")).output().expect("run kgls");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("missing"));
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::kgls_stdout;
+
+/// The du view of `path`, sizes in bytes
+fn du_lines(args: &[&str], path: &std::path::Path) -> Vec<String> {
+    kgls_stdout(&[&["--size", "bytes", "--du"], args].concat(), path)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// The name at the end of a du line, after its tree prefix.
+fn name(line: &str) -> &str {
+    line.rsplit(['\u{2500}', ' ']).next().unwrap_or(line)
+}
+
+fn listing(temp: &assert_fs::TempDir) -> assert_fs::fixture::ChildPath {
+    common::listing(
+        temp,
+        &[
+            ("big/inner.dat", &"x".repeat(60_000)),
+            ("small.dat", &"x".repeat(20_000)),
+            ("tiny.dat", "x"),
+            ("crumb.dat", "x"),
+        ],
+    )
+}
+
+/// Children come largest first, each with its share of the parent and a bar.
+#[test]
+fn test_du_sorts_largest_first_with_shares() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    let lines = du_lines(&["--du-threshold", "0"], root.path());
+    let names: Vec<&str> = lines.iter().map(|line| name(line)).collect();
+    assert_eq!(names[..3], ["listing", "big", "inner.dat"], "{lines:?}");
+    assert_eq!(names[3], "small.dat", "{lines:?}");
+
+    assert!(lines[0].contains("100.0%"), "{lines:?}");
+    // The only file of `big` takes most of it
+    let inner = &lines[2];
+    let share: f64 = inner
+        .split_whitespace()
+        .find_map(|column| column.strip_suffix('%'))
+        .and_then(|share| share.parse().ok())
+        .expect("percentage column");
+    assert!(share > 90.0 && share < 100.0, "{inner}");
+    assert!(inner.contains('\u{2588}'), "{inner}");
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Entries under the threshold fold into one line with their combined size.
+#[test]
+fn test_du_collapses_small_entries() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    let lines = du_lines(&[], root.path());
+    assert!(lines.iter().all(|line| !line.contains("tiny.dat")), "{lines:?}");
+    let other = lines
+        .iter()
+        .find(|line| line.ends_with("2 other entries"))
+        .unwrap_or_else(|| panic!("no collapsed line in {lines:?}"));
+    assert_eq!(other.split_whitespace().next(), Some("2"));
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// A single entry under the threshold is listed by name, as folding it would save no line.
+#[test]
+fn test_du_keeps_a_lone_small_entry() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = common::listing(&temp, &[("big.dat", &"x".repeat(60_000)), ("tiny.dat", "x")]);
+
+    let lines = du_lines(&[], root.path());
+    assert!(lines.iter().any(|line| name(line) == "tiny.dat"), "{lines:?}");
+    assert!(lines.iter().all(|line| !line.contains("other entr")), "{lines:?}");
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Directories past `--depth` still show their full size.
+#[test]
+fn test_du_depth_limit_keeps_totals() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    let lines = du_lines(&["--depth", "1"], root.path());
+    let big = lines
+        .iter()
+        .find(|line| name(line) == "big")
+        .unwrap_or_else(|| panic!("big missing from {lines:?}"));
+    let size: u64 = big.split_whitespace().next().and_then(|size| size.parse().ok()).expect("size");
+    assert!(size >= 60_000, "{big}");
+    assert!(lines.iter().all(|line| name(line) != "inner.dat"), "{lines:?}");
+
+    temp.close().expect("cleanup temp dir");
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_stdout;
+use serde_json::Value;
+
+/// The duplicate sets below `path`
+fn duplicates(args: &[&str], path: &std::path::Path) -> String {
+    // Sizes are printed with a thin space before their unit
+    kgls_stdout(&[&["--duplicates"], args].concat(), path).replace('\u{2009}', " ")
+}
+
+fn tree() -> assert_fs::TempDir {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let large = "0123456789".repeat(1000);
+    temp.child("a/large.dat").write_str(&large).unwrap();
+    temp.child("b/large-copy.dat").write_str(&large).unwrap();
+    temp.child("small.txt").write_str("hello\n").unwrap();
+    temp.child("b/small-copy.txt").write_str("hello\n").unwrap();
+    // Same size and same first bytes, but a different end
+    let mut tail = large.clone();
+    tail.replace_range(9990.., "9876543210");
+    temp.child("a/tail.dat").write_str(&tail).unwrap();
+    temp.child("unique.txt").write_str("world\n").unwrap();
+    temp.child("empty.txt").touch().unwrap();
+    temp.child("empty-too.txt").touch().unwrap();
+    temp
+}
+
+#[test]
+fn test_duplicate_sets() {
+    let temp = tree();
+    let stdout = duplicates(&[], temp.path());
+    let root = temp.path().display().to_string();
+    let expected = format!(
+        "2 copies of 9.8 KB, 9.8 KB wasted\n\
+         {root}/b/large-copy.dat\n\
+         {root}/a/large.dat\n\
+         \n\
+         2 copies of 6 B, 6 B wasted\n\
+         {root}/b/small-copy.txt\n\
+         {root}/small.txt\n\
+         \n\
+         2 duplicate sets, 9.8 KB wasted\n"
+    );
+    assert_eq!(stdout, expected);
+}
+
+#[test]
+fn test_json_sets() {
+    let temp = tree();
+    let stdout = duplicates(&["--output", "json"], temp.path());
+    let sets: Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(sets.as_array().unwrap().len(), 2);
+    assert_eq!(sets[0]["size"], 10000);
+    assert_eq!(sets[0]["wasted"], 10000);
+    assert_eq!(sets[0]["files"].as_array().unwrap().len(), 2);
+    assert_eq!(sets[0]["files"][0]["hash"], sets[0]["hash"]);
+
+    let stdout = duplicates(&["--output", "ndjson"], temp.path());
+    assert_eq!(stdout.lines().count(), 2);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_hard_links_are_not_duplicates() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("original.txt").write_str("shared\n").unwrap();
+    std::fs::hard_link(temp.child("original.txt").path(), temp.child("link.txt").path()).unwrap();
+    assert_eq!(duplicates(&[], temp.path()), "");
+
+    // A hard link to one of the copies is listed with them, without adding to the waste
+    temp.child("copy.txt").write_str("shared\n").unwrap();
+    let sets: Value = serde_json::from_str(&duplicates(&["--output", "json"], temp.path())).unwrap();
+    assert_eq!(sets[0]["copies"], 2);
+    assert_eq!(sets[0]["wasted"], 7);
+    assert_eq!(sets[0]["files"].as_array().unwrap().len(), 3);
+
+    let stdout = duplicates(&[], temp.path());
+    assert!(stdout.starts_with("2 copies of 7 B, 7 B wasted, 1 hard link\n"), "{stdout}");
+}
@@ -0,0 +1,77 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_stdout;
+use kgls::meta::MountTable;
+use std::path::Path;
+
+const MOUNTINFO: &str = "\
+28 1 254:0 / / rw,relatime shared:1 - ext4 /dev/vda rw
+23 28 0:22 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw
+61 28 0:48 / /mnt/usb\\040stick rw,relatime shared:30 master:2 - vfat /dev/sdb1 rw
+62 28 0:49 / /srv/share rw,relatime - nfs4 server:/export rw,vers=4.2
+garbage
+";
+
+/// Whether `/proc` is mounted, for the tests walking across it
+fn has_proc_mount() -> bool {
+    MountTable::current().is_mount_point(Path::new("/proc"))
+}
+
+#[test]
+fn test_parse_mountinfo() {
+    let table = MountTable::parse(MOUNTINFO);
+    let mounts = table.mounts();
+    assert_eq!(mounts.len(), 4);
+    assert_eq!(mounts[0].device, (254, 0));
+    assert_eq!(mounts[1].fs_type, "proc");
+    // Optional fields are skipped and escaped spaces restored
+    assert_eq!(mounts[2].mount_point, Path::new("/mnt/usb stick"));
+    assert_eq!(mounts[2].fs_type, "vfat");
+    assert_eq!(mounts[3].fs_type, "nfs4");
+
+    assert!(table.is_mount_point(Path::new("/srv/share")));
+    assert!(!table.is_mount_point(Path::new("/srv")));
+}
+
+#[test]
+fn test_fs_block() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("file").write_str("x").unwrap();
+    let file = temp.child("file");
+    let canonical = std::fs::canonicalize(file.path()).unwrap();
+    let metadata = std::fs::metadata(file.path()).unwrap();
+    let Some(expected) = MountTable::current().fs_type(&metadata, Some(&canonical)) else {
+        return;
+    };
+
+    let stdout = kgls_stdout(&["--blocks", "fs,name"], temp.path());
+    assert_eq!(stdout.split_whitespace().collect::<Vec<_>>(), [expected, "file"]);
+}
+
+#[test]
+fn test_mount_point_indicator() {
+    if !has_proc_mount() {
+        return;
+    }
+    let stdout = kgls_stdout(&["-1", "--classify"], Path::new("/"));
+    let proc_line = stdout.lines().find(|line| line.starts_with("proc")).expect("proc listed");
+    assert!(proc_line.contains('󰋊'), "no mount point indicator: {proc_line:?}");
+    let etc_line = stdout.lines().find(|line| line.starts_with("etc")).expect("etc listed");
+    assert!(!etc_line.contains('󰋊'), "regular directory marked: {etc_line:?}");
+}
+
+#[test]
+fn test_one_file_system_stops_at_mount_points() {
+    if !has_proc_mount() {
+        return;
+    }
+    let crossing = kgls_stdout(&["--tree", "--depth", "2"], Path::new("/"));
+    assert!(crossing.contains("cpuinfo"));
+
+    let staying = kgls_stdout(&["--tree", "--depth", "2", "-x"], Path::new("/"));
+    assert!(staying.lines().any(|line| line.ends_with("proc")), "mount point not listed");
+    assert!(!staying.contains("cpuinfo"));
+}
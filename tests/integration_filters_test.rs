@@ -0,0 +1,113 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_lines;
+use std::time::{Duration, SystemTime};
+
+/// A file deep in the tree, a small one next to it, and an unrelated directory.
+fn listing(temp: &assert_fs::TempDir) -> assert_fs::fixture::ChildPath {
+    common::listing(
+        temp,
+        &[("outer/inner/large.dat", &"x".repeat(10_000)), ("outer/small.dat", "x"), ("other/tiny.dat", "x")],
+    )
+}
+
+/// Tree mode keeps the directories leading to a match, and drops unrelated ones.
+#[test]
+fn test_tree_keeps_ancestors_of_matches() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    let lines = kgls_lines(&["--tree", "--min-size", "8K", "--type", "f"], root.path());
+    assert_eq!(
+        lines,
+        [
+            "listing",
+            "\u{2514}\u{2500}\u{2500} outer",
+            "\u{2514}\u{2500}\u{2500} inner",
+            "\u{2514}\u{2500}\u{2500} large.dat",
+        ]
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `--type` selects entries by kind.
+#[test]
+fn test_type_filter() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    assert_eq!(kgls_lines(&["-1", "--type", "d"], root.path()), ["other", "outer"]);
+    assert_eq!(
+        kgls_lines(&["-1", "--type", "f"], root.child("outer").path()),
+        ["small.dat"]
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `--max-size` is inclusive and applies to every listed entry.
+#[test]
+fn test_max_size_filter() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    assert_eq!(
+        kgls_lines(&["-1", "--max-size", "1", "--type", "f"], root.child("outer").path()),
+        ["small.dat"]
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `--newer` and `--older` take a duration or a reference file.
+#[test]
+fn test_time_filters() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("old.txt").write_str("x").expect("write file");
+    root.child("new.txt").write_str("x").expect("write file");
+    let old = SystemTime::now() - Duration::from_secs(10 * 86_400);
+    std::fs::File::options()
+        .write(true)
+        .open(root.child("old.txt").path())
+        .and_then(|file| file.set_modified(old))
+        .expect("set modification time");
+
+    assert_eq!(kgls_lines(&["-1", "--newer", "1d"], root.path()), ["new.txt"]);
+    assert_eq!(kgls_lines(&["-1", "--older", "1w"], root.path()), ["old.txt"]);
+
+    let reference = root.child("old.txt");
+    let reference = reference.path().to_str().expect("utf-8 path");
+    assert_eq!(kgls_lines(&["-1", "--newer", reference], root.path()), ["new.txt"]);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `--user` accepts a numeric id.
+#[cfg(unix)]
+#[test]
+fn test_user_filter() {
+    use std::os::unix::fs::MetadataExt;
+
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("mine.txt").write_str("x").expect("write file");
+    let uid = root.child("mine.txt").path().metadata().expect("stat file").uid();
+
+    assert_eq!(kgls_lines(&["-1", "--user", &uid.to_string()], root.path()), ["mine.txt"]);
+    assert!(kgls_lines(&["-1", "--user", &(uid + 1).to_string()], root.path()).is_empty());
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Invalid filters are reported before listing anything.
+#[test]
+fn test_invalid_filter_is_an_error() {
+    assert_cmd::cargo::cargo_bin_cmd!("kgls")
+        .args(["--min-size", "lots"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("invalid size 'lots'"));
+}
@@ -0,0 +1,143 @@
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+/// Initialize a git repository with one committed file.
+///
+/// Returns `false` when the `git` binary is not available, so the tests can be skipped.
+fn init_repo(temp: &assert_fs::TempDir) -> bool {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(["-c", "user.name=kgls", "-c", "user.email=kgls@example.com"])
+            .args(args)
+            .current_dir(temp.path())
+            .output()
+            .map(|out| out.status.success())
+            .unwrap_or(false)
+    };
+
+    temp.child("tracked.txt").write_str("original").expect("write tracked file");
+
+    git(&["init", "-q"]) && git(&["add", "tracked.txt"]) && git(&["commit", "-q", "-m", "init"])
+}
+
+/// Untracked and modified files are reported through the `git` block by the streaming Core.
+#[test]
+fn test_git_block_shows_workdir_status() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    if !init_repo(&temp) {
+        eprintln!("git not available, skipping");
+        return;
+    }
+
+    temp.child("tracked.txt").write_str("changed").expect("modify tracked file");
+    temp.child("untracked.txt").write_str("new").expect("write untracked file");
+    temp.child("sub/nested.txt").write_str("new").expect("write nested file");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("kgls");
+    cmd.args(["--blocks", "git,name", "--color", "never", "--icon", "never"])
+        .arg(temp.path())
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(r"│M│\s+tracked\.txt")
+                .unwrap()
+                .and(predicate::str::is_match(r"│\?│\s+untracked\.txt").unwrap())
+                // Directories aggregate the status of their content
+                .and(predicate::str::is_match(r"│\?│\s+sub").unwrap()),
+        );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Git sorting orders entries by their status instead of leaving everything equal.
+#[test]
+fn test_gitsort_orders_by_status() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    if !init_repo(&temp) {
+        eprintln!("git not available, skipping");
+        return;
+    }
+
+    temp.child("a_untracked.txt").write_str("new").expect("write untracked file");
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("kgls")
+        .args(["-1", "--gitsort", "--color", "never", "--icon", "never"])
+        .arg(temp.path())
+        .output()
+        .expect("run kgls");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let lines: Vec<&str> = stdout.lines().map(str::trim).collect();
+    let tracked = lines.iter().position(|l| *l == "tracked.txt").expect("tracked file listed");
+    let untracked = lines.iter().position(|l| *l == "a_untracked.txt").expect("untracked file listed");
+    assert!(
+        tracked < untracked,
+        "clean entries should sort before untracked ones:\n{stdout}"
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Directories outside of any repository render no git status.
+#[test]
+fn test_git_block_empty_outside_repository() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    temp.child("plain.txt").write_str("content").expect("write file");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("kgls");
+    cmd.args(["--blocks", "git,name", "--color", "never", "--icon", "never"])
+        .arg(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("│").not());
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// A file given as input is looked up in the repository of its directory.
+#[test]
+fn test_git_block_of_a_file_input() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    if !init_repo(&temp) {
+        eprintln!("git not available, skipping");
+        return;
+    }
+    temp.child("tracked.txt").write_str("changed").expect("modify tracked file");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("kgls");
+    cmd.args(["--blocks", "git,name", "--color", "never", "--icon", "never", "tracked.txt"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"│M│\s+tracked\.txt").unwrap());
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// JSON records carry the git status whether or not the git block is shown.
+#[test]
+fn test_json_git_status_does_not_need_the_git_block() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    if !init_repo(&temp) {
+        eprintln!("git not available, skipping");
+        return;
+    }
+    temp.child("tracked.txt").write_str("changed").expect("modify tracked file");
+
+    let git_status = |args: &[&str]| {
+        let output = assert_cmd::cargo::cargo_bin_cmd!("kgls")
+            .args(["--output", "json"])
+            .args(args)
+            .arg(temp.child("tracked.txt").path())
+            .output()
+            .expect("run kgls");
+        let entries: serde_json::Value = serde_json::from_slice(&output.stdout).expect("json output");
+        entries[0]["git_status"].clone()
+    };
+
+    assert!(!git_status(&[]).is_null());
+    assert_eq!(git_status(&[]), git_status(&["--blocks", "git,name"]));
+
+    temp.close().expect("cleanup temp dir");
+}
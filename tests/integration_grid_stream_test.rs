@@ -0,0 +1,175 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::{kgls_cmd, kgls_lines, kgls_stdout, non_blank_lines, stdout};
+
+/// Listing a directory shows its content, not the directory itself.
+#[test]
+fn test_directory_input_lists_content_only() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    temp.child("listing/b.txt").write_str("b").expect("write file");
+    temp.child("listing/a.txt").write_str("a").expect("write file");
+
+    let lines = kgls_lines(&["-1"], temp.child("listing").path());
+    assert_eq!(lines, ["a.txt", "b.txt"]);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Inputs are always listed, even when their name is hidden.
+#[test]
+fn test_hidden_directory_input_is_listed() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    temp.child(".hidden/visible.txt").write_str("x").expect("write file");
+    temp.child(".hidden/.secret").write_str("x").expect("write file");
+
+    let lines = kgls_lines(&["-1"], temp.child(".hidden").path());
+    assert_eq!(lines, ["visible.txt"]);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// With `-R` each directory is sorted on its own instead of in one flattened list.
+#[test]
+fn test_recursive_listing_sorts_per_directory() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("z_top.txt").write_str("x").expect("write file");
+    root.child("sub/a_nested.txt").write_str("x").expect("write file");
+    root.child("sub/b_nested.txt").write_str("x").expect("write file");
+
+    let lines = kgls_lines(&["-1", "-R"], root.path());
+    let position = |name: &str| {
+        lines
+            .iter()
+            .position(|line| line == name)
+            .unwrap_or_else(|| panic!("{name} missing from {lines:?}"))
+    };
+
+    // Nested entries stay together and sorted, and never interleave with their parent's
+    assert_eq!(position("b_nested.txt"), position("a_nested.txt") + 1);
+    assert_eq!(position("z_top.txt"), position("sub") + 1);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `-d` lists the inputs themselves.
+#[test]
+fn test_directory_only_lists_inputs() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    temp.child("listing/inner/deep.txt").write_str("x").expect("write file");
+    temp.child("file.txt").write_str("x").expect("write file");
+
+    let lines = non_blank_lines(&stdout(
+        kgls_cmd().args(["-1", "-d"]).args([temp.child("listing").path(), temp.child("file.txt").path()]),
+    ));
+    assert_eq!(lines.len(), 2, "{lines:?}");
+    assert!(lines.iter().any(|line| line.ends_with("listing")));
+    assert!(lines.iter().any(|line| line.ends_with("file.txt")));
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `-R` prints a `path:` header per directory, parents before their subdirectories.
+#[test]
+fn test_recursive_listing_prints_sections_in_order() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("b_dir/inner.txt").write_str("x").expect("write file");
+    root.child("a_dir/deep/file.txt").write_str("x").expect("write file");
+
+    let stdout = kgls_stdout(&["-1", "-R"], root.path());
+
+    let root = root.path().display();
+    let expected = format!(
+        "{root}:\na_dir\nb_dir\n\n{root}/a_dir:\ndeep\n\n{root}/a_dir/deep:\nfile.txt\n\n{root}/b_dir:\ninner.txt\n"
+    );
+    assert_eq!(stdout, expected);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// File inputs come first, then each directory input under its own header.
+#[test]
+fn test_multiple_inputs_list_files_first() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    temp.child("dir/content.txt").write_str("x").expect("write file");
+    temp.child("file.txt").write_str("x").expect("write file");
+
+    let lines = non_blank_lines(&stdout(
+        kgls_cmd().arg("-1").args([temp.child("dir").path(), temp.child("file.txt").path()]),
+    ));
+    let dir_header = format!("{}:", temp.child("dir").path().display());
+    assert_eq!(lines.len(), 3, "{lines:?}");
+    assert!(lines[0].ends_with("file.txt"));
+    assert_eq!(lines[1], dir_header);
+    assert_eq!(lines[2], "content.txt");
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `--summary` follows each listing with its counts and size.
+#[test]
+fn test_summary_line_after_listing() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("a.txt").write_str("12345").expect("write file");
+    root.child("b.txt").write_str("12345").expect("write file");
+
+    let lines = kgls_lines(&["-1", "--summary", "--size", "bytes"], root.path());
+    assert_eq!(lines, ["a.txt", "b.txt", "2 files, 0 directories, 10"]);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// A listing is emitted once its last child is seen, with the sections of the subdirectories
+/// walked so far, without waiting for the rest of the walk.
+#[test]
+fn test_recursive_sections_are_emitted_before_the_walk_ends() {
+    use futures::{FutureExt, StreamExt};
+    use kgls::display::RowRenderer;
+    use kgls::flags::color::ThemeOption;
+    use kgls::flags::{IconOption, IconTheme};
+    use kgls::stream::{GridAccumulator, IgnoreSources, OutputEvent};
+    use kgls::{Cli, Colors, Config, FileStream, Flags, GitTheme, Icons};
+    use std::sync::Arc;
+
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("a_dir/file.txt").write_str("x").expect("write file");
+    root.child("b_dir/deep/file.txt").write_str("x").expect("write file");
+
+    let cli: Cli = clap::Parser::try_parse_from(["kgls", "-1", "-R"]).expect("parse arguments");
+    let flags = Flags::configure_from(&cli, &Config::with_none()).expect("configure flags");
+    let walk = FileStream::new(
+        vec![root.path().to_path_buf()],
+        flags.recursion.depth,
+        &flags.ignore_globs,
+        IgnoreSources::from_flags(&flags),
+        flags.display,
+        false,
+    );
+    // The walk stops after b_dir/deep, as if its content were still being read
+    let source = walk.take(5).chain(futures::stream::pending());
+    let renderer = RowRenderer::new(
+        flags.clone(),
+        Arc::new(Colors::new(ThemeOption::NoColor)),
+        Arc::new(Icons::new(false, IconOption::Never, IconTheme::Fancy, " ".to_string())),
+        Arc::new(GitTheme::new()),
+    );
+    let mut events = GridAccumulator::new(source, renderer, kgls::sort::assemble_sorters(&flags), flags.recursion.depth);
+
+    let mut headers = Vec::new();
+    while let Some(Some(Ok(event))) = events.next().now_or_never() {
+        if let OutputEvent::DirectoryHeader { path, .. } = event {
+            headers.push(path);
+        }
+    }
+    // deep is the last child of b_dir, so its listing is complete too
+    assert_eq!(
+        headers,
+        [root.to_path_buf(), root.child("a_dir").to_path_buf(), root.child("b_dir").to_path_buf()]
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::{kgls_cmd, stdout};
+use serde_json::Value;
+
+/// BLAKE3 of `abc`, from the reference test vectors
+const BLAKE3_ABC: &str = "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85";
+
+/// The output of kgls keeping its digests in `cache_home`
+fn kgls_stdout(args: &[&str], path: &std::path::Path, cache_home: &std::path::Path) -> String {
+    stdout(kgls_cmd().env("XDG_CACHE_HOME", cache_home).args(args).arg(path))
+}
+
+/// The shown digest of every listed name
+fn digests(stdout: &str) -> Vec<(String, String)> {
+    stdout
+        .lines()
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            (fields[1].to_string(), fields[0].to_string())
+        })
+        .collect()
+}
+
+fn tree() -> assert_fs::TempDir {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("release/app.conf").write_str("abc").unwrap();
+    temp.child("release/copy.conf").write_str("abc").unwrap();
+    temp.child("release/other.conf").write_str("abd").unwrap();
+    temp.child("release/sub").create_dir_all().unwrap();
+    temp
+}
+
+#[test]
+fn test_hash_block() {
+    let temp = tree();
+    let root = temp.child("release");
+    let shown = digests(&kgls_stdout(&["--blocks", "hash,name"], root.path(), temp.path()));
+
+    assert_eq!(shown[0].0, "app.conf");
+    assert_eq!(shown[0].1.len(), 16);
+    assert_eq!(shown[0].1, shown[1].1);
+    assert_ne!(shown[0].1, shown[2].1);
+    assert_eq!(shown[3], ("sub".to_string(), "-".to_string()));
+}
+
+#[test]
+fn test_hash_algorithms_differ() {
+    let temp = tree();
+    let root = temp.child("release");
+    let xxh3 = digests(&kgls_stdout(&["--blocks", "hash,name"], root.path(), temp.path()));
+    let blake3 = digests(&kgls_stdout(&["--blocks", "hash,name", "--hash", "blake3"], root.path(), temp.path()));
+    assert_eq!(blake3[0].1, BLAKE3_ABC[..16]);
+    assert_ne!(xxh3[0].1, blake3[0].1);
+}
+
+#[test]
+fn test_json_has_the_full_digest() {
+    let temp = tree();
+    let file = temp.child("release/app.conf");
+    let stdout = kgls_stdout(&["--blocks", "name,hash", "--hash", "blake3", "--output", "json"], file.path(), temp.path());
+    let entries: Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(entries[0]["hash"], BLAKE3_ABC);
+
+    // Without the block, nothing is hashed
+    let stdout = kgls_stdout(&["--output", "json"], file.path(), temp.path());
+    let entries: Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert!(entries[0].get("hash").is_none());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_hash_cache_reuses_unchanged_files() {
+    let temp = tree();
+    let file = temp.child("release/app.conf");
+    let args = ["--blocks", "hash,name", "--hash", "blake3", "--hash-cache"];
+    kgls_stdout(&args, file.path(), temp.path());
+
+    let cache = temp.path().join("kgls").join("hashes-blake3");
+    let saved = std::fs::read_to_string(&cache).expect("cache written");
+    assert!(saved.contains(BLAKE3_ABC));
+
+    // A digest from the cache is trusted while the file is unchanged
+    let planted = "f".repeat(64);
+    std::fs::write(&cache, saved.replace(BLAKE3_ABC, &planted)).unwrap();
+    let stdout = kgls_stdout(&args, file.path(), temp.path());
+    assert!(stdout.starts_with(&planted[..16]), "cache not used: {stdout}");
+
+    // A new size gives a new key, so the file is hashed again
+    file.write_str("abcd").unwrap();
+    let stdout = kgls_stdout(&args, file.path(), temp.path());
+    assert!(!stdout.starts_with(&planted[..16]), "stale digest reused: {stdout}");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_hash_cache_drops_entries_unused_for_long() {
+    let temp = tree();
+    let cache = temp.path().join("kgls").join("hashes-blake3");
+    std::fs::create_dir_all(cache.parent().unwrap()).unwrap();
+    // Last used on the first day of 1970, for a file long gone
+    let forgotten = format!("1 2 3 4 0 {}", "e".repeat(64));
+    std::fs::write(&cache, format!("{forgotten}\n")).unwrap();
+
+    let args = ["--blocks", "hash,name", "--hash", "blake3", "--hash-cache"];
+    kgls_stdout(&args, temp.child("release/app.conf").path(), temp.path());
+
+    let saved = std::fs::read_to_string(&cache).expect("cache written");
+    assert!(saved.contains(BLAKE3_ABC), "{saved}");
+    assert!(!saved.contains(&forgotten), "{saved}");
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_lines;
+
+fn names(lines: &[String]) -> Vec<&str> {
+    lines
+        .iter()
+        .map(|line| line.rsplit(' ').next().unwrap_or(line))
+        .filter(|name| !name.ends_with(':'))
+        .collect()
+}
+
+/// A small repository with nested `.gitignore` files.
+fn repository(temp: &assert_fs::TempDir) -> assert_fs::fixture::ChildPath {
+    let root = temp.child("repo");
+    root.child(".git").create_dir_all().expect("create .git");
+    root.child(".gitignore")
+        .write_str("generated/\n*.trace\n!keep.trace\n/release\n")
+        .expect("write .gitignore");
+    root.child("generated/debug/kgls").write_str("x").expect("write file");
+    root.child("main.rs").write_str("x").expect("write file");
+    root.child("debug.trace").write_str("x").expect("write file");
+    root.child("keep.trace").write_str("x").expect("write file");
+    root.child("release").write_str("x").expect("write file");
+    root.child("src/release").write_str("x").expect("write file");
+    root.child("src/.gitignore").write_str("!wanted.trace\n").expect("write nested .gitignore");
+    root.child("src/wanted.trace").write_str("x").expect("write file");
+    root.child("src/other.trace").write_str("x").expect("write file");
+    root
+}
+
+/// Without the flag `.gitignore` files are just files.
+#[test]
+fn test_ignore_files_are_not_honored_by_default() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = repository(&temp);
+
+    let lines = kgls_lines(&["-1"], root.path());
+    assert!(lines.contains(&"generated".to_string()), "{lines:?}");
+    assert!(lines.contains(&"debug.trace".to_string()), "{lines:?}");
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `--git-ignore` follows negation, anchoring, directory-only patterns and nested files.
+#[test]
+fn test_git_ignore_semantics() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = repository(&temp);
+
+    let lines = kgls_lines(&["-1", "-R", "--git-ignore"], root.path());
+    let mut listed = names(&lines);
+    listed.sort_unstable();
+    assert_eq!(
+        listed,
+        ["keep.trace", "main.rs", "release", "src", "wanted.trace"],
+        "{lines:?}"
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Ignored directories are pruned before descent in tree mode too.
+#[test]
+fn test_git_ignore_prunes_tree() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = repository(&temp);
+
+    let lines = kgls_lines(&["-1", "--tree", "--git-ignore"], root.path());
+    assert!(lines.iter().all(|line| !line.contains("generated") && !line.contains("kgls")), "{lines:?}");
+    assert!(lines.iter().any(|line| line.ends_with("wanted.trace")), "{lines:?}");
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// A subdirectory of a repository still honors the `.gitignore` files above it.
+#[test]
+fn test_git_ignore_applies_parent_files() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = repository(&temp);
+
+    let lines = kgls_lines(&["-1", "--git-ignore"], root.child("src").path());
+    assert_eq!(names(&lines), ["release", "wanted.trace"]);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `.ignore` and `.kglsignore` need `--ignore-files`, and win over `.gitignore`.
+#[test]
+fn test_ignore_files_take_precedence() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child(".gitignore").write_str("*.tmp\n").expect("write .gitignore");
+    root.child(".ignore").write_str("notes.txt\n").expect("write .ignore");
+    root.child(".kglsignore").write_str("!wanted.tmp\n").expect("write .kglsignore");
+    root.child("notes.txt").write_str("x").expect("write file");
+    root.child("scratch.tmp").write_str("x").expect("write file");
+    root.child("wanted.tmp").write_str("x").expect("write file");
+
+    assert_eq!(
+        names(&kgls_lines(&["-1", "--ignore-files"], root.path())),
+        ["scratch.tmp", "wanted.tmp"]
+    );
+    assert_eq!(
+        names(&kgls_lines(&["-1", "--ignore-files", "--git-ignore"], root.path())),
+        ["wanted.tmp"]
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
@@ -0,0 +1,124 @@
+use assert_fs::prelude::*;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::{Path, PathBuf};
+
+use kgls::flags::{PermissionFlag, TimeField};
+use kgls::interactive::{Action, Browser};
+use kgls::meta::Meta;
+
+fn meta(path: &Path) -> Meta {
+    Meta::from_path(path, false, PermissionFlag::Rwx, TimeField::Modified).unwrap()
+}
+
+/// The entries below `root` down to `depth`, as a walk of the browser yields them
+fn walk(root: &Path, depth: usize) -> Vec<(usize, Meta)> {
+    let mut entries = vec![(0, meta(root))];
+    if depth > 0 && root.is_dir() {
+        for child in std::fs::read_dir(root).unwrap() {
+            let child = child.unwrap().path();
+            entries.extend(walk(&child, depth - 1).into_iter().map(|(d, meta)| (d + 1, meta)));
+        }
+    }
+    entries
+}
+
+fn browser(root: &Path, depth: usize) -> Browser {
+    let mut browser = Browser::default();
+    browser.attach(walk(root, depth), depth);
+    browser.sort_by(|a, b| a.path.cmp(&b.path));
+    browser
+}
+
+fn press(browser: &mut Browser, keys: &str) -> Action {
+    let mut action = Action::Redraw;
+    for c in keys.chars() {
+        let code = match c {
+            '\n' => KeyCode::Enter,
+            '\x1b' => KeyCode::Esc,
+            c => KeyCode::Char(c),
+        };
+        action = browser.handle(KeyEvent::new(code, KeyModifiers::NONE), 10);
+    }
+    action
+}
+
+/// The paths on screen, top to bottom
+fn shown(browser: &mut Browser) -> Vec<PathBuf> {
+    fn flatten(metas: &[Meta], paths: &mut Vec<PathBuf>) {
+        for meta in metas {
+            paths.push(meta.path.clone());
+            flatten(meta.content.as_deref().unwrap_or_default(), paths);
+        }
+    }
+    let mut paths = Vec::new();
+    flatten(&browser.view(10).metas, &mut paths);
+    paths
+}
+
+fn project() -> assert_fs::TempDir {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.rs").write_str("fn main() {}\n").unwrap();
+    temp.child("src/deep/inner.rs").write_str("\n").unwrap();
+    temp.child("README.md").write_str("readme\n").unwrap();
+    temp
+}
+
+#[test]
+fn test_expanding_asks_for_unread_directories() {
+    let temp = project();
+    let mut browser = browser(temp.path(), 1);
+    let src = temp.path().join("src");
+    assert_eq!(shown(&mut browser), vec![temp.path().to_path_buf(), temp.path().join("README.md"), src.clone()]);
+
+    assert_eq!(press(&mut browser, "jjl"), Action::Load(src.clone()));
+    browser.attach(walk(&src, 1), 1);
+    browser.sort_by(|a, b| a.path.cmp(&b.path));
+    assert_eq!(shown(&mut browser)[3..], [src.join("deep"), src.join("main.rs")]);
+
+    // Read once, the directory folds and unfolds without another walk
+    assert_eq!(press(&mut browser, "h"), Action::Redraw);
+    assert_eq!(shown(&mut browser).len(), 3);
+    assert_eq!(press(&mut browser, "l"), Action::Redraw);
+    assert_eq!(shown(&mut browser).len(), 5);
+}
+
+#[test]
+fn test_filter_shows_matches_and_their_parents() {
+    let temp = project();
+    let mut browser = browser(temp.path(), 3);
+    press(&mut browser, "/INNER\n");
+    let src = temp.path().join("src");
+    assert_eq!(
+        shown(&mut browser),
+        vec![temp.path().to_path_buf(), src.clone(), src.join("deep"), src.join("deep/inner.rs")]
+    );
+    // The cursor jumps to the match
+    assert_eq!(press(&mut browser, "\n"), Action::Accept(vec![src.join("deep/inner.rs")]));
+
+    press(&mut browser, "\x1b");
+    assert_eq!(shown(&mut browser).len(), 3);
+}
+
+#[test]
+fn test_marked_paths_are_chosen() {
+    let temp = project();
+    let mut browser = browser(temp.path(), 1);
+    assert_eq!(press(&mut browser, "j "), Action::Redraw);
+    assert_eq!(
+        press(&mut browser, " \n"),
+        Action::Accept(vec![temp.path().join("README.md"), temp.path().join("src")])
+    );
+    assert_eq!(press(&mut browser, "q"), Action::Cancel);
+}
+
+#[test]
+fn test_interactive_needs_a_terminal() {
+    let temp = project();
+    assert_cmd::cargo::cargo_bin_cmd!("kgls")
+        .arg("--interactive")
+        .arg(temp.path())
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(predicates::str::contains("needs a terminal"));
+}
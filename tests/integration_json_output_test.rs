@@ -0,0 +1,84 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::{kgls_stdout, listing};
+use serde_json::Value;
+
+fn find<'a>(entries: &'a [Value], name: &str) -> &'a Value {
+    entries
+        .iter()
+        .find(|entry| entry["name"] == name)
+        .unwrap_or_else(|| panic!("{name} missing from {entries:?}"))
+}
+
+/// `--output json` prints one array with raw values instead of the text grid.
+#[test]
+fn test_json_output_serializes_meta() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp, &[]);
+    root.child("file.txt").write_str("12345").expect("write file");
+    root.child("dir").create_dir_all().expect("create dir");
+
+    let stdout = kgls_stdout(&["--output", "json"], root.path());
+    let json: Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let entries = json.as_array().expect("top-level array");
+
+    let file = find(entries, "file.txt");
+    assert_eq!(file["type"], "file");
+    assert_eq!(file["size"], 5);
+    assert_eq!(file["path"], root.child("file.txt").path().to_string_lossy().as_ref());
+    assert!(file["modified"].is_string());
+    assert!(file["symlink"].is_null());
+    assert!(file.get("content").is_none());
+
+    assert_eq!(find(entries, "dir")["type"], "directory");
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Tree mode nests children under `content`.
+#[test]
+fn test_json_tree_output_nests_content() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp, &[]);
+    root.child("outer/inner.txt").write_str("x").expect("write nested file");
+
+    let stdout = kgls_stdout(&["--output", "json", "--tree"], root.path());
+    let json: Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let roots = json.as_array().expect("top-level array");
+    assert_eq!(roots.len(), 1);
+
+    let outer = find(roots[0]["content"].as_array().expect("root content"), "outer");
+    let inner = find(outer["content"].as_array().expect("outer content"), "inner.txt");
+    assert_eq!(inner["type"], "file");
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `--output ndjson` prints one object per line, including symlink targets.
+#[cfg(unix)]
+#[test]
+fn test_ndjson_output_one_object_per_line() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp, &[]);
+    root.child("a.txt").write_str("a").expect("write file");
+    root.child("b.txt").write_str("b").expect("write file");
+    std::os::unix::fs::symlink("a.txt", root.child("link").path()).expect("create symlink");
+
+    let stdout = kgls_stdout(&["--output", "ndjson", "--tree"], root.path());
+    let entries: Vec<Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is a JSON object"))
+        .collect();
+
+    // The root itself plus its three children, all flat
+    assert_eq!(entries.len(), 4);
+    assert!(entries.iter().all(|entry| entry.get("content").is_none()));
+
+    let link = find(&entries, "link");
+    assert_eq!(link["type"], "symlink");
+    assert_eq!(link["symlink"]["target"], "a.txt");
+    assert_eq!(link["symlink"]["valid"], true);
+
+    temp.close().expect("cleanup temp dir");
+}
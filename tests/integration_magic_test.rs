@@ -0,0 +1,81 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_stdout;
+use kgls::meta::{ContentClass, Magic};
+
+#[test]
+fn test_sniff_signatures() {
+    let elf = Magic::sniff(b"\x7fELF\x02\x01\x01\0");
+    assert_eq!(elf.mime(), "application/x-executable");
+    assert_eq!(elf.class(), Some(ContentClass::Executable));
+
+    assert_eq!(Magic::sniff(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").mime(), "image/png");
+    assert_eq!(Magic::sniff(b"\x1f\x8b\x08\0").class(), Some(ContentClass::Archive));
+    assert_eq!(Magic::sniff(b"PK\x03\x04\x14\0").extension(), Some("zip"));
+    assert_eq!(Magic::sniff(b"RIFF\0\0\0\0WEBPVP8 ").mime(), "image/webp");
+
+    let mut tar = vec![0u8; 512];
+    tar[257..262].copy_from_slice(b"ustar");
+    assert_eq!(Magic::sniff(&tar).mime(), "application/x-tar");
+
+    // A DOS stub is only a Windows executable with the PE header it points to
+    let mut exe = vec![0u8; 0x84];
+    exe[..2].copy_from_slice(b"MZ");
+    exe[0x3c] = 0x80;
+    exe[0x80..].copy_from_slice(b"PE\0\0");
+    assert_eq!(Magic::sniff(&exe).extension(), Some("exe"));
+    assert_eq!(Magic::sniff(b"MZ: notes on the Mazda engine\n").mime(), "text/plain");
+
+    assert_eq!(Magic::sniff(b"").mime(), "inode/x-empty");
+    assert_eq!(Magic::sniff("plain text, naïve\n".as_bytes()).mime(), "text/plain");
+    assert_eq!(Magic::sniff(b"\0\x01\x02\x03").mime(), "application/octet-stream");
+}
+
+#[test]
+fn test_sniff_shebangs() {
+    let shell = Magic::sniff(b"#!/bin/sh\necho hi\n");
+    assert_eq!(shell.mime(), "text/x-shellscript");
+    assert_eq!(shell.class(), Some(ContentClass::Script));
+
+    let python = Magic::sniff(b"#!/usr/bin/env -S python3.12 -u\nprint()\n");
+    assert_eq!((python.mime(), python.extension()), ("text/x-script.python", Some("py")));
+    assert_eq!(Magic::sniff(b"#!/usr/bin/perl -w\n").extension(), Some("pl"));
+}
+
+#[test]
+fn test_mime_block() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("deploy").write_str("#!/bin/bash\nexit 0\n").unwrap();
+    temp.child("image.dat").write_binary(b"\x89PNG\r\n\x1a\n\0\0").unwrap();
+    temp.child("notes.txt").write_str("hello\n").unwrap();
+    temp.child("sub").create_dir_all().unwrap();
+
+    let stdout = kgls_stdout(&["--color", "never", "--icon", "never", "--blocks", "mime,name"], temp.path());
+    let rows: Vec<Vec<&str>> = stdout.lines().map(|line| line.split_whitespace().collect()).collect();
+    assert_eq!(
+        rows,
+        [
+            ["text/x-shellscript", "deploy"],
+            ["image/png", "image.dat"],
+            ["text/plain", "notes.txt"],
+            ["inode/directory", "sub"],
+        ]
+    );
+}
+
+#[test]
+fn test_extensionless_files_get_the_icon_of_their_content() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("deploy.sh").write_str("#!/bin/sh\nexit 0\n").unwrap();
+    temp.child("deploy").write_str("#!/bin/sh\nexit 0\n").unwrap();
+    temp.child("opaque").write_binary(b"\0\x01\x02").unwrap();
+
+    let stdout = kgls_stdout(&["--color", "never", "--icon", "always", "-1"], temp.path());
+    let icon_of = |name: &str| {
+        let line = stdout.lines().find(|line| line.ends_with(name)).expect("listed");
+        line.trim_end_matches(name).to_string()
+    };
+    assert_eq!(icon_of("deploy"), icon_of("deploy.sh"));
+    assert_ne!(icon_of("opaque"), icon_of("deploy.sh"));
+}
@@ -37,12 +37,14 @@ fn test_orphaned_children_are_handled_with_warnings() {
     // By default, Display::VisibleOnly filters hidden files
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("kgls");
     cmd
+        .env("RUST_LOG", "warn")
         .arg("--tree")
         .arg(temp.path())
         .assert()
         .success()
         .stderr(
-            predicate::str::contains("Warning: Entry")
+            predicate::str::contains("WARN")
+                .and(predicate::str::contains("Entry"))
                 .and(predicate::str::contains("orphaned"))
                 .and(predicate::str::contains(".hidden"))
         )
@@ -74,6 +76,7 @@ fn test_no_orphaned_warnings_for_normal_directory_structure() {
     // Run kgls --tree
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("kgls");
     cmd
+        .env("RUST_LOG", "warn")
         .arg("--tree")
         .arg(temp.path())
         .assert()
@@ -107,6 +110,7 @@ fn test_deeply_nested_orphaned_children() {
     // Run kgls --tree
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("kgls");
     cmd
+        .env("RUST_LOG", "warn")
         .arg("--tree")
         .arg(temp.path())
         .assert()
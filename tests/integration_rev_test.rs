@@ -0,0 +1,179 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::{kgls_stdout_in, lines};
+use std::path::Path;
+use std::process::Command;
+
+fn git(dir: &Path, args: &[&str]) -> bool {
+    Command::new("git")
+        .args(["-c", "user.name=kgls", "-c", "user.email=kgls@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_COMMITTER_DATE", "2023-11-14T22:13:20Z")
+        .env("GIT_AUTHOR_DATE", "2023-11-14T22:13:20Z")
+        .output()
+        .map(|out| out.status.success())
+        .unwrap_or(false)
+}
+
+/// A repository tagged `v1`, whose working tree has changed since.
+///
+/// Returns [None] when the `git` binary is not available, so the tests can be skipped.
+fn released_repo() -> Option<assert_fs::TempDir> {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    temp.child("README.md").write_str("v1\n").unwrap();
+    temp.child("src/main.rs").write_str("fn main() {}\n").unwrap();
+    temp.child("docs/guide.md").write_str("# Guide\n").unwrap();
+    temp.child("build.sh").write_str("#!/bin/sh\n").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(temp.child("build.sh").path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::os::unix::fs::symlink("README.md", temp.child("latest").path()).unwrap();
+    }
+    let dir = temp.path();
+    if !(git(dir, &["init", "-q"]) && git(dir, &["add", "."]) && git(dir, &["commit", "-q", "-m", "v1"])) {
+        return None;
+    }
+    git(dir, &["tag", "v1"]);
+
+    temp.child("README.md").write_str("v2, with more to read\n").unwrap();
+    std::fs::remove_dir_all(temp.child("docs").path()).unwrap();
+    temp.child("src/lib.rs").write_str("\n").unwrap();
+    git(dir, &["add", "src/lib.rs"]);
+    // Not in the index, so not listed as added
+    temp.child("notes.txt").write_str("scratch\n").unwrap();
+    Some(temp)
+}
+
+#[test]
+fn test_lists_the_tree_of_a_tag() {
+    let Some(temp) = released_repo() else {
+        eprintln!("git not available, skipping");
+        return;
+    };
+    let stdout = kgls_stdout_in(temp.path(), &["--rev", "v1", "-1"]);
+    // The docs removed since are there, the untracked notes are not
+    assert_eq!(lines(&stdout), ["build.sh", "docs", "latest ⇒ README.md", "README.md", "src"]);
+
+    let stdout = kgls_stdout_in(temp.path(), &["--rev", "v1", "-1", "docs"]);
+    assert_eq!(lines(&stdout), ["guide.md"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_long_view_shows_modes_and_blob_sizes() {
+    let Some(temp) = released_repo() else {
+        eprintln!("git not available, skipping");
+        return;
+    };
+    let stdout = kgls_stdout_in(temp.path(), &["--rev", "v1", "-l", "--blocks", "permission,size,name", "--size", "bytes"]);
+    let rows: Vec<Vec<&str>> = lines(&stdout).iter().map(|line| line.split_whitespace().collect()).collect();
+    // The permissions follow the type of the entry
+    for (row, permissions, rest) in [
+        (&rows[0], "rwxr-xr-x", &["10", "build.sh"][..]),
+        (&rows[2], "rwxrwxrwx", &["9", "latest", "⇒", "README.md"]),
+        (&rows[3], "rw-r--r--", &["3", "README.md"]),
+    ] {
+        assert!(row[0].ends_with(permissions), "{row:?}");
+        assert_eq!(&row[1..], rest);
+    }
+
+    // Git records no times, so the entries take the one of the commit
+    let stdout = kgls_stdout_in(temp.path(), &["--rev", "v1", "-l", "--blocks", "date,name", "--date", "+%Y"]);
+    assert!(lines(&stdout).iter().all(|line| line.contains("2023")), "{stdout}");
+}
+
+#[test]
+fn test_tree_sorted_by_size() {
+    let Some(temp) = released_repo() else {
+        eprintln!("git not available, skipping");
+        return;
+    };
+    let stdout = kgls_stdout_in(temp.path(), &["--rev", "v1", "--tree", "-S", "src"]);
+    assert_eq!(lines(&stdout), ["src", "└── main.rs"]);
+
+    let stdout = kgls_stdout_in(
+        temp.path(),
+        &["--rev", "v1", "--tree", "-S", "--total-size", "--size", "bytes", "--blocks", "size,name"],
+    );
+    let expected = "\
+43  v1:
+13  ├── src
+13  │   └── main.rs
+10  ├── build.sh
+ 9  ├── latest ⇒ README.md
+ 8  ├── docs
+ 8  │   └── guide.md
+ 3  └── README.md
+";
+    assert_eq!(lines(&stdout), expected.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_rev_diff_marks_changes_against_the_working_tree() {
+    let Some(temp) = released_repo() else {
+        eprintln!("git not available, skipping");
+        return;
+    };
+    let stdout = kgls_stdout_in(temp.path(), &["--rev", "v1", "--tree", "--rev-diff"]);
+    let expected = "\
+│M│  v1:
+     ├── build.sh
+│D│  ├── docs
+│D│  │   └── guide.md
+     ├── latest ⇒ README.md
+│M│  ├── README.md
+│M│  └── src
+│?│      ├── lib.rs
+         └── main.rs
+";
+    assert_eq!(lines(&stdout), expected.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_rev_diff_compares_files_as_git_checks_them_in() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child(".gitattributes").write_str("*.txt text eol=crlf\n").unwrap();
+    temp.child("notes.txt").write_str("one\ntwo\n").unwrap();
+    let dir = temp.path();
+    if !(git(dir, &["init", "-q"]) && git(dir, &["add", "."]) && git(dir, &["commit", "-q", "-m", "v1"])) {
+        eprintln!("git not available, skipping");
+        return;
+    }
+    // Checked out again with the line endings of the attributes, and touched so that its
+    // stat data no longer vouches for it
+    std::fs::remove_file(temp.child("notes.txt").path()).unwrap();
+    assert!(git(dir, &["checkout", "--", "notes.txt"]));
+    assert_eq!(std::fs::read(temp.child("notes.txt").path()).unwrap(), b"one\r\ntwo\r\n");
+    std::fs::File::options()
+        .write(true)
+        .open(temp.child("notes.txt").path())
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+        .unwrap();
+
+    let stdout = kgls_stdout_in(dir, &["--rev", "HEAD", "--tree", "-a", "--rev-diff"]);
+    assert!(stdout.contains("notes.txt"), "{stdout}");
+    assert!(!stdout.contains("│M│"), "{stdout}");
+
+    temp.child("notes.txt").write_str("one\r\ntwo\r\nthree\r\n").unwrap();
+    let stdout = kgls_stdout_in(dir, &["--rev", "HEAD", "--tree", "-a", "--rev-diff"]);
+    assert!(lines(&stdout).iter().any(|line| line.starts_with("│M│") && line.ends_with("notes.txt")), "{stdout}");
+}
+
+#[test]
+fn test_unknown_revision_is_reported() {
+    let Some(temp) = released_repo() else {
+        eprintln!("git not available, skipping");
+        return;
+    };
+    assert_cmd::cargo::cargo_bin_cmd!("kgls")
+        .args(["--rev", "v9"])
+        .current_dir(temp.path())
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicates::str::contains("cannot read '.' at v9"));
+}
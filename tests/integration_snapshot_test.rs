@@ -0,0 +1,136 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::{kgls_cmd, kgls_stdout_in, stdout};
+use serde_json::Value;
+
+fn install() -> assert_fs::TempDir {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("app/bin/tool").write_str("v1\n").unwrap();
+    temp.child("app/etc/app.conf").write_str("port = 80\n").unwrap();
+    temp.child("app/README").write_str("readme\n").unwrap();
+    temp
+}
+
+#[test]
+fn test_snapshot_is_saved_without_listing() {
+    let temp = install();
+    assert_eq!(kgls_stdout_in(temp.path(), &["--snapshot", "before.kgls", "app"]), "");
+
+    let saved = std::fs::read_to_string(temp.child("before.kgls").path()).unwrap();
+    let lines: Vec<Value> = saved.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines[0]["version"], 2);
+    // Paths are kept below the input
+    let tool = lines.iter().find(|record| record["path"] == "bin/tool").expect("recorded");
+    assert_eq!(tool["type"], "file");
+    assert_eq!(tool["size"], 3);
+    assert!(tool.get("hash").is_none());
+}
+
+#[test]
+fn test_changes_since_snapshot() {
+    let temp = install();
+    kgls_stdout_in(temp.path(), &["--snapshot", "before.kgls", "app"]);
+
+    temp.child("app/bin/tool").write_str("v2 build\n").unwrap();
+    std::fs::remove_file(temp.child("app/etc/app.conf").path()).unwrap();
+    temp.child("app/etc/app.conf.d").create_dir_all().unwrap();
+    temp.child("app/share/doc.txt").write_str("new\n").unwrap();
+
+    let stdout = kgls_stdout_in(temp.path(), &["--since-snapshot", "before.kgls", "--summary", "app"]);
+    let expected = "\
+│M│  app
+│M│  ├── bin
+│M│  │   └── tool
+│M│  ├── etc
+│D│  │   ├── app.conf
+│?│  │   └── app.conf.d
+     ├── README
+│?│  └── share
+│?│      └── doc.txt
+3 added, 1 removed, 1 modified, 0 type changed
+";
+    let lines: Vec<&str> = stdout.lines().map(str::trim_end).collect();
+    assert_eq!(lines, expected.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_snapshot_compared_through_another_path() {
+    let temp = install();
+    kgls_stdout_in(temp.path(), &["--snapshot", "before.kgls", "app"]);
+    temp.child("app/README").write_str("readme, longer\n").unwrap();
+
+    // The same tree, from inside it this time
+    let stdout = kgls_stdout_in(temp.child("app").path(), &["--since-snapshot", "../before.kgls", "--tree", "."]);
+    let expected = "\
+│M│  .
+     ├── bin
+     │   └── tool
+     ├── etc
+     │   └── app.conf
+│M│  └── README
+";
+    let lines: Vec<&str> = stdout.lines().map(str::trim_end).collect();
+    assert_eq!(lines, expected.lines().collect::<Vec<_>>());
+
+    let stdout = kgls_stdout_in(temp.path(), &["--since-snapshot", "before.kgls", "--output", "json", "./app/"]);
+    let tree: Value = serde_json::from_str(&stdout).unwrap();
+    let readme = tree[0]["content"].as_array().unwrap().iter().find(|entry| entry["name"] == "README").unwrap();
+    assert_eq!(readme["diff"], "modified");
+    assert!(tree[0]["content"].as_array().unwrap().iter().all(|entry| entry["diff"] != "added"));
+}
+
+#[test]
+fn test_checksum_snapshot_ignores_rewrites() {
+    let temp = install();
+    kgls_stdout_in(temp.path(), &["--snapshot", "before.kgls", "--checksum", "--hash", "blake3", "app"]);
+    let saved = std::fs::read_to_string(temp.child("before.kgls").path()).unwrap();
+    assert!(saved.starts_with("{\"version\":2,\"hash\":\"blake3\"}\n"));
+
+    // Same content written to a new inode
+    std::fs::remove_file(temp.child("app/README").path()).unwrap();
+    temp.child("app/README").write_str("readme\n").unwrap();
+
+    let stdout = kgls_stdout_in(temp.path(), &["--since-snapshot", "before.kgls", "--output", "json", "app"]);
+    let tree: Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(tree[0]["diff"], "unchanged");
+    let readme = tree[0]["content"].as_array().unwrap().iter().find(|entry| entry["name"] == "README").unwrap();
+    assert_eq!(readme["diff"], "unchanged");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_checksum_snapshot_takes_the_digests_of_the_walk() {
+    let temp = install();
+    let snapshot = |temp: &assert_fs::TempDir| {
+        let args = ["--snapshot", "before.kgls", "--checksum", "--hash", "blake3", "--blocks", "hash,name", "--hash-cache", "app"];
+        stdout(kgls_cmd().current_dir(temp.path()).env("XDG_CACHE_HOME", temp.path()).args(args));
+        let saved = std::fs::read_to_string(temp.child("before.kgls").path()).unwrap();
+        let tool = saved
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .find(|record| record["path"] == "bin/tool")
+            .expect("recorded");
+        tool["hash"].as_str().expect("hashed").to_string()
+    };
+    let hashed = snapshot(&temp);
+
+    // A digest planted in the cache shows the file was not read again
+    let cache = temp.path().join("kgls").join("hashes-blake3");
+    let planted = "f".repeat(64);
+    std::fs::write(&cache, std::fs::read_to_string(&cache).unwrap().replace(&hashed, &planted)).unwrap();
+    assert_eq!(snapshot(&temp), planted);
+}
+
+#[test]
+fn test_unreadable_snapshot() {
+    let temp = install();
+    temp.child("bogus.kgls").write_str("not a snapshot\n").unwrap();
+    let output = kgls_cmd()
+        .current_dir(temp.path())
+        .args(["--since-snapshot", "bogus.kgls", "app"])
+        .output()
+        .expect("run kgls");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot read snapshot"));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::kgls_lines;
+
+/// Files of two extensions, two of them with the same size.
+fn listing(temp: &assert_fs::TempDir) -> assert_fs::fixture::ChildPath {
+    common::listing(
+        temp,
+        &[
+            ("b.txt", "xx"),
+            ("a.txt", "xx"),
+            ("c.txt", &"x".repeat(100)),
+            ("d.md", "x"),
+            ("e.md", &"x".repeat(50)),
+        ],
+    )
+}
+
+/// Each key of the chain breaks the ties of the previous ones.
+#[test]
+fn test_sort_chain() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    assert_eq!(
+        kgls_lines(&["-1", "--sort", "ext,size:desc,name"], root.path()),
+        ["e.md", "d.md", "c.txt", "a.txt", "b.txt"]
+    );
+    assert_eq!(
+        kgls_lines(&["-1", "--sort", "ext:desc,size:asc,name:desc"], root.path()),
+        ["b.txt", "a.txt", "c.txt", "d.md", "e.md"]
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `--reverse` reverses the whole chain.
+#[test]
+fn test_sort_chain_reverse() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    assert_eq!(
+        kgls_lines(&["-1", "--sort", "ext,size,name", "--reverse"], root.path()),
+        ["b.txt", "a.txt", "c.txt", "d.md", "e.md"]
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// An unknown key is rejected.
+#[test]
+fn test_invalid_sort_key_is_an_error() {
+    assert_cmd::cargo::cargo_bin_cmd!("kgls")
+        .args(["--sort", "colour"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown sort key 'colour'"));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_lines;
+use std::time::{Duration, SystemTime};
+
+/// `recent` was modified last but `stale` was accessed last.
+fn listing(temp: &assert_fs::TempDir) -> assert_fs::fixture::ChildPath {
+    let root = common::listing(temp, &[("recent.txt", "x"), ("stale.txt", "x")]);
+
+    let now = SystemTime::now();
+    let times = |modified: u64, accessed: u64| {
+        std::fs::FileTimes::new()
+            .set_modified(now - Duration::from_secs(modified * 86_400))
+            .set_accessed(now - Duration::from_secs(accessed * 86_400))
+    };
+    for (name, times) in [("recent.txt", times(1, 20)), ("stale.txt", times(10, 2))] {
+        std::fs::File::options()
+            .write(true)
+            .open(root.child(name).path())
+            .and_then(|file| file.set_times(times))
+            .expect("set file times");
+    }
+    root
+}
+
+/// The time sort follows the chosen timestamp.
+#[test]
+fn test_time_sort_uses_chosen_field() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    assert_eq!(kgls_lines(&["-1", "-t"], root.path()), ["recent.txt", "stale.txt"]);
+    assert_eq!(kgls_lines(&["-1", "-t", "-u"], root.path()), ["stale.txt", "recent.txt"]);
+    assert_eq!(
+        kgls_lines(&["-1", "-t", "--time", "access"], root.path()),
+        ["stale.txt", "recent.txt"]
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// The date block shows the chosen timestamp under a matching header.
+#[test]
+fn test_date_block_uses_chosen_field() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    let args = ["--header", "--blocks", "date,name", "--date", "+%F"];
+    let lines = kgls_lines(&[&args[..], &["-u"]].concat(), root.path());
+    assert!(lines[0].contains("Date Accessed"), "{lines:?}");
+    let accessed = SystemTime::now() - Duration::from_secs(20 * 86_400);
+    let accessed = chrono::DateTime::<chrono::Local>::from(accessed).format("%F").to_string();
+    assert!(
+        lines.iter().any(|line| line.contains(&accessed) && line.ends_with("recent.txt")),
+        "{lines:?}"
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Several timestamps can be shown side by side.
+#[test]
+fn test_timestamp_blocks() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = listing(&temp);
+
+    let lines = kgls_lines(&["--header", "--blocks", "atime,ctime,btime,name"], root.path());
+    let header = &lines[0];
+    for title in ["Date Accessed", "Date Changed", "Date Created", "Name"] {
+        assert!(header.contains(title), "{header}");
+    }
+    assert_eq!(lines.len(), 3, "{lines:?}");
+
+    temp.close().expect("cleanup temp dir");
+}
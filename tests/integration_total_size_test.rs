@@ -0,0 +1,135 @@
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_stdout;
+
+/// The byte size shown for `name` in a `-l --size bytes` listing.
+fn size_of(args: &[&str], path: &std::path::Path, name: &str) -> u64 {
+    let stdout = kgls_stdout(&[&["--size", "bytes", "--blocks", "size,name"], args].concat(), path);
+    stdout
+        .lines()
+        .map(str::split_whitespace)
+        .find_map(|mut columns| {
+            let size = columns.next()?;
+            let rest: Vec<&str> = columns.collect();
+            (rest.last() == Some(&name)).then(|| size.parse().expect("size in bytes"))
+        })
+        .unwrap_or_else(|| panic!("{name} missing from {stdout}"))
+}
+
+fn dir_len(path: &std::path::Path) -> u64 {
+    path.symlink_metadata().expect("stat directory").len()
+}
+
+/// A directory at the depth limit is measured with a walk of its own.
+#[test]
+fn test_total_size_of_unexpanded_directory() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("sub/a.txt").write_str("12345").expect("write file");
+    root.child("sub/deep/b.txt").write_str("1234567890").expect("write file");
+
+    let sub = root.child("sub");
+    let expected = dir_len(sub.path()) + 5 + dir_len(sub.child("deep").path()) + 10;
+    assert_eq!(size_of(&["-l", "--total-size"], root.path(), "sub"), expected);
+
+    // Without the flag the directory shows its own size
+    assert_eq!(size_of(&["-l"], root.path(), "sub"), dir_len(sub.path()));
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// With `-R` totals are summed bottom-up from the listed entries, and match a full walk.
+#[test]
+fn test_total_size_aggregated_from_recursive_listing() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("sub/a.txt").write_str("12345").expect("write file");
+    root.child("sub/deep/b.txt").write_str("1234567890").expect("write file");
+
+    let walked = size_of(&["-l", "--total-size"], root.path(), "sub");
+    assert_eq!(size_of(&["-l", "-R", "--total-size"], root.path(), "sub"), walked);
+    assert_eq!(
+        size_of(&["-l", "-R", "--depth", "2", "--total-size"], root.path(), "sub"),
+        walked
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Tree mode totals every directory in the tree.
+#[test]
+fn test_total_size_in_tree_mode() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("sub/deep/b.txt").write_str("1234567890").expect("write file");
+
+    let stdout = kgls_stdout(&["--output", "json", "--tree", "--total-size"], root.path());
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let sub = &json[0]["content"][0];
+    let deep = &sub["content"][0];
+
+    let deep_total = dir_len(root.child("sub/deep").path()) + 10;
+    assert_eq!(deep["size"], deep_total);
+    assert_eq!(sub["size"], dir_len(root.child("sub").path()) + deep_total);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// Hidden files only count when they would be listed.
+#[test]
+fn test_total_size_follows_display_mode() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("sub/.hidden").write_str("12345").expect("write file");
+
+    let sub = root.child("sub");
+    assert_eq!(size_of(&["-l", "--total-size"], root.path(), "sub"), dir_len(sub.path()));
+    assert_eq!(
+        size_of(&["-l", "-a", "--total-size"], root.path(), "sub"),
+        dir_len(sub.path()) + 5
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// A file with several hard links in the tree is counted once.
+#[cfg(unix)]
+#[test]
+fn test_total_size_counts_hard_links_once() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    let sub = root.child("sub");
+    sub.child("file").write_str("1234567890").expect("write file");
+    std::fs::hard_link(sub.child("file").path(), sub.child("link").path()).expect("create hard link");
+
+    let expected = dir_len(sub.path()) + 10;
+    assert_eq!(size_of(&["-l", "--total-size"], root.path(), "sub"), expected);
+    assert_eq!(size_of(&["-l", "-R", "--total-size"], root.path(), "sub"), expected);
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// NDJSON writes each directory after its content, with the total of the entries streamed,
+/// and counts a hard link once over the whole listing like du.
+#[cfg(unix)]
+#[test]
+fn test_total_size_in_ndjson_stream() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let root = temp.child("listing");
+    root.child("a/file").write_str("1234567890").expect("write file");
+    root.child("b").create_dir_all().expect("create directory");
+    std::fs::hard_link(root.child("a/file").path(), root.child("b/link").path()).expect("create hard link");
+
+    let stdout = kgls_stdout(&["--output", "ndjson", "-R", "--total-size"], root.path());
+    let entries: Vec<serde_json::Value> = stdout.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    let names: Vec<&str> = entries.iter().map(|entry| entry["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["file", "a", "link", "b", "listing"]);
+
+    let a = dir_len(root.child("a").path()) + 10;
+    let b = dir_len(root.child("b").path());
+    let sizes: Vec<u64> = entries.iter().map(|entry| entry["size"].as_u64().unwrap()).collect();
+    assert_eq!(sizes, [10, a, 10, b, dir_len(root.path()) + a + b]);
+
+    temp.close().expect("cleanup temp dir");
+}
@@ -0,0 +1,130 @@
+use assert_fs::prelude::*;
+use std::io::BufRead;
+use std::process::{Child, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// A running `kgls --watch`, with its frames read as they are printed
+struct Watching {
+    child: Child,
+    frames: mpsc::Receiver<String>,
+}
+
+impl Watching {
+    fn start(args: &[&str], dir: &std::path::Path) -> Self {
+        let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("kgls"))
+            .current_dir(dir)
+            .args(["--watch", "--color", "never", "--icon", "never"])
+            .args(args)
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("run kgls");
+
+        // Without a terminal, frames are printed one after another, each ending with a blank line
+        let stdout = child.stdout.take().unwrap();
+        let (sender, frames) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut frame = String::new();
+            for line in std::io::BufReader::new(stdout).lines().map_while(Result::ok) {
+                if line.is_empty() {
+                    if sender.send(std::mem::take(&mut frame)).is_err() {
+                        break;
+                    }
+                } else {
+                    frame.push_str(&line);
+                    frame.push('\n');
+                }
+            }
+        });
+        Self { child, frames }
+    }
+
+    /// The first frame to come that `matches`, failing after a few seconds
+    fn frame_where(&self, matches: impl Fn(&str) -> bool) -> String {
+        let mut last = String::new();
+        while let Ok(frame) = self.frames.recv_timeout(Duration::from_secs(5)) {
+            if matches(&frame) {
+                return frame;
+            }
+            last = frame;
+        }
+        panic!("no matching frame, last one was:\n{last}");
+    }
+}
+
+impl Drop for Watching {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn line_of<'a>(frame: &'a str, name: &str) -> &'a str {
+    frame.lines().find(|line| line.ends_with(name)).unwrap_or_default()
+}
+
+#[test]
+fn test_watch_marks_created_modified_and_removed() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("logs/app.txt").write_str("start\n").unwrap();
+    temp.child("logs/old.txt").write_str("old\n").unwrap();
+    let watching = Watching::start(&["logs"], temp.path());
+
+    let first = watching.frame_where(|frame| frame.contains("app.txt"));
+    assert!(!line_of(&first, "app.txt").contains('M'), "nothing changed yet: {first}");
+
+    temp.child("logs/new.txt").write_str("new\n").unwrap();
+    let frame = watching.frame_where(|frame| frame.contains("new.txt"));
+    assert!(line_of(&frame, "new.txt").contains("│?│"), "{frame}");
+    assert!(!line_of(&frame, "app.txt").contains("│M│"), "{frame}");
+
+    temp.child("logs/app.txt").write_str("start\nmore\n").unwrap();
+    std::fs::remove_file(temp.child("logs/old.txt").path()).unwrap();
+    let frame = watching.frame_where(|frame| line_of(frame, "old.txt").contains("│D│"));
+    assert!(line_of(&frame, "app.txt").contains("│M│"), "{frame}");
+    // Still highlighted as created from the previous change
+    assert!(line_of(&frame, "new.txt").contains("│?│"), "{frame}");
+}
+
+#[test]
+fn test_watch_highlights_fade() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("logs/app.txt").write_str("start\n").unwrap();
+    let watching = Watching::start(&["logs"], temp.path());
+    watching.frame_where(|frame| frame.contains("app.txt"));
+
+    std::fs::remove_file(temp.child("logs/app.txt").path()).unwrap();
+    watching.frame_where(|frame| line_of(frame, "app.txt").contains("│D│"));
+    // Once the highlight is over, the removed entry is gone from the view
+    let frame = watching.frame_where(|frame| !frame.contains("app.txt"));
+    assert_eq!(frame, "");
+}
+
+#[test]
+fn test_watch_tree() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("logs/2024/jan.txt").write_str("jan\n").unwrap();
+    let watching = Watching::start(&["--tree", "logs"], temp.path());
+    watching.frame_where(|frame| frame.contains("jan.txt"));
+
+    temp.child("logs/2024/feb.txt").write_str("feb\n").unwrap();
+    let frame = watching.frame_where(|frame| frame.contains("feb.txt"));
+    assert!(line_of(&frame, "feb.txt").contains("│?│"), "{frame}");
+    assert!(line_of(&frame, "feb.txt").contains("├── feb.txt"), "{frame}");
+    // The directories above a change are modified
+    assert!(line_of(&frame, "2024").contains("│M│"), "{frame}");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_watch_ends_on_interrupt() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("a.txt").write_str("a\n").unwrap();
+    let mut watching = Watching::start(&["."], temp.path());
+    watching.frame_where(|frame| frame.contains("a.txt"));
+
+    // Handled, rather than ending the process by the signal
+    unsafe { libc::kill(watching.child.id() as libc::pid_t, libc::SIGINT) };
+    let status = watching.child.wait().expect("wait for kgls");
+    assert_eq!(status.code(), Some(0), "{status:?}");
+}
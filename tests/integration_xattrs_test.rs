@@ -0,0 +1,50 @@
+#![cfg(unix)]
+
+mod common;
+
+use assert_fs::prelude::*;
+use common::kgls_lines;
+
+/// A tagged build artifact next to an untagged file, or [None] when the file system has no
+/// user attributes.
+fn listing(temp: &assert_fs::TempDir) -> Option<assert_fs::fixture::ChildPath> {
+    let root = common::listing(temp, &[("artifact", "x"), ("notes.txt", "x")]);
+    let artifact = root.child("artifact");
+    xattr::set(artifact.path(), "user.build", b"release").ok()?;
+    xattr::set(artifact.path(), "user.digest", &[0xde, 0xad, 0xbe, 0xef]).ok()?;
+    Some(root)
+}
+
+/// The xattrs block counts the attributes of each entry.
+#[test]
+fn test_xattrs_block() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let Some(root) = listing(&temp) else {
+        return;
+    };
+
+    assert_eq!(
+        kgls_lines(&["--blocks", "xattrs,name"], root.path()),
+        ["2  artifact", "0  notes.txt"]
+    );
+
+    temp.close().expect("cleanup temp dir");
+}
+
+/// `-@` prints each attribute under its entry, with text or hex values.
+#[test]
+fn test_list_xattrs() {
+    let temp = assert_fs::TempDir::new().expect("create temp dir");
+    let Some(root) = listing(&temp) else {
+        return;
+    };
+
+    let lines = kgls_lines(&["-1@"], root.path());
+    assert_eq!(lines[0], "artifact");
+    let mut details = lines[1..3].to_vec();
+    details.sort();
+    assert_eq!(details, ["user.build   7  \"release\"", "user.digest  4  0xdeadbeef"]);
+    assert_eq!(lines[3..], ["notes.txt"]);
+
+    temp.close().expect("cleanup temp dir");
+}
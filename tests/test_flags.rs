@@ -43,6 +43,72 @@ mod test_truncate_owner;
 #[path = "flags/test_total_size.rs"]
 mod test_total_size;
 
+#[path = "flags/test_output.rs"]
+mod test_output;
+
+#[path = "flags/test_summary.rs"]
+mod test_summary;
+
+#[path = "flags/test_git_ignore.rs"]
+mod test_git_ignore;
+
+#[path = "flags/test_ignore_files.rs"]
+mod test_ignore_files;
+
+#[path = "flags/test_du_threshold.rs"]
+mod test_du_threshold;
+
+#[path = "flags/test_filters.rs"]
+mod test_filters;
+
+#[path = "flags/test_time_field.rs"]
+mod test_time_field;
+
+#[path = "flags/test_allocated_size.rs"]
+mod test_allocated_size;
+
+#[path = "flags/test_block_size.rs"]
+mod test_block_size;
+
+#[path = "flags/test_list_xattrs.rs"]
+mod test_list_xattrs;
+
+#[path = "flags/test_list_acl.rs"]
+mod test_list_acl;
+
+#[path = "flags/test_one_file_system.rs"]
+mod test_one_file_system;
+
+#[path = "flags/test_hash_algorithm.rs"]
+mod test_hash_algorithm;
+
+#[path = "flags/test_reuse_hashes.rs"]
+mod test_reuse_hashes;
+
+#[path = "flags/test_duplicates.rs"]
+mod test_duplicates;
+
+#[path = "flags/test_diff.rs"]
+mod test_diff;
+
+#[path = "flags/test_checksum.rs"]
+mod test_checksum;
+
+#[path = "flags/test_snapshot.rs"]
+mod test_snapshot;
+
+#[path = "flags/test_watch.rs"]
+mod test_watch;
+
+#[path = "flags/test_interactive.rs"]
+mod test_interactive;
+
+#[path = "flags/test_rev.rs"]
+mod test_rev;
+
+#[path = "flags/test_rev_diff.rs"]
+mod test_rev_diff;
+
 // NOTE: The following tests use pub(crate) methods and are in separate integration test files:
 // - test_ignore_globs.rs
 // - test_recursion.rs
@@ -0,0 +1,130 @@
+use kgls::git::{GitCache, GitStatus, GitStatusInfo};
+use kgls::GitFileStatus;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn workdir_status(status: GitStatus) -> GitStatusInfo {
+    GitStatusInfo {
+        index_status: None,
+        workdir_status: Some(status),
+    }
+}
+
+fn lookup(cache: &GitCache, path: &str, is_directory: bool) -> Option<GitFileStatus> {
+    let path = PathBuf::from(path);
+    cache.get(&path, Some(&path), is_directory)
+}
+
+fn sample_cache() -> GitCache {
+    GitCache::from_statuses(
+        PathBuf::from("/repo"),
+        vec![
+            (PathBuf::from("/repo/src/main.rs"), workdir_status(GitStatus::Modified)),
+            (PathBuf::from("/repo/src/deep/new.rs"), workdir_status(GitStatus::NewInWorkdir)),
+            (
+                PathBuf::from("/repo/staged.rs"),
+                GitStatusInfo {
+                    index_status: Some(GitStatus::NewInIndex),
+                    workdir_status: None,
+                },
+            ),
+        ],
+    )
+}
+
+#[test]
+fn test_file_lookup_returns_own_status() {
+    let cache = sample_cache();
+    let status = lookup(&cache, "/repo/src/main.rs", false).unwrap();
+    assert_eq!(status.workdir, GitStatus::Modified);
+    assert_eq!(status.index, GitStatus::Unmodified);
+}
+
+#[test]
+fn test_file_lookup_without_status_is_default() {
+    let cache = sample_cache();
+    assert_eq!(lookup(&cache, "/repo/src/lib.rs", false), Some(GitFileStatus::default()));
+    // Intermediate trie nodes are not files with a status of their own
+    assert_eq!(lookup(&cache, "/repo/src", false), Some(GitFileStatus::default()));
+}
+
+#[test]
+fn test_directory_lookup_aggregates_subtree() {
+    let cache = sample_cache();
+
+    let src = lookup(&cache, "/repo/src", true).unwrap();
+    assert_eq!(src.workdir, GitStatus::Modified);
+    assert_eq!(src.index, GitStatus::Unmodified);
+
+    let root = lookup(&cache, "/repo", true).unwrap();
+    assert_eq!(root.index, GitStatus::NewInIndex);
+    assert_eq!(root.workdir, GitStatus::Modified);
+
+    assert_eq!(lookup(&cache, "/repo/docs", true), Some(GitFileStatus::default()));
+}
+
+#[test]
+fn test_duplicate_paths_keep_both_sides() {
+    let cache = GitCache::from_statuses(
+        PathBuf::from("/repo"),
+        vec![
+            (
+                PathBuf::from("/repo/both.rs"),
+                GitStatusInfo {
+                    index_status: Some(GitStatus::Modified),
+                    workdir_status: Some(GitStatus::Unmodified),
+                },
+            ),
+            (PathBuf::from("/repo/both.rs"), workdir_status(GitStatus::Modified)),
+        ],
+    );
+    let status = lookup(&cache, "/repo/both.rs", false).unwrap();
+    assert_eq!(status.index, GitStatus::Modified);
+    assert_eq!(status.workdir, GitStatus::Modified);
+}
+
+#[test]
+fn test_lookup_outside_workdir_is_none() {
+    let cache = sample_cache();
+    assert_eq!(lookup(&cache, "/elsewhere/main.rs", false), None);
+    assert_eq!(lookup(&GitCache::empty(), "/repo/src/main.rs", false), None);
+}
+
+/// 100k dirty entries spread over 1000 directories: building the cache and looking up every
+/// file and directory must stay far below what a per-lookup scan of all entries would cost.
+///
+/// Timing depends on the machine, so run it on demand with `cargo test -- --ignored`.
+#[test]
+#[ignore = "benchmark"]
+fn test_lookup_benchmark_100k_entries() {
+    const DIRS: usize = 1_000;
+    const FILES_PER_DIR: usize = 100;
+
+    let statuses = (0..DIRS).flat_map(|dir| {
+        (0..FILES_PER_DIR).map(move |file| {
+            (
+                PathBuf::from(format!("/repo/d{}/sub{dir}/f{file}.rs", dir % 10)),
+                workdir_status(GitStatus::NewInWorkdir),
+            )
+        })
+    });
+
+    let start = Instant::now();
+    let cache = GitCache::from_statuses(PathBuf::from("/repo"), statuses);
+    let built = start.elapsed();
+
+    let start = Instant::now();
+    for dir in 0..DIRS {
+        let dir_path = format!("/repo/d{}/sub{dir}", dir % 10);
+        let status = lookup(&cache, &dir_path, true).unwrap();
+        assert_eq!(status.workdir, GitStatus::NewInWorkdir);
+        for file in 0..FILES_PER_DIR {
+            let status = lookup(&cache, &format!("{dir_path}/f{file}.rs"), false).unwrap();
+            assert_eq!(status.workdir, GitStatus::NewInWorkdir);
+        }
+    }
+    let looked_up = start.elapsed();
+
+    assert!(built < Duration::from_secs(10), "building took {built:?}");
+    assert!(looked_up < Duration::from_secs(10), "lookups took {looked_up:?}");
+}